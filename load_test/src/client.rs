@@ -0,0 +1,61 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Method, StatusCode};
+use serde::Serialize;
+use sha2::Sha256;
+
+use crate::config::Credentials;
+
+type HmacSha256=Hmac<Sha256>;
+
+//signs requests the way the server's `authenticate` middleware checks them
+pub struct SignedClient{
+    http: Client,
+    url: String,
+    api_key: String,
+    secret: String,
+    //nonces only have to be unique per key, so each worker prefixes its own counter
+    nonce_prefix: String,
+    nonce: u64
+}
+
+pub struct Reply{
+    pub status: StatusCode,
+    pub body: serde_json::Value,
+    pub latency: Duration
+}
+
+impl SignedClient{
+    pub fn new(http:Client,url:&str,credentials:&Credentials,nonce_prefix:String)->SignedClient{
+        SignedClient { http, url:url.to_string(), api_key:credentials.api_key.clone(), secret:credentials.secret.clone(), nonce_prefix, nonce:0 }
+    }
+
+    pub async fn send(&mut self,method:Method,path:&str,body:&impl Serialize)->Result<Reply,reqwest::Error>{
+        let body=serde_json::to_vec(body).expect("request bodies always serialize");
+        let timestamp=SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis().to_string();
+        self.nonce+=1;
+        let nonce=format!("{}-{}",self.nonce_prefix,self.nonce);
+        let mut mac=HmacSha256::new_from_slice(self.secret.as_bytes()).expect("hmac takes keys of any length");
+        for part in [timestamp.as_bytes(),nonce.as_bytes(),method.as_str().as_bytes(),path.as_bytes(),&body]{
+            mac.update(part);
+        }
+        let signature=hex::encode(mac.finalize().into_bytes());
+        let start=Instant::now();
+        let response=self.http
+            .request(method, format!("{}{}",self.url,path))
+            .header("content-type", "application/json")
+            .header("x-api-key", &self.api_key)
+            .header("x-api-timestamp", timestamp)
+            .header("x-api-nonce", nonce)
+            .header("x-api-signature", signature)
+            .body(body)
+            .send()
+            .await?;
+        let status=response.status();
+        let body=response.json().await.unwrap_or(serde_json::Value::Null);
+        //the whole round trip, body included
+        let latency=start.elapsed();
+        Ok(Reply { status, body, latency })
+    }
+}
\ No newline at end of file
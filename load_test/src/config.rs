@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use order_flow::OrderMix;
+
+pub const USAGE:&str="usage: load_test --api-key <key> --secret <secret> [--url http://localhost:8000] [--concurrency 16] [--duration-secs 10] [--markets 4] [--mix limit,market,cancel,modify] [--seed 42] [--admin-key <key> --admin-secret <secret>]";
+
+pub struct Credentials{
+    pub api_key: String,
+    pub secret: String
+}
+
+pub struct LoadTestConfig{
+    pub url: String,
+    //workers sending requests at the same time, each waits for its response before the next request
+    pub concurrency: usize,
+    pub duration: Duration,
+    pub markets: usize,
+    pub mix: OrderMix,
+    pub seed: u64,
+    pub trader: Credentials,
+    //creates the markets before the run when given, otherwise they have to exist already
+    pub admin: Option<Credentials>
+}
+
+impl LoadTestConfig{
+    pub fn from_args(args:impl Iterator<Item=String>)->Result<LoadTestConfig,String>{
+        let mut url="http://localhost:8000".to_string();
+        let (mut concurrency,mut duration_secs,mut markets,mut seed)=(16,10,4,42);
+        let mut mix=OrderMix::default();
+        let (mut api_key,mut secret,mut admin_key,mut admin_secret)=(None,None,None,None);
+        let mut args=args.skip(1);
+        while let Some(flag)=args.next(){
+            let value=args.next().ok_or(format!("{flag} needs a value"))?;
+            match flag.as_str(){
+                "--url"=>url=value,
+                "--concurrency"=>concurrency=parse(&flag, &value)?,
+                "--duration-secs"=>duration_secs=parse(&flag, &value)?,
+                "--markets"=>markets=parse(&flag, &value)?,
+                "--seed"=>seed=parse(&flag, &value)?,
+                "--mix"=>mix=parse_mix(&value)?,
+                "--api-key"=>api_key=Some(value),
+                "--secret"=>secret=Some(value),
+                "--admin-key"=>admin_key=Some(value),
+                "--admin-secret"=>admin_secret=Some(value),
+                _=>return Err(format!("unknown flag {flag}"))
+            }
+        }
+        let (Some(api_key),Some(secret))=(api_key,secret) else{
+            return Err("--api-key and --secret are required".to_string())
+        };
+        if concurrency==0 || markets==0{
+            return Err("--concurrency and --markets must be at least 1".to_string())
+        }
+        let admin=match (admin_key,admin_secret){
+            (Some(api_key),Some(secret))=>Some(Credentials { api_key, secret }),
+            (None,None)=>None,
+            _=>return Err("--admin-key and --admin-secret go together".to_string())
+        };
+        Ok(LoadTestConfig {
+            url:url.trim_end_matches('/').to_string(),
+            concurrency,
+            duration:Duration::from_secs(duration_secs),
+            markets,
+            mix,
+            seed,
+            trader:Credentials { api_key, secret },
+            admin
+        })
+    }
+}
+
+fn parse<T:std::str::FromStr>(flag:&str,value:&str)->Result<T,String>{
+    value.parse().map_err(|_|format!("{flag} got {value}, expected a number"))
+}
+
+//four comma separated weights in limit, market, cancel, modify order
+fn parse_mix(value:&str)->Result<OrderMix,String>{
+    let weights:Vec<u32>=value.split(',').map(|w|parse("--mix", w.trim())).collect::<Result<_,_>>()?;
+    match weights[..]{
+        [limit,market,cancel,modify] if limit+market+cancel+modify>0=>Ok(OrderMix { limit, market, cancel, modify }),
+        _=>Err("--mix takes four weights like 60,10,20,10, at least one above zero".to_string())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,61 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+mod config;
+mod client;
+mod report;
+mod worker;
+
+use config::{LoadTestConfig, USAGE};
+use client::SignedClient;
+use order_flow::{FlowConfig, OrderFlow};
+use reqwest::{Client, Method};
+use report::Report;
+use serde_json::json;
+use trading_engine::TradingPair;
+use worker::run_worker;
+
+#[tokio::main]
+async fn main() {
+    let config = match LoadTestConfig::from_args(std::env::args()){
+        Ok(config)=>config,
+        Err(e)=>{
+            eprintln!("{e}\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    let http = Client::new();
+    let markets:Vec<TradingPair> = (0..config.markets)
+        .map(|i|TradingPair { base:format!("LT{i}"), quote:"USD".to_string() })
+        .collect();
+
+    if let Some(admin)=&config.admin{
+        let mut client = SignedClient::new(http.clone(), &config.url, admin, "admin".to_string());
+        for trading_pair in &markets{
+            //a market left over from an earlier run answers TradingPairAlreadyExists, which is fine
+            if let Err(e)=client.send(Method::POST, "/api/v1/create-market", &json!({"trading_pair":trading_pair})).await{
+                eprintln!("could not reach {}: {e}",config.url);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    println!("{} workers over {} markets for {}s against {}",config.concurrency,config.markets,config.duration.as_secs(),config.url);
+    //nonces must not repeat across runs either, since the server remembers them for a while
+    let run = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    let start = Instant::now();
+    let deadline = start+config.duration;
+    let workers:Vec<_> = (0..config.concurrency)
+        .map(|i|{
+            let client = SignedClient::new(http.clone(), &config.url, &config.trader, format!("{run}-{i}"));
+            let flow = OrderFlow::new(FlowConfig::default().with_seed(config.seed+i as u64).with_mix(config.mix));
+            tokio::spawn(run_worker(client, flow, markets[i%markets.len()].clone(), deadline))
+        })
+        .collect();
+    let mut report = Report::default();
+    for worker in workers{
+        if let Ok(worker_report)=worker.await{
+            report.merge(worker_report);
+        }
+    }
+    report.print(start.elapsed());
+}
\ No newline at end of file
@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use reqwest::Method;
+
+#[derive(Clone,Copy,Debug,PartialEq,Eq,PartialOrd,Ord)]
+pub enum Endpoint{
+    Limit,
+    Market,
+    Cancel,
+    Modify
+}
+
+impl Endpoint{
+    pub fn path(&self)->&'static str{
+        match self{
+            Endpoint::Limit=>"/api/v1/limit-order",
+            Endpoint::Market=>"/api/v1/market-order",
+            Endpoint::Cancel=>"/api/v1/delete-order",
+            Endpoint::Modify=>"/api/v1/modify-order"
+        }
+    }
+
+    pub fn method(&self)->Method{
+        match self{
+            Endpoint::Cancel=>Method::DELETE,
+            _=>Method::POST
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct EndpointStats{
+    //one per response, whatever its status
+    pub latencies: Vec<Duration>,
+    //requests that never got a response
+    pub errors: u64,
+    //responses outside 2xx other than 429
+    pub http_errors: u64,
+    pub rate_limited: u64,
+    //answered with 2xx but refused by the engine or the book, like cancelling an order that already filled
+    pub rejected: u64
+}
+
+#[derive(Default)]
+pub struct Report{
+    pub endpoints: BTreeMap<Endpoint,EndpointStats>
+}
+
+impl Report{
+    pub fn stats(&mut self,endpoint:Endpoint)->&mut EndpointStats{
+        self.endpoints.entry(endpoint).or_default()
+    }
+
+    pub fn merge(&mut self,other:Report){
+        for (endpoint,stats) in other.endpoints{
+            let into=self.stats(endpoint);
+            into.latencies.extend(stats.latencies);
+            into.errors+=stats.errors;
+            into.http_errors+=stats.http_errors;
+            into.rate_limited+=stats.rate_limited;
+            into.rejected+=stats.rejected;
+        }
+    }
+
+    pub fn print(&mut self,elapsed:Duration){
+        println!("{:<22}{:>10}{:>8}{:>8}{:>8}{:>10}{:>12}{:>10}{:>10}{:>10}{:>10}{:>10}",
+            "endpoint","requests","errors","non-2xx","429s","rejected","req/s","p50","p90","p99","p99.9","max");
+        let mut total=0;
+        for (endpoint,stats) in self.endpoints.iter_mut(){
+            stats.latencies.sort();
+            let requests=stats.latencies.len() as u64+stats.errors;
+            total+=requests;
+            println!("{:<22}{:>10}{:>8}{:>8}{:>8}{:>10}{:>12.1}{:>10}{:>10}{:>10}{:>10}{:>10}",
+                endpoint.path(),
+                requests,
+                stats.errors,
+                stats.http_errors,
+                stats.rate_limited,
+                stats.rejected,
+                requests as f64/elapsed.as_secs_f64(),
+                millis(percentile(&stats.latencies, 50.0)),
+                millis(percentile(&stats.latencies, 90.0)),
+                millis(percentile(&stats.latencies, 99.0)),
+                millis(percentile(&stats.latencies, 99.9)),
+                millis(stats.latencies.last().copied()));
+        }
+        println!("{total} requests in {:.1}s, {:.1} req/s overall",elapsed.as_secs_f64(),total as f64/elapsed.as_secs_f64());
+    }
+}
+
+//nearest rank over latencies that are already sorted
+fn percentile(sorted:&[Duration],percent:f64)->Option<Duration>{
+    let rank=(percent/100.0*sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.max(1)-1).copied()
+}
+
+fn millis(latency:Option<Duration>)->String{
+    match latency{
+        Some(latency)=>format!("{:.2}ms",latency.as_secs_f64()*1000.0),
+        None=>"-".to_string()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,55 @@
+use std::time::Instant;
+
+use order_flow::{FlowEvent, OrderFlow};
+use orderbook::{OpenOrder, Side};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use trading_engine::TradingPair;
+
+use crate::client::{Reply, SignedClient};
+use crate::report::{Endpoint, Report};
+
+//one bot: sends the flow's next event, waits for the answer, and repeats until the deadline
+pub async fn run_worker(mut client:SignedClient,mut flow:OrderFlow,trading_pair:TradingPair,deadline:Instant)->Report{
+    let mut report=Report::default();
+    while Instant::now()<deadline{
+        let event=flow.next_event();
+        let (endpoint,body)=match &event{
+            FlowEvent::Limit(order)=>(Endpoint::Limit,json!({"trading_pair":trading_pair,"order":order})),
+            FlowEvent::Market(order)=>(Endpoint::Market,json!({"trading_pair":trading_pair,"order":order})),
+            FlowEvent::Cancel { order_id }=>(Endpoint::Cancel,json!({"trading_pair":trading_pair,"order_id":order_id})),
+            FlowEvent::Modify(request)=>(Endpoint::Modify,json!({"trading_pair":trading_pair,"order_request":request}))
+        };
+        let stats=report.stats(endpoint);
+        let reply=match client.send(endpoint.method(), endpoint.path(), &body).await{
+            Ok(reply)=>reply,
+            Err(_)=>{
+                stats.errors+=1;
+                continue
+            }
+        };
+        stats.latencies.push(reply.latency);
+        match reply.status{
+            StatusCode::TOO_MANY_REQUESTS=>stats.rate_limited+=1,
+            status if !status.is_success()=>stats.http_errors+=1,
+            _ if is_rejected(&reply.body)=>stats.rejected+=1,
+            _=>{}
+        }
+        if let FlowEvent::Limit(_)=event
+            && let Some((order_id,side))=resting_order(&reply){
+            flow.track(order_id, side);
+        }
+    }
+    report
+}
+
+//every response type carries its failure in `error`, market order and batch replies also inside their response
+fn is_rejected(body:&Value)->bool{
+    !body["error"].is_null() || !body["response"]["error"].is_null()
+}
+
+//a limit order the server left on the book, the only kind worth cancelling or modifying later
+fn resting_order(reply:&Reply)->Option<(u64,Side)>{
+    let order:OpenOrder=serde_json::from_value(reply.body["open_order"].clone()).ok()?;
+    (!order.is_terminal()).then_some((order.order_id,order.side))
+}
\ No newline at end of file
@@ -0,0 +1,12 @@
+#[cfg(test)]
+mod tests;
+
+mod order_flow;
+
+pub use order_flow::types::{
+    FlowConfig,
+    OrderMix,
+    FlowEvent,
+    BookShape,
+    OrderFlow
+};
\ No newline at end of file
@@ -0,0 +1,3 @@
+pub mod types;
+#[allow(clippy::module_inception)]
+pub mod order_flow;
\ No newline at end of file
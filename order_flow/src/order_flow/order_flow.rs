@@ -0,0 +1,148 @@
+use orderbook::{FixedPoint, LimitOrder, MarketOrder, ModifyOrderRequest, Numeric, Orderbook, Side};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rust_decimal::Decimal;
+use crate::order_flow::types::{BookShape, FlowConfig, FlowEvent, OrderFlow};
+
+impl OrderFlow{
+    pub fn new(config:FlowConfig)->OrderFlow{
+        let rng=ChaCha8Rng::seed_from_u64(config.seed);
+        OrderFlow { config, rng, live_orders:Vec::new() }
+    }
+
+    //records an order the caller saw rest, so later cancels and modifies can target it
+    pub fn track(&mut self,order_id:u64,side:Side){
+        self.live_orders.push((order_id,side));
+    }
+
+    pub fn live_orders(&self)->usize{
+        self.live_orders.len()
+    }
+
+    pub fn next_event(&mut self)->FlowEvent{
+        let mix=self.config.mix;
+        let mut pick=self.rng.random_range(0..(mix.limit+mix.market+mix.cancel+mix.modify).max(1));
+        //cancels and modifies need something resting, until then they are placed as limit orders
+        if pick<mix.limit || (pick>=mix.limit+mix.market && self.live_orders.is_empty()){
+            return FlowEvent::Limit(self.limit_order())
+        }
+        pick-=mix.limit;
+        if pick<mix.market{
+            let side=self.side();
+            return FlowEvent::Market(MarketOrder::new(self.quantity(), side, self.user_id()))
+        }
+        pick-=mix.market;
+        let index=self.rng.random_range(0..self.live_orders.len());
+        if pick<mix.cancel{
+            let (order_id,_)=self.live_orders.swap_remove(index);
+            return FlowEvent::Cancel { order_id }
+        }
+        let (order_id,side)=self.live_orders[index].clone();
+        //half the modifies only resize, the other half also move the order
+        let price=self.rng.random_bool(0.5).then(||self.passive_price(&side));
+        FlowEvent::Modify(ModifyOrderRequest { price, quantity:Some(self.quantity()), order_id })
+    }
+
+    pub fn events(&mut self,count:usize)->Vec<FlowEvent>{
+        (0..count).map(|_|self.next_event()).collect()
+    }
+
+    //non crossing limit orders filling out the shape, best levels first
+    pub fn resting_book(&mut self,shape:BookShape)->Vec<LimitOrder>{
+        let mut orders=Vec::with_capacity(shape.resting_orders() as usize);
+        for level in 1..=shape.levels_per_side{
+            let offset=self.config.tick_size*Decimal::from(level);
+            for _ in 0..shape.orders_per_level{
+                for (side,price) in [(Side::Bids,self.config.mid_price-offset),(Side::Asks,self.config.mid_price+offset)]{
+                    let (quantity,user_id)=(self.quantity(),self.user_id());
+                    orders.push(LimitOrder { price, quantity, side, user_id, client_order_id:None });
+                }
+            }
+        }
+        orders
+    }
+
+    //a book seeded with the shape, with every resting order tracked
+    pub fn build_book(&mut self,shape:BookShape)->Orderbook{
+        self.fill_book(Orderbook::new(), shape)
+    }
+
+    //the same book kept in whole ticks and lots of the flow
+    pub fn build_fixed_point_book(&mut self,shape:BookShape)->Orderbook<FixedPoint>{
+        let scale=self.config.fixed_point_scale();
+        self.fill_book(Orderbook::with_scale(scale), shape)
+    }
+
+    fn fill_book<N:Numeric>(&mut self,mut orderbook:Orderbook<N>,shape:BookShape)->Orderbook<N>{
+        for order in self.resting_book(shape){
+            self.apply(&mut orderbook, FlowEvent::Limit(order));
+        }
+        orderbook
+    }
+
+    //runs an event against the book, tracking whatever is left resting
+    pub fn apply<N:Numeric>(&mut self,orderbook:&mut Orderbook<N>,event:FlowEvent){
+        match event{
+            FlowEvent::Limit(order)=>{
+                let open_order=orderbook.add_limit_order(order);
+                if !open_order.is_terminal(){
+                    self.track(open_order.order_id, open_order.side);
+                }
+            },
+            FlowEvent::Market(order)=>{
+                orderbook.add_market_order(order);
+            },
+            FlowEvent::Cancel { order_id }=>{
+                let _=orderbook.delete_order(order_id);
+            },
+            FlowEvent::Modify(request)=>{
+                let _=orderbook.modify_order(request);
+            }
+        }
+    }
+
+    fn limit_order(&mut self)->LimitOrder{
+        let side=self.side();
+        let price=if self.rng.random_bool(self.config.crossing_ratio){
+            self.aggressive_price(&side)
+        }else{
+            self.passive_price(&side)
+        };
+        LimitOrder { price, quantity:self.quantity(), side, user_id:self.user_id(), client_order_id:None }
+    }
+
+    fn side(&mut self)->Side{
+        if self.rng.random_bool(0.5){Side::Bids}else{Side::Asks}
+    }
+
+    fn ticks(&mut self)->Decimal{
+        self.config.tick_size*Decimal::from(self.rng.random_range(1..=self.config.price_range_ticks.max(1)))
+    }
+
+    //on the order's own side of the mid
+    fn passive_price(&mut self,side:&Side)->Decimal{
+        let ticks=self.ticks();
+        match side{
+            Side::Bids=>self.config.mid_price-ticks,
+            Side::Asks=>self.config.mid_price+ticks
+        }
+    }
+
+    //through the mid, into the other side of the book
+    fn aggressive_price(&mut self,side:&Side)->Decimal{
+        let ticks=self.ticks();
+        match side{
+            Side::Bids=>self.config.mid_price+ticks,
+            Side::Asks=>self.config.mid_price-ticks
+        }
+    }
+
+    fn quantity(&mut self)->Decimal{
+        let lots=self.rng.random_range(self.config.min_lots..=self.config.max_lots.max(self.config.min_lots));
+        self.config.lot_size*Decimal::from(lots)
+    }
+
+    fn user_id(&mut self)->u64{
+        self.rng.random_range(1..=self.config.users.max(1))
+    }
+}
\ No newline at end of file
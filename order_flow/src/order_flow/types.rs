@@ -0,0 +1,105 @@
+use orderbook::{FixedPointScale, LimitOrder, MarketOrder, ModifyOrderRequest, Side};
+use rand_chacha::ChaCha8Rng;
+use rust_decimal::{dec, Decimal};
+
+//what the generated flow looks like, the same seed always gives the same events
+#[derive(Clone,Debug)]
+pub struct FlowConfig{
+    pub seed: u64,
+    pub mid_price: Decimal,
+    pub tick_size: Decimal,
+    //new limit orders are priced up to this many ticks away from the mid
+    pub price_range_ticks: u32,
+    //share of limit orders priced through the mid so that they trade on arrival
+    pub crossing_ratio: f64,
+    pub lot_size: Decimal,
+    pub min_lots: u32,
+    pub max_lots: u32,
+    //orders are spread over user ids 1 to `users`
+    pub users: u64,
+    pub mix: OrderMix
+}
+
+//relative weights of each kind of event
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct OrderMix{
+    pub limit: u32,
+    pub market: u32,
+    pub cancel: u32,
+    pub modify: u32
+}
+
+#[derive(Clone)]
+pub enum FlowEvent{
+    Limit(LimitOrder),
+    Market(MarketOrder),
+    Cancel{order_id:u64},
+    Modify(ModifyOrderRequest)
+}
+
+//resting liquidity to seed a book with before running flow against it
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct BookShape{
+    pub levels_per_side: u32,
+    pub orders_per_level: u32
+}
+
+pub struct OrderFlow{
+    pub config: FlowConfig,
+    pub(crate) rng: ChaCha8Rng,
+    //orders believed to be resting, the targets for cancels and modifies
+    pub(crate) live_orders: Vec<(u64,Side)>
+}
+
+impl Default for FlowConfig{
+    fn default()->Self{
+        FlowConfig {
+            seed:42,
+            mid_price:dec!(100),
+            tick_size:dec!(0.01),
+            price_range_ticks:50,
+            crossing_ratio:0.1,
+            lot_size:dec!(0.1),
+            min_lots:1,
+            max_lots:100,
+            users:100,
+            mix:OrderMix::default()
+        }
+    }
+}
+
+impl Default for OrderMix{
+    fn default()->Self{
+        OrderMix { limit:60, market:10, cancel:20, modify:10 }
+    }
+}
+
+impl FlowConfig{
+    pub fn with_seed(mut self,seed:u64)->FlowConfig{
+        self.seed=seed;
+        self
+    }
+
+    pub fn with_mix(mut self,mix:OrderMix)->FlowConfig{
+        self.mix=mix;
+        self
+    }
+
+    //every price and quantity the flow generates is a whole number of these
+    pub fn fixed_point_scale(&self)->FixedPointScale{
+        FixedPointScale::new(self.tick_size, self.lot_size)
+    }
+}
+
+impl BookShape{
+    //a handful of orders close to the touch
+    pub const THIN:BookShape=BookShape { levels_per_side:5, orders_per_level:2 };
+    //few prices, long queues, like a liquid major pair
+    pub const DEEP:BookShape=BookShape { levels_per_side:50, orders_per_level:200 };
+    //many prices with little at each, like a long tail market
+    pub const WIDE:BookShape=BookShape { levels_per_side:5_000, orders_per_level:2 };
+
+    pub fn resting_orders(&self)->u64{
+        2*self.levels_per_side as u64*self.orders_per_level as u64
+    }
+}
\ No newline at end of file
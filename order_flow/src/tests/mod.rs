@@ -0,0 +1 @@
+mod order_flow;
\ No newline at end of file
@@ -0,0 +1,70 @@
+use orderbook::Orderbook;
+use crate::{BookShape, FlowConfig, FlowEvent, OrderFlow, OrderMix};
+#[cfg(test)]
+use pretty_assertions::{assert_eq, assert_ne};
+
+fn run(config:FlowConfig,events:usize)->Orderbook{
+    let mut flow=OrderFlow::new(config);
+    let mut orderbook=flow.build_book(BookShape::THIN);
+    for _ in 0..events{
+        let event=flow.next_event();
+        flow.apply(&mut orderbook, event);
+    }
+    orderbook
+}
+
+#[test]
+fn test_same_seed_gives_same_flow(){
+    let first=run(FlowConfig::default().with_seed(7), 5_000);
+    let second=run(FlowConfig::default().with_seed(7), 5_000);
+    assert_eq!(first.get_depth(),second.get_depth());
+    let trades=|orderbook:&Orderbook|orderbook.get_recent_trades(100).into_iter().map(|t|(t.trade_id,t.price,t.quantity)).collect::<Vec<_>>();
+    assert_eq!(trades(&first),trades(&second));
+
+    let other=run(FlowConfig::default().with_seed(8), 5_000);
+    assert_ne!(first.get_depth(),other.get_depth());
+}
+
+#[test]
+fn test_resting_book_has_the_shape_and_does_not_cross(){
+    let mut flow=OrderFlow::new(FlowConfig::default());
+    let orderbook=flow.build_book(BookShape::DEEP);
+    assert_eq!(orderbook.bids.len(),50);
+    assert_eq!(orderbook.asks.len(),50);
+    assert_eq!((orderbook.bids.order_count()+orderbook.asks.order_count()) as u64,BookShape::DEEP.resting_orders());
+    assert_eq!(flow.live_orders() as u64,BookShape::DEEP.resting_orders());
+    assert!(orderbook.get_recent_trades(1).is_empty());
+}
+
+#[test]
+fn test_flow_keeps_the_book_consistent(){
+    let orderbook=run(FlowConfig::default(), 20_000);
+    assert!(!orderbook.get_recent_trades(1).is_empty());
+    if let (Some(bid),Some(ask))=(orderbook.bids.best_price(),orderbook.asks.best_price()){
+        assert!(bid<ask);
+    }
+    assert_eq!(orderbook.order_map.len(),orderbook.bids.order_count()+orderbook.asks.order_count());
+}
+
+#[test]
+fn test_fixed_point_book_ends_up_like_the_decimal_one(){
+    let mut flow=OrderFlow::new(FlowConfig::default());
+    let mut fixed_point=flow.build_fixed_point_book(BookShape::THIN);
+    for _ in 0..5_000{
+        let event=flow.next_event();
+        flow.apply(&mut fixed_point, event);
+    }
+    let decimal=run(FlowConfig::default(), 5_000);
+    assert_eq!(fixed_point.get_depth(),decimal.get_depth());
+    let trades=|trades:Vec<orderbook::PublicTrade>|trades.into_iter().map(|t|(t.trade_id,t.price,t.quantity)).collect::<Vec<_>>();
+    assert_eq!(trades(fixed_point.get_recent_trades(100)),trades(decimal.get_recent_trades(100)));
+}
+
+#[test]
+fn test_cancels_wait_for_something_to_rest(){
+    let mut flow=OrderFlow::new(FlowConfig::default().with_mix(OrderMix{limit:0,market:0,cancel:1,modify:0}));
+    assert!(matches!(flow.next_event(),FlowEvent::Limit(_)));
+    flow.track(1, orderbook::Side::Bids);
+    assert!(matches!(flow.next_event(),FlowEvent::Cancel{order_id:1}));
+    assert_eq!(flow.live_orders(),0);
+}
\ No newline at end of file
@@ -0,0 +1,67 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use orderbook::{LimitOrder, ModifyOrderRequest, Orderbook, Side};
+use rust_decimal::Decimal;
+
+const RESTING_ORDERS:u64=100_000;
+
+//100k resting orders split evenly across both sides, `per_level` orders to a price
+fn book(per_level:u64)->Orderbook{
+    let mut orderbook=Orderbook::new();
+    for i in 0..RESTING_ORDERS{
+        let level=Decimal::from(i/2/per_level);
+        let (side,price)=if i%2==0{
+            (Side::Bids,Decimal::from(100_000)-level)
+        }else{
+            (Side::Asks,Decimal::from(100_001)+level)
+        };
+        orderbook.add_limit_order(LimitOrder{price,quantity:Decimal::from(10),side,user_id:1,client_order_id:None});
+    }
+    orderbook
+}
+
+//visits every order id once in a scattered order, so cancels hit the middle of levels as often as the ends
+fn order_id(i:u64)->u64{
+    i*7919%RESTING_ORDERS+1
+}
+
+fn cancel(c:&mut Criterion){
+    let mut group=c.benchmark_group("cancel_100k_resting");
+    //deep levels keep most cancels inside a queue, one order per level empties a level on every cancel
+    for per_level in [100,1]{
+        group.bench_with_input(BenchmarkId::from_parameter(format!("{per_level}_per_level")), &per_level, |b,&per_level|{
+            b.iter_custom(|iters|{
+                let mut elapsed=Duration::ZERO;
+                let mut done=0;
+                while done<iters{
+                    let mut orderbook=book(per_level);
+                    let batch=(iters-done).min(RESTING_ORDERS);
+                    let start=Instant::now();
+                    for i in 0..batch{
+                        black_box(orderbook.delete_order(order_id(i)).ok());
+                    }
+                    elapsed+=start.elapsed();
+                    done+=batch;
+                }
+                elapsed
+            })
+        });
+    }
+    group.finish();
+}
+
+fn reduce(c:&mut Criterion){
+    let mut orderbook=book(100);
+    let mut i=0;
+    c.bench_function("reduce_100k_resting", |b|b.iter(||{
+        i+=1;
+        //a same price modify keeps the order's place, so the book never changes shape
+        let quantity=Decimal::from(9+i%2);
+        black_box(orderbook.modify_order(ModifyOrderRequest{price:None,quantity:Some(quantity),order_id:order_id(i%RESTING_ORDERS)}).ok());
+    }));
+}
+
+criterion_group!(benches, cancel, reduce);
+criterion_main!(benches);
\ No newline at end of file
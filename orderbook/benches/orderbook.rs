@@ -0,0 +1,123 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use order_flow::{BookShape, FlowConfig, FlowEvent, OrderFlow, OrderMix};
+use orderbook::{MarketOrder, Numeric, Orderbook, Side};
+use rust_decimal::Decimal;
+
+const SHAPES:[(&str,BookShape);3]=[("thin",BookShape::THIN),("deep",BookShape::DEEP),("wide",BookShape::WIDE)];
+
+fn only(limit:u32,market:u32,cancel:u32,modify:u32)->OrderMix{
+    OrderMix { limit, market, cancel, modify }
+}
+
+fn execute<N:Numeric>(orderbook:&mut Orderbook<N>,event:FlowEvent){
+    match event{
+        FlowEvent::Limit(order)=>{
+            black_box(orderbook.add_limit_order(order));
+        },
+        FlowEvent::Market(order)=>{
+            black_box(orderbook.add_market_order(order));
+        },
+        FlowEvent::Cancel { order_id }=>{
+            black_box(orderbook.delete_order(order_id).ok());
+        },
+        FlowEvent::Modify(request)=>{
+            black_box(orderbook.modify_order(request).ok());
+        }
+    }
+}
+
+//times `iters` events against books of the given shape, building a fresh book outside the timed section every `per_book` events
+fn on_fresh_books<N:Numeric>(iters:u64,shape:BookShape,per_book:u64,build:fn(&mut OrderFlow,BookShape)->Orderbook<N>,events:impl Fn(&mut OrderFlow,BookShape,u64)->Vec<FlowEvent>)->Duration{
+    let mut elapsed=Duration::ZERO;
+    let mut done=0;
+    while done<iters{
+        let mut flow=OrderFlow::new(FlowConfig::default().with_seed(done));
+        let mut orderbook=build(&mut flow, shape);
+        let batch=(iters-done).min(per_book);
+        let events=events(&mut flow,shape,batch);
+        let start=Instant::now();
+        for event in events{
+            execute(&mut orderbook, event);
+        }
+        elapsed+=start.elapsed();
+        done+=batch;
+    }
+    elapsed
+}
+
+//each shape runs once on a book of decimals and once on a fixed point book of the flow's ticks and lots
+fn bench_events(c:&mut Criterion,name:&str,per_book:impl Fn(BookShape)->u64,events:impl Fn(&mut OrderFlow,BookShape,u64)->Vec<FlowEvent>+Copy){
+    let mut group=c.benchmark_group(name);
+    group.sample_size(20);
+    for (shape_name,shape) in SHAPES{
+        group.bench_function(BenchmarkId::new("decimal", shape_name), |b|{
+            b.iter_custom(|iters|on_fresh_books(iters, shape, per_book(shape), OrderFlow::build_book, events))
+        });
+        group.bench_function(BenchmarkId::new("fixed_point", shape_name), |b|{
+            b.iter_custom(|iters|on_fresh_books(iters, shape, per_book(shape), OrderFlow::build_fixed_point_book, events))
+        });
+    }
+    group.finish();
+}
+
+//passive limit orders that join the book without trading
+fn insert(c:&mut Criterion){
+    bench_events(c, "insert", |_|10_000, |flow,_,count|{
+        flow.config.crossing_ratio=0.0;
+        flow.config.mix=only(1,0,0,0);
+        flow.events(count as usize)
+    });
+}
+
+//limit orders priced through the mid, the path through `match_limit_order`
+fn cross(c:&mut Criterion){
+    bench_events(c, "match_limit_order", |_|1_000, |flow,_,count|{
+        flow.config.crossing_ratio=1.0;
+        flow.config.mix=only(1,0,0,0);
+        flow.events(count as usize)
+    });
+}
+
+//market orders each big enough to clear five levels, alternating sides until the book runs dry
+fn market_sweep(c:&mut Criterion){
+    bench_events(c, "market_sweep", |shape|2*(shape.levels_per_side/5) as u64, |flow,shape,count|{
+        let average_order=flow.config.lot_size*Decimal::from(flow.config.min_lots+flow.config.max_lots)/Decimal::from(2);
+        let quantity=average_order*Decimal::from(5*shape.orders_per_level);
+        (0..count)
+            .map(|i|{
+                let side=if i%2==0{Side::Bids}else{Side::Asks};
+                FlowEvent::Market(MarketOrder::new(quantity, side, 1))
+            })
+            .collect()
+    });
+}
+
+//cancels of random resting orders until the book is empty
+fn cancel(c:&mut Criterion){
+    bench_events(c, "cancel", |shape|shape.resting_orders(), |flow,_,count|{
+        flow.config.mix=only(0,0,1,0);
+        flow.events(count as usize)
+    });
+}
+
+fn depth(c:&mut Criterion){
+    let mut group=c.benchmark_group("depth");
+    for (shape_name,shape) in SHAPES{
+        let orderbook=OrderFlow::new(FlowConfig::default()).build_book(shape);
+        group.bench_function(BenchmarkId::new("decimal", shape_name), |b|b.iter(||black_box(orderbook.get_depth())));
+        let orderbook=OrderFlow::new(FlowConfig::default()).build_fixed_point_book(shape);
+        group.bench_function(BenchmarkId::new("fixed_point", shape_name), |b|b.iter(||black_box(orderbook.get_depth())));
+    }
+    group.finish();
+}
+
+//the default mix of limit, market, cancel and modify events
+fn mixed_flow(c:&mut Criterion){
+    bench_events(c, "mixed_flow", |_|10_000, |flow,_,count|flow.events(count as usize));
+}
+
+criterion_group!(benches, insert, cross, market_sweep, cancel, depth, mixed_flow);
+criterion_main!(benches);
\ No newline at end of file
@@ -9,14 +9,79 @@ pub use orderbook::types::{
     OpenOrder,
     MarketOrder,
     Depth,
+    Order,
     Side,
     ModifyOrderRequest,
+    Trade,
+    PublicTrade,
+    UserTrade,
+    LiquidityRole,
+    FeeSchedule,
+    TradeHistoryQuery,
+    TradeLog,
+    OrderStatus,
+    OrderHistory,
+    CancelFilter,
+    BatchOperation,
+    ClientOrderIds,
+    PriceBand,
+    PriceBandConfig,
+    ReferencePrice,
+    BandAction,
+    Uncross,
+    Allocation,
+    Fifo,
+    ProRata,
+    PriceTimeProRata,
+    PriceLevels,
+    Bids,
+    Asks,
+    Level,
+    OrderHandle,
+    BookChanges,
 };
 
 pub use orderbook::response::{
     MarketOrderResponse,
     ModifyOrderResponse,
-    ErrorResponse,
     DeleteResponse,
-    CustomError
+    CustomError,
+    TradeHistoryResponse,
+    BatchResult,
+    BatchResponse
+};
+
+pub use orderbook::trades::{
+    DEFAULT_TRADE_QUERY_LIMIT,
+    MAX_TRADE_QUERY_LIMIT
+};
+
+pub use orderbook::history::{
+    DEFAULT_ORDER_HISTORY_LIMIT,
+    MAX_ORDER_HISTORY_SIZE
+};
+
+pub use orderbook::batch::MAX_BATCH_SIZE;
+
+pub use orderbook::client_order_ids::{MAX_CLIENT_ORDER_IDS, IDEMPOTENCY_WINDOW_MS};
+
+pub use orderbook::clock::current_timestamp;
+
+pub use orderbook::allocation::MatchingAlgorithm;
+
+pub use orderbook::price_levels::{PriceKey, LevelOrders};
+
+pub use orderbook::numeric::{
+    Numeric,
+    FixedPoint,
+    FixedPointScale,
+    MAX_PRICE,
+    MAX_QUANTITY,
+    MAX_NOTIONAL
+};
+
+pub use orderbook::matching::{
+    MatchingPolicy,
+    LimitPolicy,
+    MarketPolicy
 };
\ No newline at end of file
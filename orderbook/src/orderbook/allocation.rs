@@ -0,0 +1,102 @@
+use rust_decimal::{Decimal, RoundingStrategy};
+use crate::orderbook::numeric::Numeric;
+use crate::orderbook::types::{Allocation, Fifo, PriceTimeProRata, ProRata};
+use crate::Orderbook;
+
+//shares a taker's quantity among the orders resting at one price, new policies only need to implement this
+//and go into a book with `Orderbook::with_algorithm`
+pub trait MatchingAlgorithm:Clone{
+    //one fill per resting order in queue order, none larger than what the order has open and together no more than `quantity`
+    fn allocate<N:Numeric>(&self,open_quantities:&[N],quantity:N,scale:&N::Scale)->Vec<N>;
+
+    //whether every level is simply filled front to back, matching then stops at the last order it needs
+    //instead of asking `allocate` about the whole level
+    fn in_time_priority(&self)->bool{
+        false
+    }
+}
+
+impl MatchingAlgorithm for Fifo{
+    fn in_time_priority(&self)->bool{
+        true
+    }
+
+    fn allocate<N:Numeric>(&self,open_quantities:&[N],quantity:N,_:&N::Scale)->Vec<N>{
+        let mut remaining=quantity;
+        open_quantities
+            .iter()
+            .map(|open|{
+                let fill=remaining.min(*open);
+                remaining-=fill;
+                fill
+            })
+            .collect()
+    }
+}
+
+impl MatchingAlgorithm for ProRata{
+    fn allocate<N:Numeric>(&self,open_quantities:&[N],quantity:N,scale:&N::Scale)->Vec<N>{
+        let total:N=open_quantities.iter().copied().sum();
+        if quantity>=total{
+            return open_quantities.to_vec()
+        }
+        //a lot size finer than the book's own lots is no rounding at all
+        let lot_size=(self.lot_size>Decimal::ZERO)
+            .then(||N::round_quantity(self.lot_size, RoundingStrategy::ToNegativeInfinity, scale))
+            .filter(|lot_size|*lot_size>N::ZERO);
+        let min_allocation=N::round_quantity(self.min_allocation, RoundingStrategy::ToPositiveInfinity, scale);
+        let mut allocated=N::ZERO;
+        let mut fills:Vec<N>=open_quantities
+            .iter()
+            .map(|open|{
+                let share=quantity.mul_div(*open, total);
+                let share=lot_size.map_or(share, |lot_size|share.floor_to(lot_size)).min(quantity-allocated);
+                let share=if share<min_allocation{N::ZERO}else{share};
+                allocated+=share;
+                share
+            })
+            .collect();
+        let room:Vec<N>=open_quantities.iter().zip(fills.iter()).map(|(open,fill)|*open-*fill).collect();
+        for (fill,extra) in fills.iter_mut().zip(Fifo.allocate(&room, quantity-allocated, scale)){
+            *fill+=extra;
+        }
+        fills
+    }
+}
+
+impl MatchingAlgorithm for PriceTimeProRata{
+    fn allocate<N:Numeric>(&self,open_quantities:&[N],quantity:N,scale:&N::Scale)->Vec<N>{
+        let fifo_quantity=quantity.bps(self.fifo_bps.min(10_000));
+        let mut fills=Fifo.allocate(open_quantities, fifo_quantity, scale);
+        let room:Vec<N>=open_quantities.iter().zip(fills.iter()).map(|(open,fill)|*open-*fill).collect();
+        let rest=quantity-fills.iter().copied().sum::<N>();
+        for (fill,extra) in fills.iter_mut().zip(self.pro_rata.allocate(&room, rest, scale)){
+            *fill+=extra;
+        }
+        fills
+    }
+}
+
+impl MatchingAlgorithm for Allocation{
+    fn allocate<N:Numeric>(&self,open_quantities:&[N],quantity:N,scale:&N::Scale)->Vec<N>{
+        match self{
+            Allocation::Fifo=>Fifo.allocate(open_quantities, quantity, scale),
+            Allocation::ProRata(pro_rata)=>pro_rata.allocate(open_quantities, quantity, scale),
+            Allocation::PriceTimeProRata(hybrid)=>hybrid.allocate(open_quantities, quantity, scale)
+        }
+    }
+
+    fn in_time_priority(&self)->bool{
+        match self{
+            Allocation::Fifo=>Fifo.in_time_priority(),
+            Allocation::ProRata(pro_rata)=>pro_rata.in_time_priority(),
+            Allocation::PriceTimeProRata(hybrid)=>hybrid.in_time_priority()
+        }
+    }
+}
+
+impl<N:Numeric,A:MatchingAlgorithm> Orderbook<N,A>{
+    pub fn set_allocation(&mut self,allocation:A){
+        self.allocation=allocation;
+    }
+}
\ No newline at end of file
@@ -0,0 +1,107 @@
+use rust_decimal::dec;
+use crate::orderbook::{numeric::Numeric, types::{Side, Taker, Uncross}};
+use crate::Orderbook;
+use crate::orderbook::allocation::MatchingAlgorithm;
+
+impl<N:Numeric> Uncross<N>{
+    fn to_decimal(&self,scale:&N::Scale)->Uncross{
+        Uncross {
+            price: self.price.to_price(scale),
+            volume: self.volume.to_quantity(scale),
+            imbalance: self.imbalance.to_quantity(scale),
+            imbalance_side: self.imbalance_side.clone()
+        }
+    }
+}
+
+impl<N:Numeric,A:MatchingAlgorithm> Orderbook<N,A>{
+    pub fn start_auction(&mut self){
+        self.in_auction=true;
+    }
+
+    fn demand_at(&self,price:N)->N{
+        self.bids.quantity_within(Some(price))
+    }
+
+    fn supply_at(&self,price:N)->N{
+        self.asks.quantity_within(Some(price))
+    }
+
+    //price the auction would clear at if it ended now, none outside an auction or when the book does not cross
+    pub fn indicative_uncross(&self)->Option<Uncross>{
+        self.uncross().map(|uncross|uncross.to_decimal(&self.scale))
+    }
+
+    fn uncross(&self)->Option<Uncross<N>>{
+        if !self.in_auction{
+            return None
+        }
+        let mut prices:Vec<N>=self.bids.keys().map(|p|p.0).chain(self.asks.keys().copied()).collect();
+        prices.sort();
+        prices.dedup();
+        let candidates:Vec<(N,N,N)>=prices
+            .into_iter()
+            .map(|price|(price,self.demand_at(price),self.supply_at(price)))
+            .filter(|&(_,demand,supply)|demand.min(supply)>N::ZERO)
+            .collect();
+        let difference=|d:N,s:N|d.max(s)-d.min(s);
+        //most volume first, then the smallest imbalance
+        let volume=candidates.iter().map(|&(_,d,s)|d.min(s)).max()?;
+        let candidates:Vec<_>=candidates.into_iter().filter(|&(_,d,s)|d.min(s)==volume).collect();
+        let imbalance=candidates.iter().map(|&(_,d,s)|difference(d,s)).min()?;
+        let candidates:Vec<_>=candidates.into_iter().filter(|&(_,d,s)|difference(d,s)==imbalance).collect();
+        //then towards the side with quantity left over, and failing that the reference price
+        let (price,demand,supply)=if candidates.iter().all(|&(_,d,s)|d>s){
+            *candidates.last()?
+        }else if candidates.iter().all(|&(_,d,s)|d<s){
+            *candidates.first()?
+        }else{
+            let (low,high)=(candidates.first()?.0.to_price(&self.scale),candidates.last()?.0.to_price(&self.scale));
+            let reference=self.price_band.reference_price.unwrap_or((low+high)/dec!(2));
+            *candidates.iter().min_by_key(|&&(p,_,_)|(p.to_price(&self.scale)-reference).abs())?
+        };
+        let imbalance_side=if demand>supply{
+            Some(Side::Bids)
+        }else if supply>demand{
+            Some(Side::Asks)
+        }else{
+            None
+        };
+        Some(Uncross { price, volume, imbalance, imbalance_side })
+    }
+
+    //uncrosses the book at the indicative price and goes back to continuous matching, the older order of each fill is the maker
+    pub fn end_auction(&mut self)->Option<Uncross>{
+        let uncross=self.uncross();
+        self.in_auction=false;
+        let uncross=uncross?;
+        let last_trade_id=self.trade_log.trade_id_index;
+        let price=uncross.price.to_price(&self.scale);
+        let mut remaining=uncross.volume;
+        while remaining>N::ZERO{
+            let (Some(bid_slot),Some(ask_slot))=(self.bids.front(),self.asks.front()) else{
+                break
+            };
+            let (Some(bid),Some(ask))=(self.bids.get_mut(bid_slot),self.asks.get_mut(ask_slot)) else{
+                break
+            };
+            let quantity=remaining.min(bid.open_quantity()).min(ask.open_quantity());
+            let (maker,taker)=if bid.order_id<ask.order_id{(&*bid,&*ask)}else{(&*ask,&*bid)};
+            let taker=Taker{order_id:Some(taker.order_id),user_id:taker.user_id,side:taker.side.clone()};
+            self.trade_log.record_at(price, &self.fee_schedule, maker, &taker, quantity.to_quantity(&self.scale));
+            remaining-=quantity;
+            bid.fill(quantity);
+            ask.fill(quantity);
+            let filled=[
+                bid.is_terminal().then(||self.bids.remove(bid_slot)).flatten(),
+                ask.is_terminal().then(||self.asks.remove(ask_slot)).flatten()
+            ];
+            for filled in filled.into_iter().flatten(){
+                self.order_map.remove(&filled.order_id);
+                self.order_history.archive(filled.into_decimal(&self.scale));
+            }
+        }
+        self.observe_trades_since(last_trade_id);
+        Some(uncross.to_decimal(&self.scale))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,214 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::orderbook::{numeric::Numeric, price_levels::PriceKey, response::{BatchResponse, BatchResult, CustomError}, types::{BatchOperation, ClientOrderIds, OrderHandle, OrderHistory, OrderStatus, PriceLevels, Side, TradeLog}};
+use crate::Orderbook;
+use crate::orderbook::allocation::MatchingAlgorithm;
+
+pub const MAX_BATCH_SIZE:usize=100;
+
+//how far into one side of the book the orders of a batch could trade
+struct Reach<K:PriceKey>{
+    quantity: K::Number,
+    //most aggressive price among them
+    limit: Option<K>
+}
+
+impl<K:PriceKey> Reach<K>{
+    fn new()->Reach<K>{
+        Reach { quantity:K::Number::ZERO, limit:None }
+    }
+
+    fn extend(&mut self,price:Option<K::Number>,quantity:K::Number){
+        self.quantity+=quantity.max(K::Number::ZERO);
+        self.limit=self.limit.max(price.map(K::from_price));
+    }
+}
+
+impl<K:PriceKey> PriceLevels<K>{
+    //the levels an order within `reach` could trade against, counting the named orders as gone since the batch may pull them first,
+    //plus the levels holding named orders, each copied with its queue in order
+    fn copy_reachable(&self,reach:&Reach<K>,named:&BTreeSet<K>,named_quantity:K::Number,side:&Side,order_map:&mut HashMap<u64,OrderHandle>)->PriceLevels<K>{
+        let needed=reach.quantity+named_quantity;
+        let mut keys=named.clone();
+        let mut reached=K::Number::ZERO;
+        for (key,orders) in self.iter(){
+            if reached>=needed || reach.limit.is_none_or(|limit|*key>limit){
+                break
+            }
+            reached+=orders.map(|o|o.open_quantity()).sum();
+            keys.insert(*key);
+        }
+        let mut copy=PriceLevels::new();
+        for key in keys{
+            for order in self.orders_at(key.price()){
+                let slot=copy.push_back(order.clone());
+                order_map.insert(order.order_id, OrderHandle { side:side.clone(), slot });
+            }
+        }
+        copy
+    }
+}
+
+impl<N:Numeric,A:MatchingAlgorithm> Orderbook<N,A>{
+    //applies the operations in order, with `all_or_nothing` nothing is applied unless every operation succeeds
+    pub fn apply_batch(&mut self,operations:Vec<BatchOperation>,all_or_nothing:bool)->BatchResponse{
+        if operations.len()>MAX_BATCH_SIZE{
+            return BatchResponse::rejected(CustomError::BatchTooLarge)
+        }
+        if all_or_nothing{
+            let dry_run=self.reachable_copy(&operations).apply_operations(operations.clone(), true);
+            if dry_run.iter().any(|r|matches!(r,BatchResult::Failed(_))){
+                let results=dry_run
+                    .into_iter()
+                    .zip(operations.iter())
+                    .map(|(result,operation)|match result{
+                        BatchResult::Failed(CustomError::OrderDoesNotExist)=>BatchResult::Failed(self.operation_error(operation)),
+                        BatchResult::Failed(e)=>BatchResult::Failed(e),
+                        _=>BatchResult::NotApplied
+                    })
+                    .collect();
+                return BatchResponse::new(results)
+            }
+        }
+        BatchResponse::new(self.apply_operations(operations, false))
+    }
+
+    //a batch sent by the user: every order it places is theirs, and one naming another user's order is turned away as a whole
+    pub fn apply_user_batch(&mut self,user_id:u64,mut operations:Vec<BatchOperation>,all_or_nothing:bool)->BatchResponse{
+        for operation in operations.iter_mut(){
+            let order_id=match operation{
+                BatchOperation::Place(order)=>{
+                    order.user_id=user_id;
+                    continue
+                },
+                BatchOperation::Modify(request)=>request.order_id,
+                BatchOperation::Cancel{order_id}=>*order_id
+            };
+            if self.is_foreign_order(user_id, order_id){
+                return BatchResponse::rejected(CustomError::OrderDoesNotExist)
+            }
+        }
+        self.apply_batch(operations, all_or_nothing)
+    }
+
+    //a dry run fails a placement that comes back rejected, as an all-or-nothing batch cannot leave it behind
+    fn apply_operations(&mut self,operations:Vec<BatchOperation>,dry_run:bool)->Vec<BatchResult>{
+        operations
+            .into_iter()
+            .map(|operation|match operation{
+                BatchOperation::Place(order) if dry_run=>{
+                    let error=self.limit_order_error(&order);
+                    match self.add_limit_order(order){
+                        //a resubmission answered with an order rejected earlier
+                        o if o.status==OrderStatus::Rejected=>BatchResult::Failed(error.unwrap_or(CustomError::OrderAlreadyClosed)),
+                        o=>BatchResult::Placed(o)
+                    }
+                },
+                BatchOperation::Place(order)=>BatchResult::Placed(self.add_limit_order(order)),
+                BatchOperation::Modify(request)=>match self.modify_order(request){
+                    Ok(r)=>BatchResult::Modified(r),
+                    Err(e)=>BatchResult::Failed(e)
+                },
+                BatchOperation::Cancel{order_id}=>match self.delete_order(order_id){
+                    Ok(r)=>BatchResult::Cancelled(r),
+                    Err(e)=>BatchResult::Failed(e)
+                }
+            })
+            .collect()
+    }
+
+    //the dry run copy only holds the history a resubmission may need, so ask the real book why an order is gone
+    fn operation_error(&self,operation:&BatchOperation)->CustomError{
+        match operation{
+            BatchOperation::Modify(request)=>self.closed_order_error(request.order_id),
+            BatchOperation::Cancel{order_id}=>self.closed_order_error(*order_id),
+            BatchOperation::Place(_)=>CustomError::OrderDoesNotExist
+        }
+    }
+
+    //the part of the book a batch can see: the orders it names and the levels its orders could trade against,
+    //so a dry run costs what the batch touches rather than what rests in the book
+    fn reachable_copy(&self,operations:&[BatchOperation])->Orderbook<N,A>{
+        //buyers reach into the asks and sellers into the bids, prices rounded towards the other side
+        let mut buyers=Reach::<N>::new();
+        let mut sellers=Reach::<Reverse<N>>::new();
+        let price=|price:Decimal,side:&Side|N::round_price(price, match side{
+            Side::Bids=>RoundingStrategy::ToPositiveInfinity,
+            Side::Asks=>RoundingStrategy::ToNegativeInfinity
+        }, &self.scale);
+        let quantity=|quantity:Decimal|N::round_quantity(quantity, RoundingStrategy::ToPositiveInfinity, &self.scale);
+        let mut extend=|side:&Side,limit:Option<Decimal>,q:N|match side{
+            Side::Bids=>buyers.extend(limit.map(|l|price(l, side)), q),
+            Side::Asks=>sellers.extend(limit.map(|l|price(l, side)), q)
+        };
+        let mut named=Vec::new();
+        let mut client_order_ids=ClientOrderIds::new();
+        let mut order_history=OrderHistory::new();
+        for operation in operations{
+            match operation{
+                BatchOperation::Place(order)=>{
+                    if let Some(client_order_id)=&order.client_order_id
+                        && let Some(order_id)=self.client_order_ids.get_limit_order(order.user_id, client_order_id){
+                        client_order_ids.limit_orders.entry(order.user_id).or_default().insert(client_order_id.clone(), order_id);
+                        named.push(order_id);
+                        //a closed original can still be the answer to the resubmission
+                        if let Some(closed)=self.order_history.get(order_id){
+                            order_history.archive(closed.clone());
+                        }
+                    }
+                    extend(&order.side, Some(order.price), quantity(order.quantity));
+                },
+                BatchOperation::Modify(request)=>{
+                    named.push(request.order_id);
+                    let q=request.quantity.map_or(N::ZERO, quantity);
+                    match self.resting_order(request.order_id){
+                        Some(o)=>{
+                            let limit=request.price.unwrap_or(o.price.to_price(&self.scale));
+                            extend(&o.side, Some(limit), q.max(o.quantity));
+                        },
+                        //placed earlier in the batch, which already counted its price and quantity but not its side
+                        None=>{
+                            extend(&Side::Bids, request.price, q);
+                            extend(&Side::Asks, request.price, q);
+                        }
+                    }
+                },
+                BatchOperation::Cancel{order_id}=>named.push(*order_id)
+            }
+        }
+        let (mut named_bids,mut named_asks)=(BTreeSet::new(),BTreeSet::new());
+        let (mut named_bid_quantity,mut named_ask_quantity)=(N::ZERO,N::ZERO);
+        for order in named.into_iter().filter_map(|order_id|self.resting_order(order_id)){
+            match order.side{
+                Side::Bids=>{
+                    named_bids.insert(Reverse(order.price));
+                    named_bid_quantity+=order.open_quantity();
+                },
+                Side::Asks=>{
+                    named_asks.insert(order.price);
+                    named_ask_quantity+=order.open_quantity();
+                }
+            }
+        }
+        let mut order_map=HashMap::new();
+        let bids=self.bids.copy_reachable(&sellers, &named_bids, named_bid_quantity, &Side::Bids, &mut order_map);
+        let asks=self.asks.copy_reachable(&buyers, &named_asks, named_ask_quantity, &Side::Asks, &mut order_map);
+        Orderbook{
+            bids,
+            asks,
+            scale:self.scale,
+            order_id_index:self.order_id_index,
+            order_map,
+            trade_log:TradeLog{trade_id_index:self.trade_log.trade_id_index,trades:VecDeque::new()},
+            fee_schedule:self.fee_schedule.clone(),
+            order_history,
+            client_order_ids,
+            price_band:self.price_band.clone(),
+            in_auction:self.in_auction,
+            allocation:self.allocation.clone()
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,69 @@
+use std::collections::BTreeSet;
+use std::mem;
+use rust_decimal::Decimal;
+use crate::orderbook::{numeric::Numeric, price_levels::PriceKey, types::{BookChanges, OpenOrder, Order, PriceLevels, SideChanges}};
+use crate::Orderbook;
+use crate::orderbook::allocation::MatchingAlgorithm;
+
+impl<N> SideChanges<N>{
+    fn new()->SideChanges<N>{
+        SideChanges { prices:BTreeSet::new(), order_ids:BTreeSet::new() }
+    }
+}
+
+//notes an order and its price level as changed, on a side that tracks its changes
+pub(crate) fn note_change<N:Numeric>(changes:&mut Option<SideChanges<N>>,order:&OpenOrder<N>){
+    if let Some(changes)=changes{
+        changes.prices.insert(order.price);
+        changes.order_ids.insert(order.order_id);
+    }
+}
+
+impl<K:PriceKey> PriceLevels<K>{
+    //the orders already resting count as changed, so whoever follows the side starts from all of it
+    fn track_changes(&mut self){
+        if self.changes.is_none(){
+            let prices=self.keys().map(|k|k.price()).collect();
+            let order_ids=self.orders().map(|o|o.order_id).collect();
+            self.changes=Some(SideChanges { prices, order_ids });
+        }
+    }
+
+    fn take_changes(&mut self)->SideChanges<K::Number>{
+        match &mut self.changes{
+            Some(changes)=>mem::replace(changes, SideChanges::new()),
+            None=>SideChanges::new()
+        }
+    }
+}
+
+impl<N:Numeric,A:MatchingAlgorithm> Orderbook<N,A>{
+    //from now on the book remembers which orders and price levels change, until `take_changes` hands them out
+    pub fn track_changes(&mut self){
+        self.bids.track_changes();
+        self.asks.track_changes();
+    }
+
+    //everything that changed since tracking started or the last call, so a copy of the book only redoes that,
+    //always empty for a book that does not track its changes
+    pub fn take_changes(&mut self)->BookChanges{
+        let (bids,asks)=(self.bids.take_changes(),self.asks.take_changes());
+        let orders=bids.order_ids.into_iter().chain(asks.order_ids)
+            .map(|order_id|(order_id,self.resting_order(order_id).map(|o|o.to_decimal(&self.scale))))
+            .collect();
+        BookChanges {
+            orders,
+            bids: self.changed_levels(&self.bids, bids.prices),
+            asks: self.changed_levels(&self.asks, asks.prices)
+        }
+    }
+
+    fn changed_levels<K:PriceKey<Number=N>>(&self,levels:&PriceLevels<K>,prices:BTreeSet<N>)->Vec<(Decimal,Option<Order>)>{
+        prices.into_iter()
+            .map(|price|{
+                let level=levels.level_total(price).map(|(quantity,order_count)|Order { price:price.to_price(&self.scale), quantity:quantity.to_quantity(&self.scale), order_count });
+                (price.to_price(&self.scale),level)
+            })
+            .collect()
+    }
+}
\ No newline at end of file
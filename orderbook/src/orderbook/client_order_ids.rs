@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use crate::orderbook::{clock::current_timestamp, numeric::Numeric, response::{CustomError, DeleteResponse, MarketOrderResponse}, types::{ClientOrderIds, OpenOrder, OrderHandle}};
+use crate::Orderbook;
+use crate::orderbook::allocation::MatchingAlgorithm;
+
+//oldest client order ids are forgotten once this many are held, unless their order is still live
+pub const MAX_CLIENT_ORDER_IDS:usize=100_000;
+//how long after it was placed a closed limit order still answers a resubmission of its client order id,
+//long enough for any retry, after that the id can be used for a new order
+pub const IDEMPOTENCY_WINDOW_MS:u64=60_000;
+
+impl ClientOrderIds{
+    pub fn new()->ClientOrderIds{
+        ClientOrderIds::default()
+    }
+
+    pub fn get_limit_order(&self,user_id:u64,client_order_id:&str)->Option<u64>{
+        self.limit_orders.get(&user_id)?.get(client_order_id).copied()
+    }
+
+    pub fn get_market_order(&self,user_id:u64,client_order_id:&str)->Option<&MarketOrderResponse>{
+        self.market_orders.get(&user_id)?.get(client_order_id)
+    }
+
+    //an id used again points at the new order and keeps its place in the queue, which holds each id once
+    pub(crate) fn insert_limit_order(&mut self,user_id:u64,client_order_id:String,order_id:u64,live_orders:&HashMap<u64,OrderHandle>){
+        if self.limit_orders.entry(user_id).or_default().insert(client_order_id.clone(), order_id).is_none(){
+            self.limit_order_keys.push_back((user_id,client_order_id));
+        }
+        if self.limit_order_keys.len()>MAX_CLIENT_ORDER_IDS
+            && let Some(key)=self.limit_order_keys.pop_front(){
+            match self.get_limit_order(key.0, &key.1){
+                Some(order_id) if live_orders.contains_key(&order_id)=>self.limit_order_keys.push_back(key),
+                _=>forget(&mut self.limit_orders, &key)
+            }
+        }
+    }
+
+    pub(crate) fn insert_market_order(&mut self,user_id:u64,client_order_id:String,response:MarketOrderResponse){
+        self.market_orders.entry(user_id).or_default().insert(client_order_id.clone(), response);
+        self.market_order_keys.push_back((user_id,client_order_id));
+        if self.market_order_keys.len()>MAX_CLIENT_ORDER_IDS
+            && let Some(key)=self.market_order_keys.pop_front(){
+            forget(&mut self.market_orders, &key);
+        }
+    }
+}
+
+fn forget<T>(ids:&mut HashMap<u64,HashMap<String,T>>,(user_id,client_order_id):&(u64,String)){
+    if let Some(user_ids)=ids.get_mut(user_id){
+        user_ids.remove(client_order_id);
+        if user_ids.is_empty(){
+            ids.remove(user_id);
+        }
+    }
+}
+
+impl<N:Numeric,A:MatchingAlgorithm> Orderbook<N,A>{
+    //what a resubmitted limit order gets back instead of being placed: the order its client order id belongs to,
+    //while that order is live or was placed within the idempotency window
+    pub(crate) fn original_limit_order(&self,user_id:u64,client_order_id:&str)->Option<OpenOrder>{
+        let order_id=self.client_order_ids.get_limit_order(user_id, client_order_id)?;
+        match self.resting_order(order_id){
+            Some(o)=>Some(o.to_decimal(&self.scale)),
+            None=>self.order_history.get(order_id)
+                .filter(|o|current_timestamp().saturating_sub(o.created_at)<IDEMPOTENCY_WINDOW_MS)
+                .cloned()
+        }
+    }
+
+    pub fn get_order_by_client_order_id(&self,user_id:u64,client_order_id:&str)->Result<OpenOrder,CustomError>{
+        match self.client_order_ids.get_limit_order(user_id, client_order_id){
+            Some(order_id)=>self.get_order(order_id),
+            None=>Err(CustomError::OrderDoesNotExist)
+        }
+    }
+
+    pub fn delete_order_by_client_order_id(&mut self,user_id:u64,client_order_id:&str)->Result<DeleteResponse,CustomError>{
+        match self.client_order_ids.get_limit_order(user_id, client_order_id){
+            Some(order_id)=>self.delete_order(order_id),
+            None=>Err(CustomError::OrderDoesNotExist)
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,9 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+//milliseconds since the unix epoch, stamped on trades and orders
+pub fn current_timestamp()->u64{
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d|d.as_millis() as u64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,63 @@
+use crate::orderbook::{numeric::Numeric, types::{OpenOrder, OrderHistory}};
+use crate::Orderbook;
+use crate::orderbook::allocation::MatchingAlgorithm;
+
+pub const DEFAULT_ORDER_HISTORY_LIMIT:usize=100;
+//oldest terminal orders are forgotten once the history grows past this
+pub const MAX_ORDER_HISTORY_SIZE:usize=100_000;
+
+impl OrderHistory{
+    pub fn new()->OrderHistory{
+        OrderHistory::default()
+    }
+
+    pub(crate) fn archive(&mut self,order:OpenOrder){
+        self.archived_order_ids.push_back(order.order_id);
+        self.archived_total+=1;
+        self.orders.insert(order.order_id, order);
+        if self.archived_order_ids.len()>MAX_ORDER_HISTORY_SIZE
+            && let Some(order_id)=self.archived_order_ids.pop_front(){
+            self.orders.remove(&order_id);
+        }
+    }
+
+    pub fn get(&self,order_id:u64)->Option<&OpenOrder>{
+        self.orders.get(&order_id)
+    }
+
+    //orders archived after the first `archived_total` ones, oldest first, minus any forgotten already
+    pub fn archived_since(&self,archived_total:u64)->impl Iterator<Item=&OpenOrder>{
+        let count=self.archived_total.saturating_sub(archived_total).min(self.archived_order_ids.len() as u64) as usize;
+        self.archived_order_ids
+            .iter()
+            .skip(self.archived_order_ids.len()-count)
+            .filter_map(|id|self.orders.get(id))
+    }
+
+    //most recently closed first
+    pub fn for_user(&self,user_id:u64,limit:usize)->Vec<OpenOrder>{
+        self.archived_order_ids
+            .iter()
+            .rev()
+            .filter_map(|id|self.orders.get(id))
+            .filter(|o|o.user_id==user_id)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+impl<N:Numeric,A:MatchingAlgorithm> Orderbook<N,A>{
+    pub fn get_open_orders(&self,user_id:u64)->Vec<OpenOrder>{
+        let mut orders:Vec<OpenOrder>=self.resting_orders()
+            .filter(|o|o.user_id==user_id)
+            .map(|o|o.to_decimal(&self.scale))
+            .collect();
+        orders.sort_by_key(|o|o.order_id);
+        orders
+    }
+
+    pub fn get_order_history(&self,user_id:u64,limit:usize)->Vec<OpenOrder>{
+        self.order_history.for_user(user_id, limit)
+    }
+}
\ No newline at end of file
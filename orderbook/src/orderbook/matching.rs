@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::orderbook::allocation::MatchingAlgorithm;
+use crate::orderbook::numeric::Numeric;
+use crate::orderbook::price_levels::PriceKey;
+use crate::orderbook::types::{FeeSchedule, OpenOrder, OrderHandle, OrderHistory, PriceLevels, Side, Taker, TradeLog};
+use crate::Orderbook;
+
+//what an incoming order may trade against and how each level it reaches is shared out
+pub trait MatchingPolicy<N:Numeric>{
+    //worst price the order may trade at, none to walk the whole opposite side
+    fn limit_price(&self)->Option<N>;
+    //one fill per resting order of a level in queue order, as `MatchingAlgorithm::allocate`
+    fn allocate(&self,open_quantities:&[N],quantity:N)->Vec<N>;
+    //whether a level is simply filled front to back, so matching can stop at the last order it needs
+    fn in_time_priority(&self)->bool;
+}
+
+//takes liquidity up to its price, whatever is left rests
+pub struct LimitPolicy<N:Numeric,A:MatchingAlgorithm>{
+    pub price: N,
+    pub allocation: A,
+    pub scale: N::Scale
+}
+
+//takes liquidity at any price, the quantity is already cut down to what its protection allows
+pub struct MarketPolicy<N:Numeric,A:MatchingAlgorithm>{
+    pub allocation: A,
+    pub scale: N::Scale
+}
+
+impl<N:Numeric,A:MatchingAlgorithm> MatchingPolicy<N> for LimitPolicy<N,A>{
+    fn limit_price(&self)->Option<N>{
+        Some(self.price)
+    }
+
+    fn allocate(&self,open_quantities:&[N],quantity:N)->Vec<N>{
+        self.allocation.allocate(open_quantities, quantity, &self.scale)
+    }
+
+    fn in_time_priority(&self)->bool{
+        self.allocation.in_time_priority()
+    }
+}
+
+impl<N:Numeric,A:MatchingAlgorithm> MatchingPolicy<N> for MarketPolicy<N,A>{
+    fn limit_price(&self)->Option<N>{
+        None
+    }
+
+    fn allocate(&self,open_quantities:&[N],quantity:N)->Vec<N>{
+        self.allocation.allocate(open_quantities, quantity, &self.scale)
+    }
+
+    fn in_time_priority(&self)->bool{
+        self.allocation.in_time_priority()
+    }
+}
+
+//quantity and value traded by one incoming order
+#[derive(Debug,Default,Clone,Copy,PartialEq)]
+pub(crate) struct Execution<N:Numeric>{
+    pub quantity: N,
+    pub notional: N::Notional
+}
+
+impl<N:Numeric> Execution<N>{
+    //volume weighted, none when nothing traded
+    pub fn average_price(&self,scale:&N::Scale)->Option<Decimal>{
+        if self.quantity>N::ZERO{
+            Some(N::notional_to_decimal(self.notional, scale)/self.quantity.to_quantity(scale))
+        }else{
+            None
+        }
+    }
+}
+
+//the parts of the book a fill has to keep up to date besides the levels
+pub(crate) struct Ledger<'a,N:Numeric>{
+    pub trade_log: &'a mut TradeLog,
+    pub fee_schedule: &'a FeeSchedule,
+    pub order_map: &'a mut HashMap<u64,OrderHandle>,
+    pub order_history: &'a mut OrderHistory,
+    pub scale: &'a N::Scale
+}
+
+impl<K:PriceKey> PriceLevels<K>{
+    //trades up to `quantity` from the best level down, the only loop every order type goes through
+    pub(crate) fn take(&mut self,mut quantity:K::Number,policy:&impl MatchingPolicy<K::Number>,taker:&Taker,ledger:&mut Ledger<K::Number>)->Execution<K::Number>{
+        let mut execution=Execution::default();
+        let limit=policy.limit_price().map(K::from_price);
+        while quantity>K::Number::ZERO{
+            let Some((&key,level))=self.levels.first_key_value() else{
+                break
+            };
+            if limit.is_some_and(|l|key>l){
+                break
+            }
+            let price=key.price();
+            let trade_price=price.to_price(ledger.scale);
+            let mut traded=K::Number::ZERO;
+            let mut next=level.head;
+            if policy.in_time_priority(){
+                //front to back, the orders behind the last one needed are never looked at
+                while traded<quantity
+                    && let Some(slot)=next
+                    && let Some(resting)=self.orders.get(slot){
+                    next=resting.next;
+                    let fill=(quantity-traded).min(resting.order.open_quantity().max(K::Number::ZERO));
+                    traded+=self.fill_resting(slot, fill, trade_price, taker, ledger);
+                }
+            }else{
+                let mut slots=Vec::new();
+                let mut open_quantities:Vec<K::Number>=Vec::new();
+                while let Some(resting)=next.and_then(|slot|self.orders.get(slot)){
+                    slots.extend(next);
+                    open_quantities.push(resting.order.open_quantity().max(K::Number::ZERO));
+                    next=resting.next;
+                }
+                for (slot,fill) in slots.into_iter().zip(policy.allocate(&open_quantities, quantity)){
+                    traded+=self.fill_resting(slot, fill, trade_price, taker, ledger);
+                }
+            }
+            quantity-=traded;
+            execution.quantity+=traded;
+            execution.notional+=K::Number::notional(price, traded);
+            if self.levels.contains_key(&key){
+                //the level still has quantity, so the policy handed out all it was going to
+                break
+            }
+        }
+        execution
+    }
+
+    //trades `fill` against the order in the slot and takes it off the book once it is done. an order left with
+    //nothing open is closed as filled when it is reached, one with quantity open keeps its place on a zero fill
+    fn fill_resting(&mut self,slot:usize,fill:K::Number,trade_price:Decimal,taker:&Taker,ledger:&mut Ledger<K::Number>)->K::Number{
+        let Some(o)=self.get_mut(slot) else{
+            return K::Number::ZERO
+        };
+        if fill<=K::Number::ZERO && o.open_quantity()>K::Number::ZERO{
+            return K::Number::ZERO
+        }
+        let fill=fill.max(K::Number::ZERO);
+        if fill>K::Number::ZERO{
+            ledger.trade_log.record_at(trade_price, ledger.fee_schedule, o, taker, fill.to_quantity(ledger.scale));
+        }
+        o.fill(fill);
+        if o.is_terminal()
+            && let Some(filled)=self.remove(slot){
+            ledger.order_map.remove(&filled.order_id);
+            ledger.order_history.archive(filled.into_decimal(ledger.scale));
+        }
+        fill
+    }
+}
+
+impl<N:Numeric,A:MatchingAlgorithm> Orderbook<N,A>{
+    //matches an incoming order against the opposite side of the book
+    pub(crate) fn take_liquidity(&mut self,taker:&Taker,quantity:N,policy:&impl MatchingPolicy<N>)->Execution<N>{
+        let mut ledger=Ledger{
+            trade_log:&mut self.trade_log,
+            fee_schedule:&self.fee_schedule,
+            order_map:&mut self.order_map,
+            order_history:&mut self.order_history,
+            scale:&self.scale
+        };
+        match taker.side{
+            Side::Bids=>self.asks.take(quantity, policy, taker, &mut ledger),
+            Side::Asks=>self.bids.take(quantity, policy, taker, &mut ledger)
+        }
+    }
+
+    //queues an order at the back of its level and indexes its slot
+    pub(crate) fn rest_order(&mut self,order:OpenOrder<N>){
+        let order_id=order.order_id;
+        let side=order.side.clone();
+        let slot=match side{
+            Side::Bids=>self.bids.push_back(order),
+            Side::Asks=>self.asks.push_back(order)
+        };
+        self.order_map.insert(order_id, OrderHandle { side, slot });
+    }
+
+    //takes a live order out of both its level and the index
+    pub(crate) fn remove_resting_order(&mut self,order_id:u64)->Option<OpenOrder<N>>{
+        let OrderHandle { side, slot }=self.order_map.remove(&order_id)?;
+        match side{
+            Side::Bids=>self.bids.remove(slot),
+            Side::Asks=>self.asks.remove(slot)
+        }
+    }
+
+    pub(crate) fn resting_order(&self,order_id:u64)->Option<&OpenOrder<N>>{
+        let handle=self.order_map.get(&order_id)?;
+        match handle.side{
+            Side::Bids=>self.bids.get(handle.slot),
+            Side::Asks=>self.asks.get(handle.slot)
+        }
+    }
+
+    pub(crate) fn resting_order_mut(&mut self,order_id:u64)->Option<&mut OpenOrder<N>>{
+        let handle=self.order_map.get(&order_id)?;
+        match handle.side{
+            Side::Bids=>self.bids.get_mut(handle.slot),
+            Side::Asks=>self.asks.get_mut(handle.slot)
+        }
+    }
+
+    //every live order on both sides in no particular order
+    pub(crate) fn resting_orders(&self)->impl Iterator<Item=&OpenOrder<N>>{
+        self.bids.orders().chain(self.asks.orders())
+    }
+}
\ No newline at end of file
@@ -1,3 +1,17 @@
 pub mod types;
+#[allow(clippy::module_inception)]
 pub mod orderbook;
-pub mod response;
\ No newline at end of file
+pub mod response;
+pub mod trades;
+pub mod clock;
+pub mod history;
+pub mod batch;
+pub mod client_order_ids;
+pub mod price_bands;
+pub mod protection;
+pub mod auction;
+pub mod allocation;
+pub mod price_levels;
+pub mod matching;
+pub mod numeric;
+pub mod changes;
\ No newline at end of file
@@ -0,0 +1,262 @@
+use std::fmt::Debug;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+use rust_decimal::{dec, prelude::ToPrimitive, Decimal, RoundingStrategy};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+//what the book stores and matches prices and quantities in, orders come in and go out as `Decimal`
+pub trait Numeric:Copy+Ord+Default+Debug+Send+Sync+'static
+    +Add<Output=Self>+Sub<Output=Self>+AddAssign+SubAssign+Sum
+    +Serialize+DeserializeOwned{
+    //what turns a value back into a price or a quantity, nothing for `Decimal`
+    type Scale:Copy+Debug+PartialEq+Send+Sync+Serialize+DeserializeOwned;
+    //price times quantity, wide enough to add up a whole book
+    type Notional:Copy+Default+PartialOrd+Add<Output=Self::Notional>+Sub<Output=Self::Notional>+AddAssign+SubAssign;
+
+    const ZERO:Self;
+
+    //none for a price that is not a whole number of ticks or out of range
+    fn from_price(price:Decimal,scale:&Self::Scale)->Option<Self>;
+    //none for a quantity that is not a whole number of lots or out of range
+    fn from_quantity(quantity:Decimal,scale:&Self::Scale)->Option<Self>;
+    //the closest price in the direction given, for limits worked out in decimals
+    fn round_price(price:Decimal,strategy:RoundingStrategy,scale:&Self::Scale)->Self;
+    fn round_quantity(quantity:Decimal,strategy:RoundingStrategy,scale:&Self::Scale)->Self;
+    fn to_price(self,scale:&Self::Scale)->Decimal;
+    fn to_quantity(self,scale:&Self::Scale)->Decimal;
+
+    fn notional(price:Self,quantity:Self)->Self::Notional;
+    fn notional_to_decimal(notional:Self::Notional,scale:&Self::Scale)->Decimal;
+    fn round_notional(notional:Decimal,strategy:RoundingStrategy,scale:&Self::Scale)->Self::Notional;
+    //quantity the notional buys at the price, rounded down
+    fn quantity_for(notional:Self::Notional,price:Self)->Self;
+
+    //`self * numerator / denominator` rounded down
+    fn mul_div(self,numerator:Self,denominator:Self)->Self;
+    //`self * bps / 10_000` rounded down
+    fn bps(self,bps:u32)->Self;
+    //rounded down to a whole multiple of a step above zero
+    fn floor_to(self,step:Self)->Self;
+}
+
+const BPS:u32=10_000;
+//largest price, quantity and quote budget an order may carry, far enough inside `Decimal` that no sum or product the book works out overflows
+pub const MAX_PRICE:Decimal=dec!(1_000_000_000_000);
+pub const MAX_QUANTITY:Decimal=dec!(1_000_000_000_000_000);
+pub const MAX_NOTIONAL:Decimal=dec!(1_000_000_000_000_000_000_000_000_000);
+
+impl Numeric for Decimal{
+    type Scale=();
+    type Notional=Decimal;
+
+    const ZERO:Self=Decimal::ZERO;
+
+    fn from_price(price:Decimal,_:&())->Option<Self>{
+        (price<=MAX_PRICE).then_some(price)
+    }
+
+    fn from_quantity(quantity:Decimal,_:&())->Option<Self>{
+        (quantity<=MAX_QUANTITY).then_some(quantity)
+    }
+
+    fn round_price(price:Decimal,_:RoundingStrategy,_:&())->Self{
+        price
+    }
+
+    fn round_quantity(quantity:Decimal,_:RoundingStrategy,_:&())->Self{
+        quantity
+    }
+
+    fn to_price(self,_:&())->Decimal{
+        self
+    }
+
+    fn to_quantity(self,_:&())->Decimal{
+        self
+    }
+
+    //a whole level at the largest price can be past the range, it saturates like the fixed point sums
+    fn notional(price:Self,quantity:Self)->Decimal{
+        price.checked_mul(quantity).unwrap_or(Decimal::MAX)
+    }
+
+    fn notional_to_decimal(notional:Decimal,_:&())->Decimal{
+        notional
+    }
+
+    fn round_notional(notional:Decimal,_:RoundingStrategy,_:&())->Decimal{
+        notional
+    }
+
+    fn quantity_for(notional:Decimal,price:Self)->Self{
+        notional/price
+    }
+
+    fn mul_div(self,numerator:Self,denominator:Self)->Self{
+        match self.checked_mul(numerator){
+            Some(product)=>product/denominator,
+            None=>self*(numerator/denominator)
+        }
+    }
+
+    fn bps(self,bps:u32)->Self{
+        self*Decimal::from(bps)/Decimal::from(BPS)
+    }
+
+    fn floor_to(self,step:Self)->Self{
+        (self/step).floor()*step
+    }
+}
+
+//a whole number of the market's ticks for prices and of its lots for quantities
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,PartialOrd,Ord,Hash,Serialize,Deserialize)]
+pub struct FixedPoint(pub i64);
+
+//smallest price and quantity increments of a market, both above zero
+#[derive(Clone,Copy,Debug,PartialEq,Serialize,Deserialize)]
+pub struct FixedPointScale{
+    pub tick_size: Decimal,
+    pub lot_size: Decimal
+}
+
+impl FixedPointScale{
+    pub fn new(tick_size:Decimal,lot_size:Decimal)->FixedPointScale{
+        FixedPointScale { tick_size, lot_size }
+    }
+}
+
+//none when not a whole number of units or out of range
+fn units(value:Decimal,unit:Decimal)->Option<i64>{
+    let units=value.checked_div(unit)?;
+    if units.fract()!=Decimal::ZERO{
+        return None
+    }
+    units.to_i64()
+}
+
+//a power of ten unit only sets the decimal point, the usual case and much cheaper than multiplying
+fn from_units(units:i64,unit:Decimal)->Decimal{
+    if unit.mantissa()==1{
+        Decimal::new(units, unit.scale())
+    }else{
+        Decimal::from(units)*unit
+    }
+}
+
+//saturated when out of range
+fn rounded_units(value:Decimal,unit:Decimal,strategy:RoundingStrategy)->i64{
+    match value.checked_div(unit){
+        Some(units)=>units.round_dp_with_strategy(0, strategy).to_i64().unwrap_or(if units>Decimal::ZERO{i64::MAX}else{i64::MIN}),
+        None if value>=Decimal::ZERO=>i64::MAX,
+        None=>i64::MIN
+    }
+}
+
+//saturating, a market's thread must not panic on a sum past the range however many orders add up to it
+impl Add for FixedPoint{
+    type Output=FixedPoint;
+
+    fn add(self,other:FixedPoint)->FixedPoint{
+        FixedPoint(self.0.saturating_add(other.0))
+    }
+}
+
+impl Sub for FixedPoint{
+    type Output=FixedPoint;
+
+    fn sub(self,other:FixedPoint)->FixedPoint{
+        FixedPoint(self.0.saturating_sub(other.0))
+    }
+}
+
+impl AddAssign for FixedPoint{
+    fn add_assign(&mut self,other:FixedPoint){
+        *self=*self+other;
+    }
+}
+
+impl SubAssign for FixedPoint{
+    fn sub_assign(&mut self,other:FixedPoint){
+        *self=*self-other;
+    }
+}
+
+impl Sum for FixedPoint{
+    fn sum<I:Iterator<Item=FixedPoint>>(iter:I)->FixedPoint{
+        iter.fold(FixedPoint(0), |total,v|total+v)
+    }
+}
+
+impl<'a> Sum<&'a FixedPoint> for FixedPoint{
+    fn sum<I:Iterator<Item=&'a FixedPoint>>(iter:I)->FixedPoint{
+        iter.copied().sum()
+    }
+}
+
+//notionals are counted in ticks times lots, an i128 holds any product of two i64
+impl Numeric for FixedPoint{
+    type Scale=FixedPointScale;
+    type Notional=i128;
+
+    const ZERO:Self=FixedPoint(0);
+
+    fn from_price(price:Decimal,scale:&FixedPointScale)->Option<Self>{
+        (price<=MAX_PRICE).then(||units(price, scale.tick_size)).flatten().map(FixedPoint)
+    }
+
+    fn from_quantity(quantity:Decimal,scale:&FixedPointScale)->Option<Self>{
+        (quantity<=MAX_QUANTITY).then(||units(quantity, scale.lot_size)).flatten().map(FixedPoint)
+    }
+
+    fn round_price(price:Decimal,strategy:RoundingStrategy,scale:&FixedPointScale)->Self{
+        FixedPoint(rounded_units(price, scale.tick_size, strategy))
+    }
+
+    fn round_quantity(quantity:Decimal,strategy:RoundingStrategy,scale:&FixedPointScale)->Self{
+        FixedPoint(rounded_units(quantity, scale.lot_size, strategy))
+    }
+
+    fn to_price(self,scale:&FixedPointScale)->Decimal{
+        from_units(self.0, scale.tick_size)
+    }
+
+    fn to_quantity(self,scale:&FixedPointScale)->Decimal{
+        from_units(self.0, scale.lot_size)
+    }
+
+    fn notional(price:Self,quantity:Self)->i128{
+        price.0 as i128*quantity.0 as i128
+    }
+
+    fn notional_to_decimal(notional:i128,scale:&FixedPointScale)->Decimal{
+        Decimal::try_from_i128_with_scale(notional, 0)
+            .map(|n|n*scale.tick_size*scale.lot_size)
+            .unwrap_or(if notional>0{Decimal::MAX}else{Decimal::MIN})
+    }
+
+    fn round_notional(notional:Decimal,strategy:RoundingStrategy,scale:&FixedPointScale)->i128{
+        notional.checked_div(scale.tick_size*scale.lot_size)
+            .and_then(|n|n.round_dp_with_strategy(0, strategy).to_i128())
+            .unwrap_or(if notional>=Decimal::ZERO{i128::MAX}else{i128::MIN})
+    }
+
+    fn quantity_for(notional:i128,price:Self)->Self{
+        if price.0==0{
+            return FixedPoint(i64::MAX)
+        }
+        FixedPoint((notional/price.0 as i128).clamp(i64::MIN as i128, i64::MAX as i128) as i64)
+    }
+
+    fn mul_div(self,numerator:Self,denominator:Self)->Self{
+        FixedPoint((self.0 as i128*numerator.0 as i128/denominator.0 as i128) as i64)
+    }
+
+    fn bps(self,bps:u32)->Self{
+        FixedPoint((self.0 as i128*bps as i128/BPS as i128) as i64)
+    }
+
+    fn floor_to(self,step:Self)->Self{
+        FixedPoint(self.0.div_euclid(step.0)*step.0)
+    }
+}
\ No newline at end of file
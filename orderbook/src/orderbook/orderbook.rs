@@ -1,39 +1,48 @@
-use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::collections::HashMap;
 use rust_decimal::{dec, Decimal};
-use crate::{orderbook::{response::{CustomError,DeleteResponse,ErrorResponse, MarketOrderResponse,ModifyOrderResponse}, types::{Depth, ModifyOrderRequest, OpenOrder, Order, Side}}, LimitOrder, MarketOrder, Orderbook};
-use std::cmp::Reverse;
+use crate::{orderbook::{response::{CustomError,DeleteResponse, MarketOrderResponse,ModifyOrderResponse}, types::{Allocation, CancelFilter, ClientOrderIds, Depth, FeeSchedule, ModifyOrderRequest, OpenOrder, Order, OrderHistory, OrderStatus, PriceBand, PriceLevels, Side, Taker, TradeLog}}, LimitOrder, MarketOrder, Orderbook};
+use crate::orderbook::allocation::MatchingAlgorithm;
+use crate::orderbook::matching::{LimitPolicy, MarketPolicy};
+use crate::orderbook::numeric::Numeric;
+use crate::orderbook::price_levels::PriceKey;
+use crate::orderbook::clock::current_timestamp;
+
+impl Default for Orderbook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl Orderbook{
     pub fn new()->Orderbook{
-        Orderbook{
-            asks:BTreeMap::new(),
-            bids: BTreeMap::new(),
-            order_id_index:0,
-            order_map:HashMap::new()
-        }
+        Orderbook::with_scale(())
     }
+}
 
-    fn clear_empty_bids_or_asks(&mut self){
-        let mut to_be_removed: Vec<Reverse<Decimal>>=Vec::new();
-        for (price,_) in self.bids.iter(){
-            if self.bids.get(price).unwrap().is_empty(){
-                to_be_removed.push(*price);
-            }
-        }
-        for i in to_be_removed.iter(){
-            self.bids.remove(i);
-        }
+impl<N:Numeric> Orderbook<N>{
+    //an empty book keeping its prices and quantities as `N`, each level filled in time priority until told otherwise
+    pub fn with_scale(scale:N::Scale)->Orderbook<N>{
+        Orderbook::with_algorithm(scale, Allocation::default())
+    }
+}
 
-        let mut to_be_removed_asks: Vec<Decimal>=Vec::new();
-        for (price,_) in self.asks.iter(){
-            if self.asks.get(price).unwrap().is_empty(){
-                to_be_removed_asks.push(*price);
-            }
-        }
-        for j in to_be_removed_asks.iter(){
-            self.asks.remove(j);
+impl<N:Numeric,A:MatchingAlgorithm> Orderbook<N,A>{
+    //an empty book sharing each price level out with `allocation`
+    pub fn with_algorithm(scale:N::Scale,allocation:A)->Orderbook<N,A>{
+        Orderbook{
+            asks:PriceLevels::new(),
+            bids:PriceLevels::new(),
+            scale,
+            order_id_index:0,
+            order_map:HashMap::new(),
+            trade_log:TradeLog::new(),
+            fee_schedule:FeeSchedule::default(),
+            order_history:OrderHistory::new(),
+            client_order_ids:ClientOrderIds::new(),
+            price_band:PriceBand::new(),
+            in_auction:false,
+            allocation
         }
-
     }
 
     fn is_bids_empty(&self)->bool{
@@ -44,42 +53,42 @@ impl Orderbook{
         self.asks.is_empty()
     }
 
-    pub fn get_best_bid(&self)->Option<&Reverse<Decimal>>{
-        self.bids.keys().next()
+    pub fn get_best_bid(&self)->Option<Decimal>{
+        self.bids.best_price().map(|p|p.to_price(&self.scale))
     }
 
-    pub fn get_best_ask(&self)->Option<&Decimal>{
-        self.asks.keys().next()
+    pub fn get_best_ask(&self)->Option<Decimal>{
+        self.asks.best_price().map(|p|p.to_price(&self.scale))
     }
 
-    pub fn get_worst_bid(&self)->Option<&Reverse<Decimal>>{
-        self.bids.keys().last()
+    pub fn get_worst_bid(&self)->Option<Decimal>{
+        self.bids.keys().last().map(|p|p.price().to_price(&self.scale))
     }
 
-    pub fn get_worst_ask(&self)->Option<&Decimal>{
-        self.asks.keys().last()
+    pub fn get_worst_ask(&self)->Option<Decimal>{
+        self.asks.keys().last().map(|p|p.price().to_price(&self.scale))
     }
 
     pub fn get_spread(&self)->Option<Decimal>{
-        let best_ask = self.get_best_ask()?.to_owned();
-        let best_bid = self.get_best_bid()?.0;
+        let best_ask = self.get_best_ask()?;
+        let best_bid = self.get_best_bid()?;
         Some(best_ask-best_bid)
     }
 
     pub fn mid_price(&self)->Option<Decimal>{
-        let best_ask = self.get_best_ask()?.to_owned();
-        let best_bid = self.get_best_bid()?.0;
-        return Some((best_ask+best_bid)/dec!(2));
+        let best_ask = self.get_best_ask()?;
+        let best_bid = self.get_best_bid()?;
+        Some((best_ask+best_bid)/dec!(2))
     }
 
-    pub fn get_order(&self,order_id:u64)->Result<OpenOrder,ErrorResponse>{
-        let order =self.order_map.get(&order_id);
+    pub fn get_order(&self,order_id:u64)->Result<OpenOrder,CustomError>{
+        let order =self.resting_order(order_id).map(|o|o.to_decimal(&self.scale)).or_else(||self.order_history.get(order_id).cloned());
         match order{
             Some(o)=>{
-                return Ok(o.clone())
+                Ok(o)
             },
             None=>{
-                return Err(ErrorResponse::new(CustomError::OrderDoesNotExist))
+                Err(CustomError::OrderDoesNotExist)
             }
         }
     }
@@ -96,7 +105,7 @@ impl Orderbook{
             return bids
         }
         for (price,orders) in self.bids.iter(){
-            bids.push(Order{price:price.0,quantity:orders.iter().map(|v|v.quantity-v.quantity_filled).sum(),order_count:orders.iter().count() as u64})
+            bids.push(Order{price:price.0.to_price(&self.scale),quantity:orders.map(|v|v.open_quantity()).sum::<N>().to_quantity(&self.scale),order_count:self.bids.levels.get(price).map_or(0, |l|l.order_count)})
         }
 
         bids
@@ -108,454 +117,277 @@ impl Orderbook{
             return asks;
         }
         for (price,orders) in self.asks.iter(){
-            asks.push(Order{price:*price,quantity:orders.iter().map(|v|v.quantity-v.quantity_filled).sum(),order_count:orders.iter().count() as u64})
+            asks.push(Order{price:price.to_price(&self.scale),quantity:orders.map(|v|v.open_quantity()).sum::<N>().to_quantity(&self.scale),order_count:self.asks.levels.get(price).map_or(0, |l|l.order_count)})
         }
         asks
     }
 
-    pub fn delete_order(&mut self,order_id:u64)->Result<DeleteResponse,ErrorResponse>{
-        let order=self.order_map.get(&order_id);
-        if let Some(o)=order{
-            let side=o.side.clone();
-            let price = o.price;
-            if o.quantity==o.quantity_filled{
-                return Err(ErrorResponse::new(CustomError::OrderAlreadyMatched))
-            }
-            let response =match side{
-                Side::Asks=>{
-                    let open_orders=self.asks.get_mut(&price).unwrap();
-                    let (price,quantity,quantity_filled)=open_orders
-                        .iter()
-                        .find(|v|v.order_id==order_id)
-                        .map(|v|(v.price,v.quantity,v.quantity_filled))
-                        .unwrap();
-                    
-                    open_orders.retain(|v|v.order_id!=order_id);
-                    DeleteResponse::new(price, quantity, quantity_filled, order_id)
-                },
-                Side::Bids=>{
-                    let open_orders=self.bids.get_mut(&Reverse(price)).unwrap();
-                    let (price,quantity,quantity_filled)=open_orders
-                        .iter()
-                        .find(|v|v.order_id==order_id)
-                        .map(|v|(v.price,v.quantity,v.quantity_filled))
-                        .unwrap();
-                    open_orders.retain(|v|v.order_id!=order_id);
-                    DeleteResponse::new(price, quantity, quantity_filled, order_id)
-                }
-            };
-            self.clear_empty_bids_or_asks();
-            Ok(response)
-        }else{
-            let err = CustomError::OrderDoesNotExist;
-            Err(ErrorResponse::new(err))
-        }   
+    //error for an order id that is no longer live in the book
+    pub(crate) fn closed_order_error(&self,order_id:u64)->CustomError{
+        match self.order_history.get(order_id).map(|o|&o.status){
+            Some(OrderStatus::Filled)=>CustomError::OrderAlreadyMatched,
+            Some(_)=>CustomError::OrderAlreadyClosed,
+            None=>CustomError::OrderDoesNotExist
+        }
     }
 
-    pub fn modify_order(&mut self,modify_order_request:ModifyOrderRequest)->Result<ModifyOrderResponse,ErrorResponse>{
-        let order = self.order_map.get_mut(&modify_order_request.order_id);
-        if let Some(o)=order{
-            let side=o.side.clone();
-            let price = o.price;
-            if o.quantity==o.quantity_filled{
-                return Err(ErrorResponse::new(CustomError::OrderAlreadyMatched));
+    pub fn delete_order(&mut self,order_id:u64)->Result<DeleteResponse,CustomError>{
+        match self.remove_resting_order(order_id){
+            Some(o)=>{
+                let mut o=o.into_decimal(&self.scale);
+                let response=DeleteResponse::new(o.price, o.quantity, o.quantity_filled, order_id);
+                o.cancel();
+                self.order_history.archive(o);
+                Ok(response)
+            },
+            None=>Err(self.closed_order_error(order_id))
+        }
+    }
+
+    //whether the order, live or closed, was placed by someone other than the user
+    pub(crate) fn is_foreign_order(&self,user_id:u64,order_id:u64)->bool{
+        let owner=self.resting_order(order_id).map(|o|o.user_id).or_else(||self.order_history.get(order_id).map(|o|o.user_id));
+        owner.is_some_and(|owner|owner!=user_id)
+    }
+
+    //cancels the order only if it is the user's, another user's order is reported as missing so order ids cannot be probed
+    pub fn delete_user_order(&mut self,user_id:u64,order_id:u64)->Result<DeleteResponse,CustomError>{
+        if self.is_foreign_order(user_id, order_id){
+            return Err(CustomError::OrderDoesNotExist)
+        }
+        self.delete_order(order_id)
+    }
+
+    //cancels every live order matching the filter, oldest first
+    pub fn cancel_all(&mut self,filter:CancelFilter)->Vec<DeleteResponse>{
+        let mut order_ids:Vec<u64>=self.resting_orders()
+            .filter(|o|filter.matches(o, &self.scale))
+            .map(|o|o.order_id)
+            .collect();
+        order_ids.sort();
+        order_ids
+            .into_iter()
+            .filter_map(|order_id|self.delete_order(order_id).ok())
+            .collect()
+    }
+
+    //a new price sends the order to the back of its new level, where it may trade like a fresh order
+    pub fn modify_order(&mut self,modify_order_request:ModifyOrderRequest)->Result<ModifyOrderResponse,CustomError>{
+        let order_id=modify_order_request.order_id;
+        let Some(o)=self.resting_order(order_id) else{
+            return Err(self.closed_order_error(order_id))
+        };
+        //the new values have to sit on the market's ticks and lots like a new order's
+        //and a new price is held to the band, as it may trade straight away
+        let price=match modify_order_request.price{
+            Some(price)=>self.band_price(&o.side, price)?,
+            None=>o.price
+        };
+        let quantity=match modify_order_request.quantity{
+            Some(quantity) if quantity<Decimal::ZERO=>return Err(CustomError::InvalidQuantity),
+            Some(quantity)=>N::from_quantity(quantity, &self.scale).ok_or(CustomError::InvalidQuantity)?,
+            None=>o.quantity
+        };
+        if quantity<o.quantity_filled{
+            return Err(CustomError::ModifyQuantityCannotBeLesserThanFilledQuantity)
+        }
+        let response=ModifyOrderResponse::new(price.to_price(&self.scale), quantity.to_quantity(&self.scale), order_id);
+        let updated_at=current_timestamp();
+        if price==o.price && quantity>o.quantity_filled{
+            //same level, the order keeps its place in the queue
+            if let Some(order)=self.resting_order_mut(order_id){
+                order.quantity=quantity;
+                order.updated_at=updated_at;
             }
-            let response =match side{
-                Side::Asks=>{
-                    let open_orders=self.asks.get_mut(&price).unwrap();
-                    let open_order =open_orders
-                        .iter_mut()
-                        .find(|v|v.order_id==modify_order_request.order_id)
-                        .unwrap();
-                    match modify_order_request.price{
-                        Some(x)=>{open_order.price=x},
-                        None=>{}
-                    }
-                    match modify_order_request.quantity{
-                        Some(x)=>{
-                            if x >=open_order.quantity_filled{
-                                open_order.quantity=x;
-                            }
-                            else{
-                                return Err(ErrorResponse::new(CustomError::ModifyQuantityCannotBeLesserThanFilledQuantity))
-                            }
-                        },
-                        None=>{}
-                    }
-                    let open_order =open_orders
-                        .iter_mut()
-                        .find(|v|v.order_id==modify_order_request.order_id)
-                        .unwrap();
-                    o.price=open_order.price;
-                    o.quantity=open_order.quantity;
-                    ModifyOrderResponse::new(open_order.price, open_order.quantity, open_order.order_id)
-                },
-                Side::Bids=>{
-                    let open_orders=self.bids.get_mut(&Reverse(price)).unwrap();
-                    let open_order =open_orders
-                        .iter_mut()
-                        .find(|v|v.order_id==modify_order_request.order_id)
-                        .unwrap();
-                    match modify_order_request.price{
-                        Some(x)=>{open_order.price=x},
-                        None=>{}
-                    }
-                    match modify_order_request.quantity{
-                        Some(x)=>{
-                            if x >=open_order.quantity_filled{
-                                open_order.quantity=x;
-                            }
-                            else{
-                                return Err(ErrorResponse::new(CustomError::ModifyQuantityCannotBeLesserThanFilledQuantity))
-                            }
-                        },
-                        None=>{}
-                    }
-                    let open_order =open_orders
-                        .iter_mut()
-                        .find(|v|v.order_id==modify_order_request.order_id)
-                        .unwrap();
-                    o.price=open_order.price;
-                    o.quantity=open_order.quantity;
-                    ModifyOrderResponse::new(open_order.price, open_order.quantity, open_order.order_id)
-                }
-            };
-            self.clear_empty_bids_or_asks();
             return Ok(response)
+        }
+        let Some(mut order)=self.remove_resting_order(order_id) else{
+            return Err(self.closed_order_error(order_id))
+        };
+        order.price=price;
+        order.quantity=quantity;
+        order.updated_at=updated_at;
+        let last_trade_id=self.trade_log.trade_id_index;
+        if !self.in_auction && order.quantity>order.quantity_filled{
+            let taker=Taker{order_id:Some(order_id),user_id:order.user_id,side:order.side.clone()};
+            let policy=LimitPolicy{price,allocation:self.allocation.clone(),scale:self.scale};
+            let execution=self.take_liquidity(&taker, order.quantity-order.quantity_filled, &policy);
+            if execution.quantity>N::ZERO{
+                order.fill(execution.quantity);
+            }
+        }
+        self.observe_trades_since(last_trade_id);
+        if order.quantity_filled>=order.quantity{
+            //cutting the quantity down to what has already traded closes the order
+            if order.status!=OrderStatus::Filled{
+                order.cancel();
+            }
+            self.order_history.archive(order.into_decimal(&self.scale));
         }else{
-            let err = CustomError::OrderDoesNotExist;
-            Err(ErrorResponse::new(err))
+            self.rest_order(order);
         }
+        Ok(response)
+    }
+
+    //amends the order only if it is the user's, as `delete_user_order`
+    pub fn modify_user_order(&mut self,user_id:u64,modify_order_request:ModifyOrderRequest)->Result<ModifyOrderResponse,CustomError>{
+        if self.is_foreign_order(user_id, modify_order_request.order_id){
+            return Err(CustomError::OrderDoesNotExist)
+        }
+        self.modify_order(modify_order_request)
     }
 
     pub fn add_limit_order(&mut self,order: LimitOrder)->OpenOrder{
+        //a resubmitted client order id gets the original order back instead of placing it twice
+        if let Some(client_order_id)=&order.client_order_id
+            && let Some(original)=self.original_limit_order(order.user_id, client_order_id){
+            return original
+        }
         self.order_id_index+=1;
         let order_id=self.order_id_index;
-        let open_order=self.match_limit_order(order, order_id);
-        self.order_map.insert(order_id, open_order.clone());
-        self.clear_empty_bids_or_asks();
-        open_order
-    }
-    
-    fn match_limit_order(&mut self,order: LimitOrder,order_id:u64)->OpenOrder{
-        let price = order.price;
-        let mut remaining_quantity=order.quantity;
-        match order.side{
-            Side::Asks=>{
-                let option_best_bid = self.get_best_bid();
-                let is_first_bids=self.is_bids_empty();
-                if let Some(b)=option_best_bid{
-                    let best_bid=b.0;
-                    if !is_first_bids && price<best_bid {
-                        let mut new_best_bid = self.get_best_bid().unwrap().0;
-                        let mut bids = self.bids.iter_mut();
-                        let mut price_array:Vec<Decimal>=Vec::new();
-                        'outer: while remaining_quantity>dec!(0) && new_best_bid>=price{
-                            if let Some(open_order)=bids.next(){
-                                let open_orders = open_order.1;
-                                let order_price=open_order.0.0;
-                                let mut iter = open_orders.iter_mut();
-                                let mut to_remove:Vec<u64> = Vec::new();
-                                new_best_bid=order_price;
-                                if new_best_bid<price{
-                                    break 'outer;
-                                }
-                                while remaining_quantity>dec!(0){
-                                    if let Some(o) =iter.next(){
-                                        let quantity_remaining=o.quantity-o.quantity_filled;
-                                        if remaining_quantity>=quantity_remaining{
-                                            remaining_quantity-=quantity_remaining;
-                                            let order_id=o.order_id;
-                                            let order_map_order=self.order_map.get_mut(&order_id).unwrap();
-                                            order_map_order.quantity_filled=order_map_order.quantity;
-                                            to_remove.push(o.order_id);
-                                        }else{
-                                            o.quantity_filled+=remaining_quantity;
-                                            let order_id=o.order_id;
-                                            let order_map_order=self.order_map.get_mut(&order_id).unwrap();
-                                            order_map_order.quantity_filled+=remaining_quantity;
-                                            remaining_quantity=dec!(0);
-                                        }
-                                        price_array.push(o.price); 
-                                    }else{
-                                        break;
-                                    }
-                                }
-                                open_orders.retain(|v|!to_remove.contains(&v.order_id));
-                            }else {
-                                break;
-                            }
-                        }
-                        let open_order=OpenOrder::new(price, order.quantity, order.side, order.quantity-remaining_quantity, order.user_id, order_id);
-                        if remaining_quantity!=dec!(0){
-                            self.asks.entry(price).or_insert(VecDeque::new()).push_back(open_order.clone());
-                        }
-                        return open_order;
-                    }
-                }
-                //checks if a order exists for a particular price
-                if let Some(open_orders)  = self.bids.get_mut(&Reverse(price)){
-                    let mut iter = open_orders.iter_mut();
-                    let mut to_remove:Vec<u64> = Vec::new();
-                    //while the remaining_quantity_to_be_filled is greater than 0
-                    while remaining_quantity>dec!(0){
-                        //if there is a element in the iterator
-                        if let Some(o)=iter.next(){
-                            //quantity remaining for this particular order
-                            let quantity_remaining=o.quantity-o.quantity_filled;
-                            if remaining_quantity>=quantity_remaining{
-                                remaining_quantity-=quantity_remaining;
-                                let order_id=o.order_id;
-                                let order_map_order=self.order_map.get_mut(&order_id).unwrap();
-                                order_map_order.quantity_filled=order_map_order.quantity;
-                                to_remove.push(o.order_id);
-                            }else{
-                                o.quantity_filled+=remaining_quantity;
-                                let order_id=o.order_id;
-                                let order_map_order=self.order_map.get_mut(&order_id).unwrap();
-                                order_map_order.quantity_filled+=remaining_quantity;
-                                remaining_quantity=dec!(0);
-                            }
-                        }
-                        //if there is no element in the iterator do this 
-                        else{
-                            break;
-                        }
-                    }
-                    open_orders.retain(|v|!to_remove.contains(&v.order_id));
-                    let open_order=OpenOrder::new(price, order.quantity, order.side, order.quantity-remaining_quantity, order.user_id, order_id);
-                    if remaining_quantity!=dec!(0){
-                        self.asks.entry(price).or_insert(VecDeque::new()).push_back(open_order.clone());
-                    }
-                    return open_order;
-                }
-                //if there is no order for that particular price do this
-                else{
-                    let open_order=OpenOrder::new(price, order.quantity, order.side, dec!(0), order.user_id, order_id);
-                    self.asks.entry(price).or_insert(VecDeque::new()).push_back(open_order.clone());
-                    return open_order;
-                }
+        let user_id=order.user_id;
+        let client_order_id=order.client_order_id.clone();
+        let last_trade_id=self.trade_log.trade_id_index;
+        let price=self.band_price(&order.side, order.price).ok();
+        let open_order=match price.zip(self.limit_quantity(order.quantity).ok()){
+            //not positive, past a band that rejects, or off the market's ticks or lots
+            None=>{
+                let mut open_order=OpenOrder::new(order.price, order.quantity, order.side, dec!(0), user_id, order_id).with_client_order_id(order.client_order_id);
+                open_order.reject();
+                self.order_history.archive(open_order.clone());
+                open_order
             },
-            Side::Bids=>{
-                let option_best_ask = self.get_best_ask();
-                let is_first_ask=self.is_asks_empty();
-                if let Some(b)=option_best_ask{
-                    let best_ask=b.to_owned();
-                    if !is_first_ask && price>best_ask{
-                        let mut new_best_ask = self.get_best_ask().unwrap().clone();
-                        let mut bids = self.asks.iter_mut();
-                        let mut price_array:Vec<Decimal>=Vec::new();
-                        'outer: while remaining_quantity>dec!(0) && new_best_ask<=price{
-                            if let Some(open_order)=bids.next(){
-                                let open_orders = open_order.1;
-                                let order_price=open_order.0.clone();
-                                let mut iter = open_orders.iter_mut();
-                                let mut to_remove:Vec<u64> = Vec::new();
-                                new_best_ask=order_price;
-                                if new_best_ask>price{
-                                    break 'outer;
-                                }
-                                while remaining_quantity>dec!(0){
-                                    if let Some(o) =iter.next(){
-                                        let quantity_remaining=o.quantity-o.quantity_filled;
-                                        if remaining_quantity>=quantity_remaining{
-                                            remaining_quantity-=quantity_remaining;
-                                            let order_id=o.order_id;
-                                            let order_map_order=self.order_map.get_mut(&order_id).unwrap();
-                                            order_map_order.quantity_filled=order_map_order.quantity;
-                                            to_remove.push(o.order_id);
-                                            // open_orders.pop_front();
-                                        }else{
-                                            o.quantity_filled+=remaining_quantity;
-                                            let order_id=o.order_id;
-                                            let order_map_order=self.order_map.get_mut(&order_id).unwrap();
-                                            order_map_order.quantity_filled+=remaining_quantity;
-                                            remaining_quantity=dec!(0);
-                                        }
-                                        price_array.push(o.price); 
-                                    }else{
-                                        break;
-                                    }
-                                }
-                                open_orders.retain(|v|!to_remove.contains(&v.order_id));
-                            }else {
-                                break;
-                            }
-                        }
-                        let open_order=OpenOrder::new(price, order.quantity, order.side, order.quantity-remaining_quantity, order.user_id, order_id);
-                        if remaining_quantity!=dec!(0){
-                            self.bids.entry(Reverse(price)).or_insert(VecDeque::new()).push_back(open_order.clone());
-                        }
-                        return open_order;
-                    }
-                }
-                
-                //checks if a order exists for a particular price
-                if let Some(open_orders)  = self.asks.get_mut(&price){
-                    let mut iter = open_orders.iter_mut();
-                    let mut to_remove:Vec<u64> = Vec::new();
-                    //while the remaining_quantity_to_be_filled is greater than 0
-                    while remaining_quantity>dec!(0){
-                        //if there is a element in the iterator
-                        if let Some(o)=iter.next(){
-                            //quantity remaining for this particular order
-                            let quantity_remaining=o.quantity-o.quantity_filled;
-                            if remaining_quantity>=quantity_remaining{
-                                remaining_quantity-=quantity_remaining;
-                                let order_id=o.order_id;
-                                let order_map_order=self.order_map.get_mut(&order_id).unwrap();
-                                order_map_order.quantity_filled=order_map_order.quantity;
-                                to_remove.push(o.order_id);
-                            }else{
-                                o.quantity_filled+=remaining_quantity;
-                                let order_id=o.order_id;
-                                let order_map_order=self.order_map.get_mut(&order_id).unwrap();
-                                order_map_order.quantity_filled+=remaining_quantity;
-                                remaining_quantity=dec!(0);
-                            }
-                        }
-                        //if there is no element in the iterator do this 
-                        else{
-                            break;
-                        }
-                    }
-                    open_orders.retain(|v|!to_remove.contains(&v.order_id));
-                    let open_order=OpenOrder::new(price, order.quantity, order.side, order.quantity-remaining_quantity, order.user_id, order_id);
-                    if remaining_quantity!=dec!(0){
-                        self.bids.entry(Reverse(price)).or_insert(VecDeque::new()).push_back(open_order.clone());
-                    }
-                    return open_order;
-                }
-                //if there is no order for that particular price do this
-                else{
-                    let open_order=OpenOrder::new(price, order.quantity, order.side, dec!(0), order.user_id, order_id);
-                    self.bids.entry(Reverse(price)).or_insert(VecDeque::new()).push_back(open_order.clone());
-                    return open_order;
+            Some((price,quantity))=>{
+                let open_order=if self.in_auction{
+                    OpenOrder::new(price, quantity, order.side, N::ZERO, user_id, order_id).with_client_order_id(order.client_order_id)
+                }else{
+                    self.match_limit_order(price, quantity, order, order_id)
+                };
+                self.observe_trades_since(last_trade_id);
+                let response=open_order.to_decimal(&self.scale);
+                if open_order.is_terminal(){
+                    self.order_history.archive(response.clone());
+                }else{
+                    self.rest_order(open_order);
                 }
+                response
             }
+        };
+        if let Some(client_order_id)=client_order_id{
+            self.client_order_ids.insert_limit_order(user_id, client_order_id, order_id, &self.order_map);
         }
+        open_order
     }
 
-    pub fn add_market_order(&mut self,order:MarketOrder)->MarketOrderResponse{
-        let remaining_quantity=order.quantity;
-        let response =self.match_market_order(remaining_quantity, order);
-        self.clear_empty_bids_or_asks();
-        response
+    //the same as `add_limit_order`, but an order the book would reject is turned away with the reason
+    //before it takes an order id or goes into the history
+    pub fn place_limit_order(&mut self,order:LimitOrder)->Result<OpenOrder,CustomError>{
+        match self.limit_order_error(&order){
+            Some(e)=>Err(e),
+            None=>Ok(self.add_limit_order(order))
+        }
+    }
+    
+    //a limit order's quantity has to be positive and a whole number of lots
+    fn limit_quantity(&self,quantity:Decimal)->Result<N,CustomError>{
+        if quantity<=Decimal::ZERO{
+            return Err(CustomError::InvalidQuantity)
+        }
+        N::from_quantity(quantity, &self.scale).ok_or(CustomError::InvalidQuantity)
     }
 
-    fn match_market_order(&mut self,mut remaining_quantity:Decimal,order:MarketOrder)->MarketOrderResponse{
-        match order.side{
-            Side::Asks=>{
-                if self.is_bids_empty(){
-                    return MarketOrderResponse::new(false, None, None,Some(CustomError::LimitOrderDoesNotExist))
-                }
-                let mut bids =self.bids.iter_mut();
-                let mut price_array: Vec<Decimal> = Vec::new();
-                while remaining_quantity>dec!(0){
-                    if let Some(open_order)=bids.next(){
-                        let open_orders=open_order.1;
-                        let mut iter = open_orders.iter_mut();
-                        let mut to_remove:Vec<u64> = Vec::new();
-                        while remaining_quantity>dec!(0) {
-                            if let Some(o) =iter.next(){
-                                let quantity_remaining=o.quantity-o.quantity_filled;
-                                if remaining_quantity>=quantity_remaining{
-                                    remaining_quantity-=quantity_remaining;
-                                    to_remove.push(o.order_id);
-                                    // open_orders.pop_front();
-                                }else{
-                                    o.quantity_filled+=remaining_quantity;
-                                    remaining_quantity=dec!(0);
-                                }
-                                price_array.push(o.price); 
-                            }else{
-                                break;
-                            }
-                        }
-                        
-                        open_orders.retain(|v|!to_remove.contains(&v.order_id));
-                    }else{
-                        break;
-                    }
-                }
-                let quantity_filled = order.quantity-remaining_quantity;
-                let length_of_price_array=price_array.len() as i64;
-                let length_of_price =Decimal::new(length_of_price_array, 0);
-                let iter = price_array.iter();
-                let mut total_sum=dec!(0);
-                for i in iter{
-                    total_sum+=i;
-                }
-                let average_price = total_sum/length_of_price;
-                let market_order_response = MarketOrderResponse::new(true, Some(average_price), Some(quantity_filled),None);
-                return market_order_response;
-            },
-            Side::Bids=>{
-                if self.is_asks_empty(){
-                    return MarketOrderResponse::new(false, None, None, Some(CustomError::LimitOrderDoesNotExist));
-                }
-                let mut asks = self.asks.iter_mut();
-                let mut price_array = Vec::new();
-                while remaining_quantity>dec!(0){
-                    if let Some(open_order) = asks.next(){
-                        let open_orders = open_order.1;
-                        let mut iter = open_orders.iter_mut();
-                        let mut to_remove: Vec<u64> = Vec::new();
-                        while remaining_quantity>dec!(0){
-                            if let Some(o)=iter.next(){
-                                let quantity_remaing=o.quantity-o.quantity_filled;
-                                if remaining_quantity>=(quantity_remaing){
-                                    remaining_quantity-=quantity_remaing;
-                                    to_remove.push(o.order_id);
-                                }else{
-                                    o.quantity_filled+=remaining_quantity;
-                                    remaining_quantity=dec!(0);
-                                }
-                                price_array.push(o.price);
-                            }else{
-                                break;
+    //why a limit order would come back rejected, if it would
+    pub(crate) fn limit_order_error(&self,order:&LimitOrder)->Option<CustomError>{
+        self.band_price(&order.side, order.price).and_then(|_|self.limit_quantity(order.quantity)).err()
+    }
+
+    fn match_limit_order(&mut self,price:N,quantity:N,order: LimitOrder,order_id:u64)->OpenOrder<N>{
+        let taker=Taker{order_id:Some(order_id),user_id:order.user_id,side:order.side.clone()};
+        let policy=LimitPolicy{price,allocation:self.allocation.clone(),scale:self.scale};
+        let execution=self.take_liquidity(&taker, quantity, &policy);
+        OpenOrder::new(price, quantity, order.side, execution.quantity, order.user_id, order_id).with_client_order_id(order.client_order_id)
+    }
+
+    pub fn add_market_order(&mut self,order:MarketOrder)->MarketOrderResponse{
+        if let Some(client_order_id)=&order.client_order_id
+            && let Some(original)=self.client_order_ids.get_market_order(order.user_id, client_order_id){
+            return original.clone()
+        }
+        let user_id=order.user_id;
+        let client_order_id=order.client_order_id.clone();
+        let last_trade_id=self.trade_log.trade_id_index;
+        //whatever cannot trade inside the band or the order's protection is cancelled rather than swept
+        let quantity=if self.in_auction{Err(CustomError::AuctionInProgress)}else{self.market_order_quantity(&order)};
+        let response=match quantity{
+            Err(e)=>MarketOrderResponse::new(false, None, None, Some(e)),
+            Ok(quantity)=>{
+                let (requested,quote_quantity)=(order.quantity,order.quote_quantity);
+                let mut response=self.match_market_order(quantity, &order);
+                let quantity=quantity.to_quantity(&self.scale);
+                if response.is_success(){
+                    match quote_quantity{
+                        Some(quote_quantity)=>{
+                            let spent=self.quote_traded_since(last_trade_id);
+                            response=response.with_quote_quantity(spent);
+                            if spent<quote_quantity{
+                                response=response.with_remaining(quote_quantity-spent);
                             }
-                        }
-                        open_orders.retain(|v|!to_remove.contains(&v.order_id));
-                    }else{
-                        break;
+                        },
+                        None if quantity<requested=>response=response.with_remaining(requested-quantity),
+                        None=>{}
                     }
                 }
-                let quantity_filled=order.quantity-remaining_quantity;
-                let length_of_price_array=price_array.len() as i64;
-                let length_of_price =Decimal::new(length_of_price_array, 0);
-                let iter = price_array.iter();
-                let mut total_sum=dec!(0);
-                for i in iter{
-                    total_sum+=i;
-                }
-                let average_price = total_sum/length_of_price;
-                let market_order_response = MarketOrderResponse::new(true, Some(average_price), Some(quantity_filled),None);
-                return market_order_response;
+                response
             }
+        };
+        self.observe_trades_since(last_trade_id);
+        if let Some(client_order_id)=client_order_id{
+            self.client_order_ids.insert_market_order(user_id, client_order_id, response.clone());
         }
+        response
+    }
+
+    fn match_market_order(&mut self,quantity:N,order:&MarketOrder)->MarketOrderResponse{
+        let opposite_is_empty=match order.side{
+            Side::Asks=>self.is_bids_empty(),
+            Side::Bids=>self.is_asks_empty()
+        };
+        if opposite_is_empty{
+            return MarketOrderResponse::new(false, None, None,Some(CustomError::LimitOrderDoesNotExist))
+        }
+        let taker=Taker{order_id:None,user_id:order.user_id,side:order.side.clone()};
+        let policy=MarketPolicy{allocation:self.allocation.clone(),scale:self.scale};
+        let execution=self.take_liquidity(&taker, quantity, &policy);
+        MarketOrderResponse::new(true, execution.average_price(&self.scale), Some(execution.quantity.to_quantity(&self.scale)),None)
     }
 }
 
 #[cfg(test)]
 mod tests{
-    use super::{Orderbook,Reverse,dec,VecDeque};
+    use super::{Orderbook,Side,dec};
+    use crate::LimitOrder;
     
     #[cfg(test)]
     use pretty_assertions::{assert_eq};
     #[test]
-    fn test_clear_empty_bids_or_asks(){
+    fn test_delete_order_unlinks_it_from_its_level(){
         let mut orderbook =Orderbook::new();
+        for _ in 0..3{
+            orderbook.add_limit_order(LimitOrder{price:dec!(110),quantity:dec!(10),side:Side::Asks,user_id:1,client_order_id:None});
+        }
+        let queue=|orderbook:&Orderbook|orderbook.asks.orders_at(dec!(110)).map(|o|o.order_id).collect::<Vec<u64>>();
 
-        orderbook.asks.insert(dec!(110), VecDeque::new());
-        assert_eq!(orderbook.asks.get(&dec!(110)).unwrap(),&VecDeque::new());
-        orderbook.clear_empty_bids_or_asks();
-        assert_eq!(orderbook.asks.get(&dec!(110)),None);
-    
-        orderbook.bids.insert(Reverse(dec!(110)), VecDeque::new());
-        assert_eq!(orderbook.bids.get(&Reverse(dec!(110))).unwrap(),&VecDeque::new());
-        orderbook.clear_empty_bids_or_asks();
-        assert_eq!(orderbook.bids.get(&Reverse(dec!(110))),None);
+        orderbook.delete_order(2).unwrap();
+        assert_eq!(queue(&orderbook),vec![1,3]);
+        orderbook.delete_order(1).unwrap();
+        assert_eq!(queue(&orderbook),vec![3]);
+        orderbook.add_limit_order(LimitOrder{price:dec!(110),quantity:dec!(10),side:Side::Asks,user_id:1,client_order_id:None});
+        assert_eq!(queue(&orderbook),vec![3,4]);
+        orderbook.delete_order(4).unwrap();
+        orderbook.delete_order(3).unwrap();
+        //the level goes with its last order
+        assert!(orderbook.asks.is_empty());
+        assert_eq!(orderbook.asks.order_count(),0);
     }
 
 }
\ No newline at end of file
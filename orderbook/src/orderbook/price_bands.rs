@@ -0,0 +1,87 @@
+use rust_decimal::{dec, Decimal, RoundingStrategy};
+use crate::orderbook::numeric::Numeric;
+use crate::orderbook::response::CustomError;
+use crate::orderbook::types::{BandAction, PriceBand, PriceBandConfig, ReferencePrice, Side};
+use crate::Orderbook;
+use crate::orderbook::allocation::MatchingAlgorithm;
+
+const BPS:Decimal=dec!(10_000);
+
+impl PriceBand{
+    pub fn new()->PriceBand{
+        PriceBand::default()
+    }
+
+    //lowest and highest price a trade may print at
+    pub fn limits(&self)->Option<(Decimal,Decimal)>{
+        let config=self.config.as_ref()?;
+        let reference=self.reference_price?;
+        let width=reference*Decimal::from(config.width_bps)/BPS;
+        Some((reference-width,reference+width))
+    }
+
+    //worst price a taker on this side may trade at and what happens to orders that go past it
+    pub fn limit_for(&self,side:&Side)->Option<(Decimal,BandAction)>{
+        let (low,high)=self.limits()?;
+        let action=self.config.as_ref()?.action;
+        match side{
+            Side::Bids=>Some((high,action)),
+            Side::Asks=>Some((low,action))
+        }
+    }
+
+    //the reference follows trades even without a band so one can be switched on at any time
+    pub(crate) fn observe(&mut self,price:Decimal){
+        self.reference_price=Some(match (self.config.as_ref().map(|c|&c.reference),self.reference_price){
+            (Some(ReferencePrice::Ema(weight)),Some(previous))=>previous+(price-previous)*weight,
+            _=>price
+        });
+    }
+}
+
+impl<N:Numeric,A:MatchingAlgorithm> Orderbook<N,A>{
+    pub fn set_price_band(&mut self,config:Option<PriceBandConfig>){
+        self.price_band.config=config;
+    }
+
+    //the price a limit order on this side may rest or trade at: past a band that rejects it fails, past one that truncates it is pulled back to the edge
+    pub(crate) fn band_price(&self,side:&Side,price:Decimal)->Result<N,CustomError>{
+        //checked first so a band that truncates cannot pull a price that was never valid back into it
+        if price<=Decimal::ZERO{
+            return Err(CustomError::InvalidPrice)
+        }
+        if let Some((limit,action))=self.price_band.limit_for(side)
+            && match side{Side::Bids=>price>limit,Side::Asks=>price<limit}{
+            return match action{
+                BandAction::Reject=>Err(CustomError::PriceBandExceeded),
+                //an edge between two ticks is rounded back inside the band
+                BandAction::Truncate=>Ok(N::round_price(limit, match side{
+                    Side::Bids=>RoundingStrategy::ToNegativeInfinity,
+                    Side::Asks=>RoundingStrategy::ToPositiveInfinity
+                }, &self.scale))
+            }
+        }
+        N::from_price(price, &self.scale).ok_or(CustomError::InvalidPrice)
+    }
+
+    //quantity resting against a taker on this side at prices no worse than the limit, up to `cap`, a limit between ticks counts the ticks inside it
+    pub(crate) fn quantity_within(&self,side:&Side,limit:Option<Decimal>,cap:N)->N{
+        match side{
+            Side::Bids=>self.asks.quantity_within_up_to(limit.map(|l|N::round_price(l, RoundingStrategy::ToNegativeInfinity, &self.scale)), cap),
+            Side::Asks=>self.bids.quantity_within_up_to(limit.map(|l|N::round_price(l, RoundingStrategy::ToPositiveInfinity, &self.scale)), cap)
+        }
+    }
+
+    //moves the reference price along the trades printed after `last_trade_id`
+    pub(crate) fn observe_trades_since(&mut self,last_trade_id:u64){
+        let prices:Vec<Decimal>=self.trade_log.trades
+            .iter()
+            .rev()
+            .take_while(|t|t.trade_id>last_trade_id)
+            .map(|t|t.price)
+            .collect();
+        for price in prices.into_iter().rev(){
+            self.price_band.observe(price);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,197 @@
+use std::cmp::Reverse;
+use std::collections::btree_map::Keys;
+use std::collections::BTreeMap;
+
+use slab::Slab;
+use crate::orderbook::numeric::Numeric;
+use crate::orderbook::changes::note_change;
+use crate::orderbook::types::{Level, OpenOrder, PriceLevels, RestingOrder};
+
+//orders the levels of one side of the book so that its best price sorts first
+pub trait PriceKey:Ord+Copy{
+    type Number:Numeric;
+
+    fn from_price(price:Self::Number)->Self;
+    fn price(&self)->Self::Number;
+}
+
+impl<N:Numeric> PriceKey for N{
+    type Number=N;
+
+    fn from_price(price:N)->Self{
+        price
+    }
+
+    fn price(&self)->N{
+        *self
+    }
+}
+
+impl<N:Numeric> PriceKey for Reverse<N>{
+    type Number=N;
+
+    fn from_price(price:N)->Self{
+        Reverse(price)
+    }
+
+    fn price(&self)->N{
+        self.0
+    }
+}
+
+impl<K:PriceKey> Default for PriceLevels<K>{
+    fn default()->Self{
+        PriceLevels::new()
+    }
+}
+
+//walks one level's queue from the oldest order to the newest
+pub struct LevelOrders<'a,N>{
+    orders: &'a Slab<RestingOrder<N>>,
+    next: Option<usize>
+}
+
+impl<'a,N> Iterator for LevelOrders<'a,N>{
+    type Item=&'a OpenOrder<N>;
+
+    fn next(&mut self)->Option<Self::Item>{
+        let resting=self.orders.get(self.next?)?;
+        self.next=resting.next;
+        Some(&resting.order)
+    }
+}
+
+impl<K:PriceKey> PriceLevels<K>{
+    pub fn new()->PriceLevels<K>{
+        PriceLevels { levels:BTreeMap::new(), orders:Slab::new(), changes:None }
+    }
+
+    pub fn is_empty(&self)->bool{
+        self.levels.is_empty()
+    }
+
+    //number of price levels
+    pub fn len(&self)->usize{
+        self.levels.len()
+    }
+
+    pub fn order_count(&self)->usize{
+        self.orders.len()
+    }
+
+    pub fn best_price(&self)->Option<K::Number>{
+        self.levels.keys().next().map(|k|k.price())
+    }
+
+    pub fn keys(&self)->Keys<'_,K,Level>{
+        self.levels.keys()
+    }
+
+    //each level best price first, with its orders in time priority
+    pub fn iter(&self)->impl Iterator<Item=(&K,LevelOrders<'_,K::Number>)>{
+        self.levels.iter().map(|(key,level)|(key,self.level_orders(level)))
+    }
+
+    pub fn orders_at(&self,price:K::Number)->LevelOrders<'_,K::Number>{
+        match self.levels.get(&K::from_price(price)){
+            Some(level)=>self.level_orders(level),
+            None=>LevelOrders { orders:&self.orders, next:None }
+        }
+    }
+
+    //every resting order on this side in no particular order
+    pub fn orders(&self)->impl Iterator<Item=&OpenOrder<K::Number>>{
+        self.orders.iter().map(|(_,resting)|&resting.order)
+    }
+
+    fn level_orders(&self,level:&Level)->LevelOrders<'_,K::Number>{
+        LevelOrders { orders:&self.orders, next:level.head }
+    }
+
+    pub fn get(&self,slot:usize)->Option<&OpenOrder<K::Number>>{
+        self.orders.get(slot).map(|resting|&resting.order)
+    }
+
+    pub(crate) fn get_mut(&mut self,slot:usize)->Option<&mut OpenOrder<K::Number>>{
+        let resting=self.orders.get_mut(slot)?;
+        note_change(&mut self.changes, &resting.order);
+        Some(&mut resting.order)
+    }
+
+    //oldest order at the best price
+    pub(crate) fn front(&self)->Option<usize>{
+        self.levels.values().next()?.head
+    }
+
+    //queues an order at the back of its price level and returns its slot
+    pub(crate) fn push_back(&mut self,order:OpenOrder<K::Number>)->usize{
+        note_change(&mut self.changes, &order);
+        let level=self.levels.entry(K::from_price(order.price)).or_default();
+        let slot=self.orders.insert(RestingOrder { order, prev:level.tail, next:None });
+        match level.tail.and_then(|tail|self.orders.get_mut(tail)){
+            Some(tail)=>tail.next=Some(slot),
+            None=>level.head=Some(slot)
+        }
+        level.tail=Some(slot);
+        level.order_count+=1;
+        slot
+    }
+
+    //unlinks an order from its level in constant time, dropping the level once it is empty,
+    //none for a slot that holds no order
+    pub(crate) fn remove(&mut self,slot:usize)->Option<OpenOrder<K::Number>>{
+        let RestingOrder { order, prev, next }=self.orders.try_remove(slot)?;
+        note_change(&mut self.changes, &order);
+        let key=K::from_price(order.price);
+        if let Some(prev)=prev.and_then(|prev|self.orders.get_mut(prev)){
+            prev.next=next;
+        }
+        if let Some(next)=next.and_then(|next|self.orders.get_mut(next)){
+            next.prev=prev;
+        }
+        if let Some(level)=self.levels.get_mut(&key){
+            level.order_count-=1;
+            if level.order_count==0{
+                self.levels.remove(&key);
+            }else{
+                if prev.is_none(){
+                    level.head=next;
+                }
+                if next.is_none(){
+                    level.tail=prev;
+                }
+            }
+        }
+        Some(order)
+    }
+
+    //open quantity and number of orders resting at a price, none once nothing rests there
+    pub fn level_total(&self,price:K::Number)->Option<(K::Number,u64)>{
+        let level=self.levels.get(&K::from_price(price))?;
+        Some((self.level_orders(level).map(|o|o.open_quantity()).sum(),level.order_count))
+    }
+
+    //open quantity resting at prices no worse than the limit, the whole side without one
+    pub fn quantity_within(&self,limit:Option<K::Number>)->K::Number{
+        self.iter()
+            .take_while(|(key,_)|limit.is_none_or(|l|**key<=K::from_price(l)))
+            .flat_map(|(_,orders)|orders)
+            .map(|o|o.open_quantity())
+            .sum()
+    }
+
+    //as `quantity_within` but no more than `cap`, the walk stops as soon as the cap is covered
+    pub fn quantity_within_up_to(&self,limit:Option<K::Number>,cap:K::Number)->K::Number{
+        let mut quantity=K::Number::ZERO;
+        let orders=self.iter()
+            .take_while(|(key,_)|limit.is_none_or(|l|**key<=K::from_price(l)))
+            .flat_map(|(_,orders)|orders);
+        for order in orders{
+            quantity+=order.open_quantity();
+            if quantity>=cap{
+                return cap
+            }
+        }
+        quantity
+    }
+}
\ No newline at end of file
@@ -0,0 +1,98 @@
+use rust_decimal::{dec, Decimal, RoundingStrategy};
+use crate::orderbook::{numeric::{Numeric, MAX_NOTIONAL}, response::CustomError, types::{BandAction, Side}};
+use crate::{MarketOrder, Orderbook};
+use crate::orderbook::allocation::MatchingAlgorithm;
+
+const BPS:Decimal=dec!(10_000);
+
+//the worse of two limits for the taker is the tighter one
+fn tighter(side:&Side,a:Option<Decimal>,b:Option<Decimal>)->Option<Decimal>{
+    match (a,b){
+        (Some(a),Some(b))=>Some(match side{
+            Side::Bids=>a.min(b),
+            Side::Asks=>a.max(b)
+        }),
+        (a,b)=>a.or(b)
+    }
+}
+
+impl<N:Numeric,A:MatchingAlgorithm> Orderbook<N,A>{
+    //worst price a market order accepts from its protection price and slippage allowance
+    pub(crate) fn protection_limit(&self,order:&MarketOrder)->Option<Decimal>{
+        let from_slippage=order.max_slippage_bps.and_then(|bps|{
+            let slippage=Decimal::from(bps)/BPS;
+            match order.side{
+                Side::Bids=>self.get_best_ask().map(|p|p*(Decimal::ONE+slippage)),
+                Side::Asks=>self.get_best_bid().map(|p|p*(Decimal::ONE-slippage))
+            }
+        });
+        tighter(&order.side, from_slippage, order.protection_price)
+    }
+
+    //base quantity a quote budget buys from the asks up to the limit, never more than the budget
+    pub(crate) fn quantity_for_quote(&self,quote_quantity:Decimal,limit:Option<Decimal>)->N{
+        let limit=limit.map(|l|N::round_price(l, RoundingStrategy::ToNegativeInfinity, &self.scale));
+        let mut budget=N::round_notional(quote_quantity, RoundingStrategy::ToNegativeInfinity, &self.scale);
+        let mut quantity=N::ZERO;
+        for (price,orders) in self.asks.iter().take_while(|(price,_)|limit.is_none_or(|l|**price<=l)){
+            let level_quantity:N=orders.map(|o|o.open_quantity()).sum();
+            let level_notional=N::notional(*price, level_quantity);
+            if level_notional>=budget{
+                quantity+=N::quantity_for(budget, *price);
+                break;
+            }
+            budget-=level_notional;
+            quantity+=level_quantity;
+        }
+        quantity
+    }
+
+    //how much of a market order may trade once the band and its protection are applied, or why none of it can
+    pub(crate) fn market_order_quantity(&self,order:&MarketOrder)->Result<N,CustomError>{
+        match order.quote_quantity{
+            Some(_) if order.side!=Side::Bids=>return Err(CustomError::QuoteQuantityOnlyForBuys),
+            Some(q) if q<=Decimal::ZERO || q>MAX_NOTIONAL=>return Err(CustomError::InvalidQuantity),
+            None if order.quantity<=Decimal::ZERO=>return Err(CustomError::InvalidQuantity),
+            _=>{}
+        }
+        //a base quantity has to be a whole number of lots, it is not looked at when spending a quote budget
+        let requested=match order.quote_quantity{
+            Some(_)=>N::ZERO,
+            None=>N::from_quantity(order.quantity, &self.scale).ok_or(CustomError::InvalidQuantity)?
+        };
+        //each walk stops once the order is covered, so it never reaches further into the book than the order could trade
+        let available=|limit:Option<Decimal>|match order.quote_quantity{
+            Some(quote_quantity)=>self.quantity_for_quote(quote_quantity, limit),
+            None=>self.quantity_within(&order.side, limit, requested)
+        };
+        let mut quantity=available(None);
+        if let Some((limit,action))=self.price_band.limit_for(&order.side){
+            let within=available(Some(limit));
+            if within<quantity{
+                if action==BandAction::Reject || within==N::ZERO{
+                    return Err(CustomError::PriceBandExceeded)
+                }
+                quantity=within;
+            }
+        }
+        if let Some(limit)=self.protection_limit(order){
+            let within=available(Some(limit));
+            if within<quantity{
+                if within==N::ZERO{
+                    return Err(CustomError::ProtectionPriceExceeded)
+                }
+                quantity=within;
+            }
+        }
+        Ok(quantity)
+    }
+
+    pub(crate) fn quote_traded_since(&self,last_trade_id:u64)->Decimal{
+        self.trade_log.trades
+            .iter()
+            .rev()
+            .take_while(|t|t.trade_id>last_trade_id)
+            .map(|t|t.price*t.quantity)
+            .sum()
+    }
+}
\ No newline at end of file
@@ -1,25 +1,51 @@
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug,PartialEq,Serialize,Deserialize)]
+use crate::OpenOrder;
+
+#[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
 pub enum CustomError{
     OrderDoesNotExist,
     ModifyQuantityCannotBeLesserThanFilledQuantity,
     LimitOrderDoesNotExist,
-    OrderAlreadyMatched
+    OrderAlreadyMatched,
+    OrderAlreadyClosed,
+    BatchTooLarge,
+    PriceBandExceeded,
+    ProtectionPriceExceeded,
+    QuoteQuantityOnlyForBuys,
+    InvalidQuantity,
+    InvalidPrice,
+    AuctionInProgress
 }
 
-#[derive(Debug,PartialEq,Serialize,Deserialize)]
+#[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
 pub struct MarketOrderResponse{
     success: bool,
     average_price: Option<Decimal>,
     quantity:Option<Decimal>,
-    error:Option<CustomError>
+    error:Option<CustomError>,
+    //quote currency spent, for orders placed with a quote quantity
+    quote_quantity:Option<Decimal>,
+    //what was left unfilled and cancelled, in the unit the order was placed in
+    remaining:Option<Decimal>
 }
 
-#[derive(PartialEq,Debug)]
-pub struct ErrorResponse{
-    pub error:CustomError
+#[derive(PartialEq,Debug,Serialize,Deserialize)]
+pub enum BatchResult{
+    Placed(OpenOrder),
+    Modified(ModifyOrderResponse),
+    Cancelled(DeleteResponse),
+    Failed(CustomError),
+    //valid on its own but not applied because another item of an all-or-nothing batch failed
+    NotApplied
+}
+
+#[derive(PartialEq,Debug,Serialize,Deserialize)]
+pub struct BatchResponse{
+    pub success: bool,
+    pub results: Vec<BatchResult>,
+    pub error: Option<CustomError>
 }
 
 #[derive(PartialEq,Debug,Serialize,Deserialize)]
@@ -42,7 +68,21 @@ pub struct ModifyOrderResponse{
 
 impl MarketOrderResponse{
     pub fn new(success: bool,average_price:Option<Decimal>,quantity:Option<Decimal>,error:Option<CustomError>)->MarketOrderResponse{
-        MarketOrderResponse { success, average_price:average_price, quantity: quantity,error:error }
+        MarketOrderResponse { success, average_price, quantity,error, quote_quantity:None, remaining:None }
+    }
+
+    pub fn with_quote_quantity(mut self,quote_quantity:Decimal)->MarketOrderResponse{
+        self.quote_quantity=Some(quote_quantity);
+        self
+    }
+
+    pub fn with_remaining(mut self,remaining:Decimal)->MarketOrderResponse{
+        self.remaining=Some(remaining);
+        self
+    }
+
+    pub fn is_success(&self)->bool{
+        self.success
     }
 }
 
@@ -52,14 +92,32 @@ impl ModifyOrderResponse{
     }
 }
 
-impl ErrorResponse{
-    pub fn new(err: CustomError)->ErrorResponse{
-        ErrorResponse { error:err}
-    }
-}
-
 impl DeleteResponse{
     pub fn new(price:Decimal,quantity:Decimal,quantity_filled:Decimal,order_id:u64)->DeleteResponse{
         DeleteResponse { success:true,price,quantity, quantity_filled, order_id }
     }
+}
+
+#[derive(PartialEq,Debug,Serialize,Deserialize)]
+pub struct TradeHistoryResponse<T>{
+    pub trades: Vec<T>,
+    //pass back as `from_trade_id` to fetch the next page
+    pub next_cursor: Option<u64>
+}
+
+impl<T> TradeHistoryResponse<T>{
+    pub fn new(trades:Vec<T>,next_cursor:Option<u64>)->TradeHistoryResponse<T>{
+        TradeHistoryResponse { trades, next_cursor }
+    }
+}
+
+impl BatchResponse{
+    pub fn new(results:Vec<BatchResult>)->BatchResponse{
+        let success=!results.iter().any(|r|matches!(r,BatchResult::Failed(_)|BatchResult::NotApplied));
+        BatchResponse { success, results, error:None }
+    }
+
+    pub fn rejected(error:CustomError)->BatchResponse{
+        BatchResponse { success:false, results:Vec::new(), error:Some(error) }
+    }
 }
\ No newline at end of file
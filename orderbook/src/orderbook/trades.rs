@@ -0,0 +1,130 @@
+use rust_decimal::Decimal;
+use crate::orderbook::{clock::current_timestamp, numeric::Numeric, response::TradeHistoryResponse, types::{FeeSchedule, LiquidityRole, OpenOrder, PublicTrade, Taker, Trade, TradeHistoryQuery, TradeLog, UserTrade}};
+use crate::Orderbook;
+use crate::orderbook::allocation::MatchingAlgorithm;
+
+pub const DEFAULT_TRADE_QUERY_LIMIT:usize=100;
+pub const MAX_TRADE_QUERY_LIMIT:usize=1000;
+//oldest trades are dropped once the log grows past this
+pub const MAX_TRADE_LOG_SIZE:usize=100_000;
+
+impl TradeLog{
+    pub fn new()->TradeLog{
+        TradeLog::default()
+    }
+
+    //continuous matching prints at the maker's price, auctions at the uncrossing price
+    pub(crate) fn record_at<N:Numeric>(&mut self,price:Decimal,fee_schedule:&FeeSchedule,maker:&OpenOrder<N>,taker:&Taker,quantity:Decimal)->Trade{
+        self.trade_id_index+=1;
+        let notional=price*quantity;
+        let trade=Trade{
+            trade_id:self.trade_id_index,
+            price,
+            quantity,
+            taker_side:taker.side.clone(),
+            maker_order_id:maker.order_id,
+            maker_user_id:maker.user_id,
+            maker_fee:notional*fee_schedule.maker_fee_rate,
+            taker_order_id:taker.order_id,
+            taker_user_id:taker.user_id,
+            taker_fee:notional*fee_schedule.taker_fee_rate,
+            timestamp:current_timestamp()
+        };
+        self.trades.push_back(trade.clone());
+        if self.trades.len()>MAX_TRADE_LOG_SIZE{
+            self.trades.pop_front();
+        }
+        trade
+    }
+
+    pub fn recent(&self,limit:usize)->Vec<Trade>{
+        self.trades.iter().rev().take(limit.min(MAX_TRADE_QUERY_LIMIT)).cloned().collect()
+    }
+
+    pub fn history<F>(&self,query:&TradeHistoryQuery,filter:F)->TradeHistoryResponse<Trade>
+    where F:Fn(&Trade)->bool{
+        let limit=query.limit.unwrap_or(DEFAULT_TRADE_QUERY_LIMIT).min(MAX_TRADE_QUERY_LIMIT);
+        let start=match query.from_trade_id{
+            Some(cursor)=>self.trades.partition_point(|t|t.trade_id<=cursor),
+            None=>0
+        };
+        let mut trades:Vec<Trade>=self.trades
+            .range(start..)
+            .filter(|t|query.start_time.is_none_or(|s|t.timestamp>=s))
+            .filter(|t|query.end_time.is_none_or(|e|t.timestamp<=e))
+            .filter(|t|filter(t))
+            .take(limit+1)
+            .cloned()
+            .collect();
+        let mut next_cursor=None;
+        if trades.len()>limit{
+            trades.truncate(limit);
+            next_cursor=trades.last().map(|t|t.trade_id);
+        }
+        TradeHistoryResponse::new(trades, next_cursor)
+    }
+}
+
+impl Trade{
+    pub fn to_public(&self)->PublicTrade{
+        PublicTrade {
+            trade_id: self.trade_id,
+            price: self.price,
+            quantity: self.quantity,
+            taker_side: self.taker_side.clone(),
+            timestamp: self.timestamp
+        }
+    }
+
+    //a self-trade yields both a maker and a taker entry
+    pub fn to_user_trades(&self,user_id:u64)->Vec<UserTrade>{
+        let mut user_trades=Vec::new();
+        if self.maker_user_id==user_id{
+            user_trades.push(UserTrade{
+                trade_id:self.trade_id,
+                order_id:Some(self.maker_order_id),
+                price:self.price,
+                quantity:self.quantity,
+                side:self.taker_side.opposite(),
+                role:LiquidityRole::Maker,
+                fee:self.maker_fee,
+                timestamp:self.timestamp
+            });
+        }
+        if self.taker_user_id==user_id{
+            user_trades.push(UserTrade{
+                trade_id:self.trade_id,
+                order_id:self.taker_order_id,
+                price:self.price,
+                quantity:self.quantity,
+                side:self.taker_side.clone(),
+                role:LiquidityRole::Taker,
+                fee:self.taker_fee,
+                timestamp:self.timestamp
+            });
+        }
+        user_trades
+    }
+}
+
+impl<N:Numeric,A:MatchingAlgorithm> Orderbook<N,A>{
+    pub fn set_fee_schedule(&mut self,fee_schedule:FeeSchedule){
+        self.fee_schedule=fee_schedule;
+    }
+
+    pub fn get_recent_trades(&self,limit:usize)->Vec<PublicTrade>{
+        self.trade_log.recent(limit).iter().map(|t|t.to_public()).collect()
+    }
+
+    pub fn get_trade_history(&self,query:TradeHistoryQuery)->TradeHistoryResponse<PublicTrade>{
+        let page=self.trade_log.history(&query, |_|true);
+        let trades=page.trades.iter().map(|t|t.to_public()).collect();
+        TradeHistoryResponse::new(trades, page.next_cursor)
+    }
+
+    pub fn get_user_trades(&self,user_id:u64,query:TradeHistoryQuery)->TradeHistoryResponse<UserTrade>{
+        let page=self.trade_log.history(&query, |t|t.maker_user_id==user_id||t.taker_user_id==user_id);
+        let trades=page.trades.iter().flat_map(|t|t.to_user_trades(user_id)).collect();
+        TradeHistoryResponse::new(trades, page.next_cursor)
+    }
+}
\ No newline at end of file
@@ -1,8 +1,14 @@
 use std::collections::HashMap;
-use std::collections::{BTreeMap, VecDeque};
-use rust_decimal::Decimal;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::{Deserialize, Serialize};
+use slab::Slab;
 use std::cmp::Reverse;
+use crate::orderbook::clock::current_timestamp;
+use crate::orderbook::numeric::Numeric;
+use crate::orderbook::allocation::MatchingAlgorithm;
+use crate::orderbook::price_levels::PriceKey;
+use crate::orderbook::response::MarketOrderResponse;
 
 #[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
 pub enum Side{
@@ -10,12 +16,70 @@ pub enum Side{
     Bids
 }
 
+//one side of the book, keyed so that the best price comes first, with each level's orders in time priority
+#[derive(Debug,Clone,Serialize,Deserialize)]
+pub struct PriceLevels<K:PriceKey>{
+    pub(crate) levels: BTreeMap<K,Level>,
+    //every order resting on this side, linked into the queue of its level
+    pub(crate) orders: Slab<RestingOrder<K::Number>>,
+    //kept once `Orderbook::track_changes` was called, until the changes are taken
+    #[serde(skip)]
+    pub(crate) changes: Option<SideChanges<K::Number>>
+}
+
+//prices and orders of one side that changed since its changes were last taken
+#[derive(Debug,Clone)]
+pub(crate) struct SideChanges<N>{
+    pub(crate) prices: BTreeSet<N>,
+    pub(crate) order_ids: BTreeSet<u64>
+}
+
+//queue of one price level, threaded through the slab from the oldest order to the newest
+#[derive(Debug,Clone,Copy,Default,PartialEq,Serialize,Deserialize)]
+pub struct Level{
+    pub head: Option<usize>,
+    pub tail: Option<usize>,
+    pub order_count: u64
+}
+
+#[derive(Debug,Clone,PartialEq,Serialize,Deserialize)]
+pub struct RestingOrder<N=Decimal>{
+    pub order: OpenOrder<N>,
+    pub prev: Option<usize>,
+    pub next: Option<usize>
+}
+
+//where a live order sits, so it can be reached without searching its level
+#[derive(Debug,Clone,PartialEq,Serialize,Deserialize)]
+pub struct OrderHandle{
+    pub side: Side,
+    pub slot: usize
+}
+
+//bids are kept highest price first
+pub type Bids<N=Decimal>=PriceLevels<Reverse<N>>;
+//asks are kept lowest price first
+pub type Asks<N=Decimal>=PriceLevels<N>;
+
+//prices and quantities live in the book as `N`, everything that goes in or comes out is a `Decimal`
 #[derive(Debug,Serialize,Deserialize,Clone)]
-pub struct Orderbook{
-    pub bids: BTreeMap<Reverse<Decimal>,VecDeque<OpenOrder>>,
-    pub asks: BTreeMap<Decimal,VecDeque<OpenOrder>>,
+#[serde(bound(deserialize="A:Deserialize<'de>"))]
+pub struct Orderbook<N:Numeric=Decimal,A:MatchingAlgorithm=Allocation>{
+    pub bids: Bids<N>,
+    pub asks: Asks<N>,
+    //turns the book's `N` values into decimals and back
+    pub scale: N::Scale,
     pub order_id_index:u64,
-    pub order_map:HashMap<u64,OpenOrder>
+    pub order_map:HashMap<u64,OrderHandle>,
+    pub trade_log:TradeLog,
+    pub fee_schedule:FeeSchedule,
+    pub order_history:OrderHistory,
+    pub client_order_ids:ClientOrderIds,
+    pub price_band:PriceBand,
+    //limit orders rest without matching until the auction is uncrossed
+    pub in_auction:bool,
+    //how a taker's quantity is shared among the orders resting at a price
+    pub allocation:A
 }
 
 #[derive(Clone,Serialize,Deserialize)]
@@ -23,23 +87,51 @@ pub struct LimitOrder{
     pub price: Decimal,
     pub quantity: Decimal,
     pub side: Side,
-    pub user_id:u64
+    //the server fills this in from the authenticated api key
+    #[serde(default)]
+    pub user_id:u64,
+    #[serde(default)]
+    pub client_order_id:Option<String>
 }
 
-#[derive(Serialize,Deserialize)]
+#[derive(Clone,Serialize,Deserialize)]
 pub struct MarketOrder{
+    //left out when buying with `quote_quantity`
+    #[serde(default)]
     pub quantity: Decimal,
     pub side: Side,
-    pub user_id: u64
+    #[serde(default)]
+    pub user_id: u64,
+    #[serde(default)]
+    pub client_order_id:Option<String>,
+    //buys spend exactly this much quote currency instead of buying a base quantity
+    #[serde(default)]
+    pub quote_quantity:Option<Decimal>,
+    //how far past the best price at arrival the order may trade
+    #[serde(default)]
+    pub max_slippage_bps:Option<u32>,
+    //worst price the order may trade at
+    #[serde(default)]
+    pub protection_price:Option<Decimal>
 }
 
-#[derive(Debug,PartialEq,Serialize,Deserialize)]
+#[derive(Debug,Clone,PartialEq,Serialize,Deserialize)]
 pub struct Depth{
     pub bids: Vec<Order>,
     pub asks: Vec<Order>
 }
 
-#[derive(Debug,PartialEq,Serialize,Deserialize)]
+//what changed in the book since `Orderbook::take_changes` was last called, each as it is now
+#[derive(Debug,Clone,Default,PartialEq)]
+pub struct BookChanges{
+    //none for an order that left the book
+    pub orders: Vec<(u64,Option<OpenOrder>)>,
+    //none for a price that nothing rests at any more
+    pub bids: Vec<(Decimal,Option<Order>)>,
+    pub asks: Vec<(Decimal,Option<Order>)>
+}
+
+#[derive(Debug,Clone,PartialEq,Serialize,Deserialize)]
 pub struct Order{
     pub price: Decimal,
     pub quantity: Decimal,
@@ -47,32 +139,200 @@ pub struct Order{
     
 }
 
-#[derive(Serialize,Deserialize)]
+#[derive(Clone,Serialize,Deserialize)]
 pub struct ModifyOrderRequest{
     pub price:Option<Decimal>,
     pub quantity: Option<Decimal>,
     pub order_id:u64
 }
 
+#[derive(Clone,Serialize,Deserialize)]
+pub enum BatchOperation{
+    Place(LimitOrder),
+    Modify(ModifyOrderRequest),
+    Cancel{order_id:u64}
+}
+
+//every set field must match for an order to be cancelled, an empty filter cancels everything
+#[derive(Clone,Debug,Default,PartialEq,Serialize,Deserialize)]
+#[serde(default)]
+pub struct CancelFilter{
+    pub user_id: Option<u64>,
+    pub side: Option<Side>,
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>
+}
+
 #[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
-pub struct OpenOrder{
-    pub price: Decimal,
-    pub quantity: Decimal,
+pub enum OrderStatus{
+    New,
+    PartiallyFilled,
+    Filled,
+    Cancelled,
+    Expired,
+    Rejected
+}
+
+//resting orders hold the book's `N`, the ones handed out and archived are decimal
+#[derive(Clone,Debug,Serialize,Deserialize)]
+pub struct OpenOrder<N=Decimal>{
+    pub price: N,
+    pub quantity: N,
     pub side: Side,
-    pub quantity_filled:Decimal,
+    pub quantity_filled:N,
     pub user_id: u64,
-    pub order_id: u64
+    pub order_id: u64,
+    pub status: OrderStatus,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub client_order_id: Option<String>
+}
+
+//client order ids per user, limit orders point at their order id and market orders keep their response
+#[derive(Debug,Default,Serialize,Deserialize,Clone)]
+pub struct ClientOrderIds{
+    pub limit_orders: HashMap<u64,HashMap<String,u64>>,
+    pub market_orders: HashMap<u64,HashMap<String,MarketOrderResponse>>,
+    pub limit_order_keys: VecDeque<(u64,String)>,
+    pub market_order_keys: VecDeque<(u64,String)>
+}
+
+//orders that have left the book, bounded so memory does not grow forever
+#[derive(Debug,Default,Serialize,Deserialize,Clone)]
+pub struct OrderHistory{
+    pub orders: HashMap<u64,OpenOrder>,
+    pub archived_order_ids: VecDeque<u64>,
+    //every order ever archived, including the ones forgotten since
+    #[serde(default)]
+    pub archived_total: u64
 }
 
-impl OpenOrder {
-    pub fn new(price:Decimal,quantity:Decimal,side:Side,quantity_filled:Decimal,user_id:u64,order_id:u64)->OpenOrder{
-        OpenOrder { price, quantity, side, quantity_filled, user_id, order_id }
+impl Side{
+    pub fn opposite(&self)->Side{
+        match self{
+            Side::Asks=>Side::Bids,
+            Side::Bids=>Side::Asks
+        }
+    }
+}
+
+impl CancelFilter{
+    pub fn for_user(user_id:u64)->CancelFilter{
+        CancelFilter { user_id:Some(user_id), ..Default::default() }
+    }
+
+    pub fn matches<N:Numeric>(&self,order:&OpenOrder<N>,scale:&N::Scale)->bool{
+        self.user_id.is_none_or(|u|u==order.user_id)
+            && self.side.as_ref().is_none_or(|s|*s==order.side)
+            && self.min_price.is_none_or(|p|order.price>=N::round_price(p, RoundingStrategy::ToPositiveInfinity, scale))
+            && self.max_price.is_none_or(|p|order.price<=N::round_price(p, RoundingStrategy::ToNegativeInfinity, scale))
+    }
+}
+
+impl<N:Numeric> OpenOrder<N> {
+    pub fn new(price:N,quantity:N,side:Side,quantity_filled:N,user_id:u64,order_id:u64)->OpenOrder<N>{
+        let status = if quantity_filled==N::ZERO{
+            OrderStatus::New
+        }else if quantity_filled<quantity{
+            OrderStatus::PartiallyFilled
+        }else{
+            OrderStatus::Filled
+        };
+        let now = current_timestamp();
+        OpenOrder { price, quantity, side, quantity_filled, user_id, order_id, status, created_at:now, updated_at:now, client_order_id:None }
+    }
+
+    pub fn with_client_order_id(mut self,client_order_id:Option<String>)->OpenOrder<N>{
+        self.client_order_id=client_order_id;
+        self
+    }
+
+    pub fn open_quantity(&self)->N{
+        self.quantity-self.quantity_filled
+    }
+
+    //the order as callers see it
+    pub fn to_decimal(&self,scale:&N::Scale)->OpenOrder{
+        self.clone().into_decimal(scale)
+    }
+
+    pub fn into_decimal(self,scale:&N::Scale)->OpenOrder{
+        OpenOrder {
+            price: self.price.to_price(scale),
+            quantity: self.quantity.to_quantity(scale),
+            side: self.side,
+            quantity_filled: self.quantity_filled.to_quantity(scale),
+            user_id: self.user_id,
+            order_id: self.order_id,
+            status: self.status,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            client_order_id: self.client_order_id
+        }
+    }
+
+    pub fn is_terminal(&self)->bool{
+        matches!(self.status,OrderStatus::Filled|OrderStatus::Cancelled|OrderStatus::Expired|OrderStatus::Rejected)
+    }
+
+    pub(crate) fn fill(&mut self,quantity:N){
+        self.quantity_filled+=quantity;
+        self.status = if self.quantity_filled>=self.quantity{
+            OrderStatus::Filled
+        }else{
+            OrderStatus::PartiallyFilled
+        };
+        self.updated_at=current_timestamp();
+    }
+
+    pub(crate) fn cancel(&mut self){
+        self.status=OrderStatus::Cancelled;
+        self.updated_at=current_timestamp();
+    }
+
+    pub(crate) fn reject(&mut self){
+        self.status=OrderStatus::Rejected;
+        self.updated_at=current_timestamp();
+    }
+}
+
+//timestamps are bookkeeping only, two orders are the same if everything else matches
+impl<N:PartialEq> PartialEq for OpenOrder<N>{
+    fn eq(&self,other:&Self)->bool{
+        self.price==other.price
+            && self.quantity==other.quantity
+            && self.side==other.side
+            && self.quantity_filled==other.quantity_filled
+            && self.user_id==other.user_id
+            && self.order_id==other.order_id
+            && self.status==other.status
+            && self.client_order_id==other.client_order_id
     }
 }
 
 impl MarketOrder{
     pub fn new(quantity:Decimal,side:Side,user_id:u64)->MarketOrder{
-        MarketOrder { quantity , side, user_id }
+        MarketOrder { quantity , side, user_id, client_order_id:None, quote_quantity:None, max_slippage_bps:None, protection_price:None }
+    }
+
+    //a buy spending `quote_quantity` of the quote currency
+    pub fn buy_with_quote(quote_quantity:Decimal,user_id:u64)->MarketOrder{
+        MarketOrder { quote_quantity:Some(quote_quantity), ..MarketOrder::new(Decimal::ZERO, Side::Bids, user_id) }
+    }
+
+    pub fn with_max_slippage_bps(mut self,max_slippage_bps:u32)->MarketOrder{
+        self.max_slippage_bps=Some(max_slippage_bps);
+        self
+    }
+
+    pub fn with_protection_price(mut self,protection_price:Decimal)->MarketOrder{
+        self.protection_price=Some(protection_price);
+        self
+    }
+
+    pub fn with_client_order_id(mut self,client_order_id:String)->MarketOrder{
+        self.client_order_id=Some(client_order_id);
+        self
     }
 }
 
@@ -80,4 +340,156 @@ impl Order{
     pub fn new(price:Decimal,quantity:Decimal,order_count:u64)->Order{
         Order { price, quantity, order_count}
     }
+}
+
+#[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
+pub enum LiquidityRole{
+    Maker,
+    Taker
+}
+
+#[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
+pub struct FeeSchedule{
+    pub maker_fee_rate: Decimal,
+    pub taker_fee_rate: Decimal
+}
+
+#[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
+pub enum ReferencePrice{
+    LastTrade,
+    //exponential moving average of trade prices, the weight is what each new trade counts for
+    Ema(Decimal)
+}
+
+#[derive(Clone,Copy,Debug,PartialEq,Serialize,Deserialize)]
+pub enum BandAction{
+    //orders that would trade outside the band are turned away untouched
+    Reject,
+    //orders only trade up to the edge of the band
+    Truncate
+}
+
+#[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
+pub struct PriceBandConfig{
+    //how far from the reference price trades may print, either way
+    pub width_bps: u32,
+    pub reference: ReferencePrice,
+    pub action: BandAction
+}
+
+//the band only applies once the market has traded and there is a reference price
+#[derive(Clone,Debug,Default,PartialEq,Serialize,Deserialize)]
+pub struct PriceBand{
+    pub config: Option<PriceBandConfig>,
+    pub reference_price: Option<Decimal>
+}
+
+//strict time priority, the oldest order at a price fills first
+#[derive(Clone,Copy,Debug,Default,PartialEq,Serialize,Deserialize)]
+pub struct Fifo;
+
+//fills in proportion to the open quantity of each order, the rounding left over goes out in time priority
+#[derive(Clone,Debug,Default,PartialEq,Serialize,Deserialize)]
+#[serde(default)]
+pub struct ProRata{
+    //pro-rata shares below this are not handed out
+    pub min_allocation: Decimal,
+    //shares are rounded down to a multiple of this, no rounding when zero
+    pub lot_size: Decimal
+}
+
+//the first `fifo_bps` of the quantity goes out in time priority, the rest pro-rata
+#[derive(Clone,Debug,Default,PartialEq,Serialize,Deserialize)]
+pub struct PriceTimeProRata{
+    pub fifo_bps: u32,
+    pub pro_rata: ProRata
+}
+
+#[derive(Clone,Debug,Default,PartialEq,Serialize,Deserialize)]
+pub enum Allocation{
+    #[default]
+    Fifo,
+    ProRata(ProRata),
+    PriceTimeProRata(PriceTimeProRata)
+}
+
+//the single price an auction clears at and what it leaves unmatched
+#[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
+pub struct Uncross<N=Decimal>{
+    pub price: N,
+    pub volume: N,
+    pub imbalance: N,
+    //side with quantity left over at the price, none when both sides match exactly
+    pub imbalance_side: Option<Side>
+}
+
+#[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
+pub struct Trade{
+    pub trade_id: u64,
+    pub price: Decimal,
+    pub quantity: Decimal,
+    pub taker_side: Side,
+    pub maker_order_id: u64,
+    pub maker_user_id: u64,
+    pub maker_fee: Decimal,
+    //market orders are never assigned an order id
+    pub taker_order_id: Option<u64>,
+    pub taker_user_id: u64,
+    pub taker_fee: Decimal,
+    pub timestamp: u64
+}
+
+#[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
+pub struct PublicTrade{
+    pub trade_id: u64,
+    pub price: Decimal,
+    pub quantity: Decimal,
+    pub taker_side: Side,
+    pub timestamp: u64
+}
+
+#[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
+pub struct UserTrade{
+    pub trade_id: u64,
+    pub order_id: Option<u64>,
+    pub price: Decimal,
+    pub quantity: Decimal,
+    pub side: Side,
+    pub role: LiquidityRole,
+    pub fee: Decimal,
+    pub timestamp: u64
+}
+
+#[derive(Clone,Debug,Default,PartialEq,Serialize,Deserialize)]
+#[serde(default)]
+pub struct TradeHistoryQuery{
+    //cursor, only trades with a greater trade id are returned
+    pub from_trade_id: Option<u64>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub limit: Option<usize>
+}
+
+#[derive(Debug,Default,Serialize,Deserialize,Clone)]
+pub struct TradeLog{
+    pub trade_id_index: u64,
+    pub trades: VecDeque<Trade>
+}
+
+pub(crate) struct Taker{
+    pub order_id: Option<u64>,
+    pub user_id: u64,
+    pub side: Side
+}
+
+impl FeeSchedule{
+    pub fn new(maker_fee_rate:Decimal,taker_fee_rate:Decimal)->FeeSchedule{
+        FeeSchedule { maker_fee_rate, taker_fee_rate }
+    }
+}
+
+impl Default for FeeSchedule{
+    fn default()->Self{
+        FeeSchedule::new(Decimal::ZERO, Decimal::ZERO)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,123 @@
+#[cfg(test)]
+use pretty_assertions::{assert_eq};
+use rust_decimal::{dec, Decimal};
+
+use crate::{orderbook::types::Side, Allocation, Fifo, LimitOrder, MarketOrder, MatchingAlgorithm, Numeric, OpenOrder, OrderStatus, PriceTimeProRata, ProRata};
+
+use super::{new_orderbook, new_orderbook_with, price, quantity, scale, Number};
+
+//a policy the crate knows nothing about, the newest order at a level is filled first
+#[derive(Clone)]
+struct NewestFirst;
+
+impl MatchingAlgorithm for NewestFirst{
+    fn allocate<N:Numeric>(&self,open_quantities:&[N],quantity:N,_:&N::Scale)->Vec<N>{
+        let mut remaining=quantity;
+        let mut fills:Vec<N>=open_quantities.iter().rev().map(|open|{
+            let fill=remaining.min(*open);
+            remaining-=fill;
+            fill
+        }).collect();
+        fills.reverse();
+        fills
+    }
+}
+
+fn quantities(quantities:&[Decimal])->Vec<Number>{
+    quantities.iter().map(|q|quantity(*q)).collect()
+}
+
+fn limit_order(price:Decimal,quantity:Decimal,side:Side,user_id:u64)->LimitOrder{
+    LimitOrder{price,quantity,side,user_id,client_order_id:None}
+}
+
+#[test]
+fn test_fifo_fills_the_oldest_order_first(){
+    assert_eq!(Fifo.allocate(&quantities(&[dec!(10),dec!(30)]), quantity(dec!(15)), &scale()),quantities(&[dec!(10),dec!(5)]));
+}
+
+#[test]
+fn test_pro_rata_fills_in_proportion(){
+    let pro_rata = ProRata{min_allocation:dec!(0),lot_size:dec!(1)};
+    assert_eq!(pro_rata.allocate(&quantities(&[dec!(10),dec!(30),dec!(60)]), quantity(dec!(50)), &scale()),quantities(&[dec!(5),dec!(15),dec!(30)]));
+    //the whole level is taken when the quantity covers it
+    assert_eq!(pro_rata.allocate(&quantities(&[dec!(10),dec!(30)]), quantity(dec!(50)), &scale()),quantities(&[dec!(10),dec!(30)]));
+}
+
+#[test]
+fn test_pro_rata_rounding_goes_out_in_time_priority(){
+    //0.2 is below the minimum and 9.8 rounds down to 9, the lot left over goes to the oldest order
+    let pro_rata = ProRata{min_allocation:dec!(1),lot_size:dec!(1)};
+    assert_eq!(pro_rata.allocate(&quantities(&[dec!(2),dec!(98)]), quantity(dec!(10)), &scale()),quantities(&[dec!(1),dec!(9)]));
+}
+
+#[test]
+fn test_price_time_pro_rata_gives_the_queue_head_priority(){
+    //8 of 20 in time priority, the other 12 pro-rata over what is left
+    let hybrid = PriceTimeProRata{fifo_bps:4000,pro_rata:ProRata{min_allocation:dec!(0),lot_size:dec!(1)}};
+    assert_eq!(hybrid.allocate(&quantities(&[dec!(10),dec!(10),dec!(20)]), quantity(dec!(20)), &scale()),quantities(&[dec!(10),dec!(3),dec!(7)]));
+}
+
+#[test]
+fn test_orderbook_matches_with_its_allocation(){
+    let mut orderbook = new_orderbook();
+    orderbook.set_allocation(Allocation::ProRata(ProRata::default()));
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(10), Side::Asks, 1));
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(30), Side::Asks, 2));
+    let response = orderbook.add_market_order(MarketOrder::new(dec!(20), Side::Bids, 3));
+    assert!(response.is_success());
+    assert_eq!(orderbook.get_order(1).unwrap().quantity_filled,dec!(5));
+    assert_eq!(orderbook.get_order(2).unwrap().quantity_filled,dec!(15));
+
+    //a limit order crossing the level is shared out the same way
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(4), Side::Bids, 3));
+    assert_eq!(orderbook.get_order(1).unwrap().quantity_filled,dec!(6));
+    assert_eq!(orderbook.get_order(2).unwrap().quantity_filled,dec!(18));
+}
+
+#[test]
+fn test_order_with_nothing_open_does_not_stop_matching(){
+    for allocation in [Allocation::Fifo,Allocation::ProRata(ProRata::default())]{
+        let mut orderbook = new_orderbook();
+        orderbook.set_allocation(allocation);
+        //a zero quantity order at the front of the best level, ahead of real liquidity there and behind it
+        orderbook.rest_order(OpenOrder::new(price(dec!(100)), quantity(dec!(0)), Side::Asks, quantity(dec!(0)), 1, 99));
+        orderbook.add_limit_order(limit_order(dec!(100), dec!(5), Side::Asks, 1));
+        orderbook.add_limit_order(limit_order(dec!(101), dec!(5), Side::Asks, 1));
+        let bid = orderbook.add_limit_order(limit_order(dec!(101), dec!(10), Side::Bids, 2));
+        assert_eq!(bid.status,OrderStatus::Filled);
+        assert!(orderbook.asks.is_empty());
+        assert!(orderbook.bids.is_empty());
+        //it is closed as filled once reached, without a trade
+        assert_eq!(orderbook.get_order(99).unwrap().status,OrderStatus::Filled);
+        assert_eq!(orderbook.trade_log.trades.len(),2);
+    }
+}
+
+#[test]
+fn test_zero_allocation_keeps_the_orders_place(){
+    let mut orderbook = new_orderbook();
+    orderbook.set_allocation(Allocation::ProRata(ProRata{min_allocation:dec!(2),..ProRata::default()}));
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(99), Side::Asks, 1));
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(1), Side::Asks, 2));
+    //the small order's share is under the minimum, so it gets nothing and keeps its place
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(10), Side::Bids, 3));
+    assert_eq!(orderbook.get_order(1).unwrap().quantity_filled,dec!(10));
+    assert_eq!(orderbook.get_order(2).unwrap().status,OrderStatus::New);
+    assert_eq!(orderbook.asks.orders_at(price(dec!(100))).map(|o|o.order_id).collect::<Vec<_>>(),vec![1,2]);
+}
+
+#[test]
+fn test_orderbook_matches_with_an_outside_algorithm(){
+    let mut orderbook = new_orderbook_with(NewestFirst);
+    assert!(!orderbook.allocation.in_time_priority());
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(10), Side::Asks, 1));
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(10), Side::Asks, 2));
+    let response = orderbook.add_market_order(MarketOrder::new(dec!(15), Side::Bids, 3));
+    assert!(response.is_success());
+    assert_eq!(orderbook.get_order(1).unwrap().quantity_filled,dec!(5));
+    assert_eq!(orderbook.get_order(2).unwrap().status,OrderStatus::Filled);
+    assert!(Fifo.in_time_priority());
+    assert!(Allocation::Fifo.in_time_priority());
+    assert!(!Allocation::ProRata(ProRata::default()).in_time_priority());
+}
\ No newline at end of file
@@ -0,0 +1,78 @@
+#[cfg(test)]
+use pretty_assertions::{assert_eq};
+use rust_decimal::{dec, Decimal};
+
+use crate::{orderbook::types::Side, CustomError, LimitOrder, MarketOrder, MarketOrderResponse, OrderStatus, Uncross};
+
+use super::{new_orderbook, Orderbook};
+
+fn limit_order(price:Decimal,quantity:Decimal,side:Side,user_id:u64)->LimitOrder{
+    LimitOrder{price,quantity,side,user_id,client_order_id:None}
+}
+
+//bids 102x5 and 100x5 against asks 99x4 and 101x4, 5 can trade at 101 or 102 with 3 asks left over
+fn auction_orderbook()->Orderbook{
+    let mut orderbook = new_orderbook();
+    orderbook.start_auction();
+    orderbook.add_limit_order(limit_order(dec!(102), dec!(5), Side::Bids, 1));
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(5), Side::Bids, 1));
+    orderbook.add_limit_order(limit_order(dec!(99), dec!(4), Side::Asks, 2));
+    orderbook.add_limit_order(limit_order(dec!(101), dec!(4), Side::Asks, 2));
+    orderbook
+}
+
+#[test]
+fn test_auction_collects_orders_without_matching(){
+    let mut orderbook = auction_orderbook();
+    assert_eq!(orderbook.get_bids().len(),2);
+    assert_eq!(orderbook.get_asks().len(),2);
+    assert!(orderbook.get_recent_trades(10).is_empty());
+    assert_eq!(orderbook.get_order(1).unwrap().status,OrderStatus::New);
+
+    let response = orderbook.add_market_order(MarketOrder::new(dec!(1), Side::Bids, 3));
+    assert_eq!(response,MarketOrderResponse::new(false, None, None, Some(CustomError::AuctionInProgress)));
+}
+
+#[test]
+fn test_indicative_uncross_maximises_volume(){
+    let orderbook = auction_orderbook();
+    let expected = Uncross{price:dec!(101),volume:dec!(5),imbalance:dec!(3),imbalance_side:Some(Side::Asks)};
+    assert_eq!(orderbook.indicative_uncross(),Some(expected));
+    assert_eq!(new_orderbook().indicative_uncross(),None);
+}
+
+#[test]
+fn test_indicative_uncross_breaks_ties_towards_reference_price(){
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(limit_order(dec!(103), dec!(1), Side::Asks, 2));
+    orderbook.add_limit_order(limit_order(dec!(103), dec!(1), Side::Bids, 1));
+    orderbook.start_auction();
+    orderbook.add_limit_order(limit_order(dec!(101), dec!(5), Side::Bids, 1));
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(5), Side::Asks, 2));
+    let uncross = orderbook.indicative_uncross().unwrap();
+    assert_eq!(uncross.price,dec!(101));
+    assert_eq!(uncross.imbalance_side,None);
+}
+
+#[test]
+fn test_end_auction_trades_at_a_single_price(){
+    let mut orderbook = auction_orderbook();
+    let uncross = orderbook.end_auction().unwrap();
+    assert_eq!(uncross.price,dec!(101));
+    assert!(!orderbook.in_auction);
+
+    let trades = orderbook.get_recent_trades(10);
+    assert_eq!(trades.len(),2);
+    assert!(trades.iter().all(|t|t.price==dec!(101)));
+    assert_eq!(trades.iter().map(|t|t.quantity).sum::<Decimal>(),dec!(5));
+
+    assert_eq!(orderbook.get_order(1).unwrap().status,OrderStatus::Filled);
+    assert_eq!(orderbook.get_order(3).unwrap().status,OrderStatus::Filled);
+    assert_eq!(orderbook.get_best_bid().unwrap(),dec!(100));
+    assert_eq!(orderbook.get_best_ask().unwrap(),dec!(101));
+    assert_eq!(orderbook.get_order(4).unwrap().quantity_filled,dec!(1));
+
+    //back to continuous matching
+    let response = orderbook.add_market_order(MarketOrder::new(dec!(3), Side::Bids, 3));
+    assert!(response.is_success());
+}
\ No newline at end of file
@@ -0,0 +1,161 @@
+#[cfg(test)]
+use pretty_assertions::{assert_eq};
+use rust_decimal::dec;
+
+use crate::{orderbook::types::Side, BandAction, BatchOperation, BatchResult, CustomError, DeleteResponse, LimitOrder, ModifyOrderRequest, ModifyOrderResponse, OpenOrder, OrderStatus, PriceBandConfig, ReferencePrice, MAX_BATCH_SIZE};
+
+use super::new_orderbook;
+
+#[test]
+fn test_batch_is_applied_in_order(){
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None});
+    let operations = vec![
+        BatchOperation::Place(LimitOrder{price:dec!(101),quantity:dec!(10),side:Side::Asks,user_id:1,client_order_id:None}),
+        BatchOperation::Modify(ModifyOrderRequest{price:None,quantity:Some(dec!(20)),order_id:2}),
+        BatchOperation::Cancel{order_id:1},
+        BatchOperation::Cancel{order_id:7}
+    ];
+    let response = orderbook.apply_batch(operations, false);
+    assert!(!response.success);
+    assert_eq!(response.results,vec![
+        BatchResult::Placed(OpenOrder::new(dec!(101), dec!(10), Side::Asks, dec!(0), 1, 2)),
+        BatchResult::Modified(ModifyOrderResponse::new(dec!(101), dec!(20), 2)),
+        BatchResult::Cancelled(DeleteResponse::new(dec!(100), dec!(10), dec!(0), 1)),
+        BatchResult::Failed(CustomError::OrderDoesNotExist)
+    ]);
+    //the failed item does not undo the others
+    assert!(orderbook.bids.is_empty());
+    assert_eq!(orderbook.get_order(2).unwrap().quantity,dec!(20));
+}
+
+#[test]
+fn test_all_or_nothing_batch_applies_nothing_on_failure(){
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None});
+    orderbook.delete_order(1).unwrap();
+    let operations = vec![
+        BatchOperation::Place(LimitOrder{price:dec!(101),quantity:dec!(10),side:Side::Asks,user_id:1,client_order_id:None}),
+        BatchOperation::Cancel{order_id:1}
+    ];
+    let response = orderbook.apply_batch(operations, true);
+    assert!(!response.success);
+    assert_eq!(response.results,vec![
+        BatchResult::NotApplied,
+        BatchResult::Failed(CustomError::OrderAlreadyClosed)
+    ]);
+    assert!(orderbook.asks.is_empty());
+    assert_eq!(orderbook.order_id_index,1);
+}
+
+#[test]
+fn test_all_or_nothing_batch_sees_its_own_fills(){
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None});
+    //the place fills order 1, so cancelling it afterwards has to fail
+    let operations = vec![
+        BatchOperation::Place(LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Asks,user_id:2,client_order_id:None}),
+        BatchOperation::Cancel{order_id:1}
+    ];
+    let response = orderbook.apply_batch(operations, true);
+    assert_eq!(response.results[1],BatchResult::Failed(CustomError::OrderAlreadyMatched));
+    assert_eq!(orderbook.get_bids().len(),1);
+    assert!(orderbook.trade_log.trades.is_empty());
+
+    let operations = vec![
+        BatchOperation::Place(LimitOrder{price:dec!(100),quantity:dec!(4),side:Side::Asks,user_id:2,client_order_id:None}),
+        BatchOperation::Cancel{order_id:1}
+    ];
+    let response = orderbook.apply_batch(operations, true);
+    assert!(response.success);
+    assert!(orderbook.bids.is_empty());
+    assert_eq!(orderbook.trade_log.trades.len(),1);
+}
+
+#[test]
+fn test_all_or_nothing_batch_reaches_past_the_orders_it_cancels(){
+    let mut orderbook = new_orderbook();
+    for price in [dec!(101),dec!(102),dec!(103),dec!(104)]{
+        orderbook.add_limit_order(LimitOrder{price,quantity:dec!(5),side:Side::Asks,user_id:1,client_order_id:None});
+    }
+    //with the ask at 101 pulled first the bid has to reach 103 to fill, so there is nothing left of it to cancel
+    let operations = vec![
+        BatchOperation::Cancel{order_id:1},
+        BatchOperation::Place(LimitOrder{price:dec!(103),quantity:dec!(10),side:Side::Bids,user_id:2,client_order_id:None}),
+        BatchOperation::Cancel{order_id:5}
+    ];
+    let response = orderbook.apply_batch(operations, true);
+    assert_eq!(response.results,vec![
+        BatchResult::NotApplied,
+        BatchResult::NotApplied,
+        BatchResult::Failed(CustomError::OrderAlreadyMatched)
+    ]);
+    assert_eq!(orderbook.asks.order_count(),4);
+
+    let operations = vec![
+        BatchOperation::Cancel{order_id:1},
+        BatchOperation::Place(LimitOrder{price:dec!(103),quantity:dec!(10),side:Side::Bids,user_id:2,client_order_id:None}),
+        BatchOperation::Cancel{order_id:4}
+    ];
+    let response = orderbook.apply_batch(operations, true);
+    assert!(response.success);
+    assert!(orderbook.asks.is_empty());
+}
+
+#[test]
+fn test_all_or_nothing_batch_fails_on_a_rejected_placement(){
+    let mut orderbook = new_orderbook();
+    orderbook.set_price_band(Some(PriceBandConfig{width_bps:1000,reference:ReferencePrice::LastTrade,action:BandAction::Reject}));
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Asks,user_id:1,client_order_id:None});
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Bids,user_id:2,client_order_id:None});
+    let resting = orderbook.add_limit_order(LimitOrder{price:dec!(95),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None});
+    let operations = vec![
+        BatchOperation::Cancel{order_id:resting.order_id},
+        BatchOperation::Place(LimitOrder{price:dec!(120),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None})
+    ];
+    let response = orderbook.apply_batch(operations.clone(), true);
+    assert!(!response.success);
+    assert_eq!(response.results,vec![BatchResult::NotApplied,BatchResult::Failed(CustomError::PriceBandExceeded)]);
+    assert_eq!(orderbook.get_order(resting.order_id).unwrap().status,OrderStatus::New);
+
+    //without all or nothing the rejected order is placed and reported as such
+    let response = orderbook.apply_batch(operations, false);
+    assert!(matches!(&response.results[1],BatchResult::Placed(o) if o.status==OrderStatus::Rejected));
+    assert!(orderbook.bids.is_empty());
+}
+
+#[test]
+fn test_batch_too_large(){
+    let mut orderbook = new_orderbook();
+    let operations = (0..=MAX_BATCH_SIZE as u64).map(|order_id|BatchOperation::Cancel{order_id}).collect();
+    let response = orderbook.apply_batch(operations, false);
+    assert!(!response.success);
+    assert_eq!(response.error,Some(CustomError::BatchTooLarge));
+}
+
+#[test]
+fn test_user_batch_is_turned_away_when_it_names_another_users_order(){
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None});
+    let operations = vec![
+        BatchOperation::Place(LimitOrder{price:dec!(101),quantity:dec!(10),side:Side::Asks,user_id:1,client_order_id:None}),
+        BatchOperation::Cancel{order_id:1}
+    ];
+    let response = orderbook.apply_user_batch(2, operations.clone(), false);
+    assert!(!response.success);
+    assert_eq!(response.error,Some(CustomError::OrderDoesNotExist));
+    assert!(orderbook.asks.is_empty());
+    assert_eq!(orderbook.get_order(1).unwrap().quantity,dec!(10));
+
+    //orders placed belong to the sender whatever the user id they carry
+    let response = orderbook.apply_user_batch(1, operations, false);
+    assert!(response.success);
+    assert_eq!(orderbook.get_order(2).unwrap().user_id,1);
+    let operations = vec![
+        BatchOperation::Place(LimitOrder{price:dec!(102),quantity:dec!(10),side:Side::Asks,user_id:1,client_order_id:None}),
+        BatchOperation::Cancel{order_id:3}
+    ];
+    let response = orderbook.apply_user_batch(2, operations, false);
+    assert!(response.success);
+    assert_eq!(orderbook.get_order(3).unwrap().user_id,2);
+}
\ No newline at end of file
@@ -0,0 +1,52 @@
+#[cfg(test)]
+use pretty_assertions::{assert_eq};
+use rust_decimal::dec;
+
+use crate::{orderbook::types::Side, CancelFilter, DeleteResponse, LimitOrder, OrderStatus};
+
+use super::{new_orderbook, Orderbook};
+
+fn populated_orderbook()->Orderbook{
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None});
+    orderbook.add_limit_order(LimitOrder{price:dec!(99),quantity:dec!(10),side:Side::Bids,user_id:2,client_order_id:None});
+    orderbook.add_limit_order(LimitOrder{price:dec!(101),quantity:dec!(10),side:Side::Asks,user_id:1,client_order_id:None});
+    orderbook.add_limit_order(LimitOrder{price:dec!(105),quantity:dec!(10),side:Side::Asks,user_id:1,client_order_id:None});
+    orderbook
+}
+
+#[test]
+fn test_cancel_all_for_user(){
+    let mut orderbook = populated_orderbook();
+    let result = orderbook.cancel_all(CancelFilter::for_user(1));
+    assert_eq!(result,vec![
+        DeleteResponse::new(dec!(100), dec!(10), dec!(0), 1),
+        DeleteResponse::new(dec!(101), dec!(10), dec!(0), 3),
+        DeleteResponse::new(dec!(105), dec!(10), dec!(0), 4)
+    ]);
+    assert!(orderbook.asks.is_empty());
+    assert_eq!(orderbook.bids.len(),1);
+    assert_eq!(orderbook.get_order(1).unwrap().status,OrderStatus::Cancelled);
+}
+
+#[test]
+fn test_cancel_all_by_side_and_price_range(){
+    let mut orderbook = populated_orderbook();
+    let filter = CancelFilter{side:Some(Side::Asks),max_price:Some(dec!(102)),..Default::default()};
+    let result = orderbook.cancel_all(filter);
+    assert_eq!(result,vec![DeleteResponse::new(dec!(101), dec!(10), dec!(0), 3)]);
+    assert_eq!(orderbook.get_best_ask(),Some(dec!(105)));
+
+    let filter = CancelFilter{min_price:Some(dec!(99.5)),..Default::default()};
+    let result = orderbook.cancel_all(filter);
+    assert_eq!(result,vec![
+        DeleteResponse::new(dec!(100), dec!(10), dec!(0), 1),
+        DeleteResponse::new(dec!(105), dec!(10), dec!(0), 4)
+    ]);
+
+    //an empty filter takes out whatever is left
+    let result = orderbook.cancel_all(CancelFilter::default());
+    assert_eq!(result.len(),1);
+    assert!(orderbook.bids.is_empty());
+    assert!(orderbook.order_map.is_empty());
+}
\ No newline at end of file
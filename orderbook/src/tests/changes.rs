@@ -0,0 +1,101 @@
+#[cfg(test)]
+use pretty_assertions::{assert_eq};
+use std::{cmp::Reverse, collections::BTreeMap};
+use rust_decimal::{dec, Decimal};
+
+use crate::{orderbook::types::{Order, Side}, BookChanges, CancelFilter, Depth, LimitOrder, MarketOrder, ModifyOrderRequest, OpenOrder};
+
+use super::{new_orderbook, Orderbook};
+
+//a copy of the book kept up to date from its changes alone
+#[derive(Default)]
+struct Follower{
+    orders: BTreeMap<u64,OpenOrder>,
+    bids: BTreeMap<Reverse<Decimal>,Order>,
+    asks: BTreeMap<Decimal,Order>
+}
+
+impl Follower{
+    fn follow(&mut self,orderbook:&mut Orderbook){
+        let BookChanges { orders, bids, asks }=orderbook.take_changes();
+        for (order_id,order) in orders{
+            match order{
+                Some(order)=>self.orders.insert(order_id, order),
+                None=>self.orders.remove(&order_id)
+            };
+        }
+        for (price,level) in bids{
+            match level{
+                Some(level)=>self.bids.insert(Reverse(price), level),
+                None=>self.bids.remove(&Reverse(price))
+            };
+        }
+        for (price,level) in asks{
+            match level{
+                Some(level)=>self.asks.insert(price, level),
+                None=>self.asks.remove(&price)
+            };
+        }
+        assert_eq!(Depth { bids:self.bids.values().cloned().collect(), asks:self.asks.values().cloned().collect() },orderbook.get_depth());
+        let mut open_orders=orderbook.get_open_orders(1);
+        open_orders.extend(orderbook.get_open_orders(2));
+        open_orders.sort_by_key(|o|o.order_id);
+        assert_eq!(self.orders.values().cloned().collect::<Vec<_>>(),open_orders);
+    }
+}
+
+#[test]
+fn test_book_without_tracking_has_no_changes(){
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(LimitOrder{price:dec!(105),quantity:dec!(2),side:Side::Asks,user_id:1,client_order_id:None});
+    assert_eq!(orderbook.take_changes(),BookChanges::default());
+}
+
+#[test]
+fn test_changes_name_only_what_a_command_touched(){
+    let mut orderbook = new_orderbook();
+    let ask=orderbook.add_limit_order(LimitOrder{price:dec!(105),quantity:dec!(2),side:Side::Asks,user_id:1,client_order_id:None});
+    let other=orderbook.add_limit_order(LimitOrder{price:dec!(106),quantity:dec!(2),side:Side::Asks,user_id:1,client_order_id:None});
+    //what rests already is handed out first
+    orderbook.track_changes();
+    let changes=orderbook.take_changes();
+    assert_eq!(changes.orders.iter().map(|(order_id,_)|*order_id).collect::<Vec<_>>(),vec![ask.order_id,other.order_id]);
+    assert_eq!(changes.asks.len(),2);
+    assert_eq!(orderbook.take_changes(),BookChanges::default());
+
+    orderbook.add_market_order(MarketOrder::new(dec!(1), Side::Bids, 2));
+    let changes=orderbook.take_changes();
+    assert_eq!(changes.orders.len(),1);
+    assert_eq!(changes.orders[0].1.as_ref().map(|o|o.open_quantity()),Some(dec!(1)));
+    assert_eq!(changes.asks,vec![(dec!(105),Some(Order::new(dec!(105), dec!(1), 1)))]);
+    assert!(changes.bids.is_empty());
+
+    orderbook.delete_order(ask.order_id).unwrap();
+    let changes=orderbook.take_changes();
+    assert_eq!(changes.orders,vec![(ask.order_id,None)]);
+    assert_eq!(changes.asks,vec![(dec!(105),None)]);
+}
+
+#[test]
+fn test_changes_keep_a_copy_of_the_book_up_to_date(){
+    let mut orderbook = new_orderbook();
+    let mut follower=Follower::default();
+    orderbook.add_limit_order(LimitOrder{price:dec!(99),quantity:dec!(3),side:Side::Bids,user_id:1,client_order_id:None});
+    orderbook.track_changes();
+    follower.follow(&mut orderbook);
+
+    let ask=orderbook.add_limit_order(LimitOrder{price:dec!(101),quantity:dec!(4),side:Side::Asks,user_id:1,client_order_id:None});
+    orderbook.add_limit_order(LimitOrder{price:dec!(101),quantity:dec!(1),side:Side::Asks,user_id:2,client_order_id:None});
+    orderbook.add_limit_order(LimitOrder{price:dec!(98),quantity:dec!(2),side:Side::Bids,user_id:2,client_order_id:None});
+    follower.follow(&mut orderbook);
+
+    orderbook.modify_order(ModifyOrderRequest{price:Some(dec!(102)),quantity:None,order_id:ask.order_id}).unwrap();
+    follower.follow(&mut orderbook);
+    //sweeps a level and a half, leaving the crossing bid resting
+    orderbook.add_limit_order(LimitOrder{price:dec!(102),quantity:dec!(9),side:Side::Bids,user_id:2,client_order_id:None});
+    follower.follow(&mut orderbook);
+    orderbook.add_market_order(MarketOrder::new(dec!(5), Side::Asks, 1));
+    follower.follow(&mut orderbook);
+    orderbook.cancel_all(CancelFilter::for_user(2));
+    follower.follow(&mut orderbook);
+}
\ No newline at end of file
@@ -0,0 +1,93 @@
+#[cfg(test)]
+use pretty_assertions::{assert_eq};
+use rust_decimal::dec;
+
+use crate::{orderbook::types::Side, CustomError, LimitOrder, MarketOrder, OpenOrder, OrderStatus, IDEMPOTENCY_WINDOW_MS};
+
+use super::new_orderbook;
+
+fn limit_order(price:rust_decimal::Decimal,side:Side,user_id:u64,client_order_id:&str)->LimitOrder{
+    LimitOrder{price,quantity:dec!(10),side,user_id,client_order_id:Some(client_order_id.to_string())}
+}
+
+#[test]
+fn test_resubmitted_limit_order_returns_original(){
+    let mut orderbook = new_orderbook();
+    let original = orderbook.add_limit_order(limit_order(dec!(100), Side::Bids, 1, "abc"));
+    let retry = orderbook.add_limit_order(limit_order(dec!(100), Side::Bids, 1, "abc"));
+    assert_eq!(retry,original);
+    assert_eq!(orderbook.order_id_index,1);
+    assert_eq!(orderbook.get_bids()[0].quantity,dec!(10));
+}
+
+#[test]
+fn test_client_order_ids_are_per_user(){
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(limit_order(dec!(100), Side::Bids, 1, "abc"));
+    let other_user = orderbook.add_limit_order(limit_order(dec!(100), Side::Bids, 2, "abc"));
+    assert_eq!(other_user,OpenOrder::new(dec!(100), dec!(10), Side::Bids, dec!(0), 2, 2).with_client_order_id(Some("abc".to_string())));
+    assert_eq!(orderbook.get_order_by_client_order_id(2, "abc").unwrap().order_id,2);
+}
+
+#[test]
+fn test_resubmitted_filled_limit_order_is_not_placed_again(){
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Asks,user_id:1,client_order_id:None});
+    let original = orderbook.add_limit_order(limit_order(dec!(100), Side::Bids, 2, "abc"));
+    assert_eq!(original.status,OrderStatus::Filled);
+    let retry = orderbook.add_limit_order(limit_order(dec!(100), Side::Bids, 2, "abc"));
+    assert_eq!(retry,original);
+    assert!(orderbook.bids.is_empty());
+    assert_eq!(orderbook.trade_log.trades.len(),1);
+}
+
+#[test]
+fn test_client_order_id_is_free_again_once_its_order_closed_outside_the_window(){
+    let mut orderbook = new_orderbook();
+    let original = orderbook.add_limit_order(limit_order(dec!(100), Side::Bids, 1, "abc"));
+    orderbook.delete_order(original.order_id).unwrap();
+    //a retry right after is still answered with the closed order
+    assert_eq!(orderbook.add_limit_order(limit_order(dec!(100), Side::Bids, 1, "abc")).status,OrderStatus::Cancelled);
+    if let Some(o)=orderbook.order_history.orders.get_mut(&original.order_id){
+        o.created_at-=IDEMPOTENCY_WINDOW_MS;
+    }
+    let reused = orderbook.add_limit_order(limit_order(dec!(101), Side::Bids, 1, "abc"));
+    assert_eq!(reused,OpenOrder::new(dec!(101), dec!(10), Side::Bids, dec!(0), 1, 2).with_client_order_id(Some("abc".to_string())));
+    assert_eq!(orderbook.get_order_by_client_order_id(1, "abc").unwrap().order_id,2);
+    //the id is queued to be forgotten only once
+    assert_eq!(orderbook.client_order_ids.limit_order_keys.len(),1);
+}
+
+#[test]
+fn test_live_order_keeps_its_client_order_id_past_the_window(){
+    let mut orderbook = new_orderbook();
+    let original = orderbook.add_limit_order(limit_order(dec!(100), Side::Bids, 1, "abc"));
+    if let Some(o)=orderbook.resting_order_mut(original.order_id){
+        o.created_at-=IDEMPOTENCY_WINDOW_MS;
+    }
+    let retry = orderbook.add_limit_order(limit_order(dec!(100), Side::Bids, 1, "abc"));
+    assert_eq!(retry,original);
+    assert_eq!(orderbook.bids.order_count(),1);
+}
+
+#[test]
+fn test_resubmitted_market_order_returns_original(){
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(15),side:Side::Asks,user_id:1,client_order_id:None});
+    let original = orderbook.add_market_order(MarketOrder::new(dec!(10), Side::Bids, 2).with_client_order_id("abc".to_string()));
+    let retry = orderbook.add_market_order(MarketOrder::new(dec!(10), Side::Bids, 2).with_client_order_id("abc".to_string()));
+    assert_eq!(retry,original);
+    assert_eq!(orderbook.get_asks()[0].quantity,dec!(5));
+}
+
+#[test]
+fn test_delete_order_by_client_order_id(){
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(limit_order(dec!(100), Side::Bids, 1, "abc"));
+    assert_eq!(orderbook.delete_order_by_client_order_id(2, "abc").unwrap_err(),CustomError::OrderDoesNotExist);
+    let response = orderbook.delete_order_by_client_order_id(1, "abc");
+    assert!(response.is_ok());
+    assert!(orderbook.bids.is_empty());
+    assert_eq!(orderbook.get_order_by_client_order_id(1, "abc").unwrap().status,OrderStatus::Cancelled);
+    assert_eq!(orderbook.delete_order_by_client_order_id(1, "abc").unwrap_err(),CustomError::OrderAlreadyClosed);
+}
\ No newline at end of file
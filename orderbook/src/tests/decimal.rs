@@ -0,0 +1,61 @@
+use rust_decimal::Decimal;
+
+use crate::MatchingAlgorithm;
+
+pub(super) type Number=Decimal;
+pub(super) type Orderbook=crate::Orderbook<Number>;
+
+pub(super) fn new_orderbook()->Orderbook{
+    Orderbook::new()
+}
+
+pub(super) fn new_orderbook_with<A:MatchingAlgorithm>(allocation:A)->crate::Orderbook<Number,A>{
+    crate::Orderbook::with_algorithm((), allocation)
+}
+
+pub(super) fn scale(){}
+
+pub(super) fn price(price:Decimal)->Number{
+    price
+}
+
+pub(super) fn quantity(quantity:Decimal)->Number{
+    quantity
+}
+
+#[path="limit_order.rs"]
+mod limit_order;
+#[path="tests.rs"]
+mod tests;
+#[path="market_order.rs"]
+mod market_order;
+#[path="delete_order.rs"]
+mod delete_order;
+#[path="modify_order.rs"]
+mod modify_order;
+#[path="get_order.rs"]
+mod get_order;
+#[path="depth.rs"]
+mod depth;
+#[path="trades.rs"]
+mod trades;
+#[path="order_history.rs"]
+mod order_history;
+#[path="cancel_all.rs"]
+mod cancel_all;
+#[path="batch.rs"]
+mod batch;
+#[path="client_order_ids.rs"]
+mod client_order_ids;
+#[path="price_bands.rs"]
+mod price_bands;
+#[path="market_protection.rs"]
+mod market_protection;
+#[path="auction.rs"]
+mod auction;
+#[path="allocation.rs"]
+mod allocation;
+#[path="matching.rs"]
+mod matching;
+#[path="changes.rs"]
+mod changes;
\ No newline at end of file
@@ -2,24 +2,26 @@
 use pretty_assertions::{assert_eq};
 use rust_decimal::dec;
 
-use crate::{orderbook::{response::CustomError, types::Side}, DeleteResponse, ErrorResponse, LimitOrder, Orderbook};
+use crate::{orderbook::{response::CustomError, types::Side}, DeleteResponse, LimitOrder, MarketOrder, MarketOrderResponse, ModifyOrderRequest};
+
+use super::new_orderbook;
 
 #[test]
 fn test_delete_order(){
-    let mut orderbook = Orderbook::new();
+    let mut orderbook = new_orderbook();
     
     //Asks Order
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     let open_order=orderbook.add_limit_order(limit_order);
     let result = orderbook.delete_order(open_order.order_id);
     assert_eq!(result,Ok(DeleteResponse::new(dec!(105),dec!(200),dec!(0),open_order.order_id)));
 
     drop(orderbook);
 
-    let mut orderbook = Orderbook::new();
+    let mut orderbook = new_orderbook();
     
     //Bids Order
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     let open_order=orderbook.add_limit_order(limit_order);
     let result = orderbook.delete_order(open_order.order_id);
     assert_eq!(result,Ok(DeleteResponse::new(dec!(105),dec!(200),dec!(0),open_order.order_id)));
@@ -27,21 +29,48 @@ fn test_delete_order(){
 
 #[test]
 fn test_deleting_an_non_existent_order(){
-    let mut orderbook = Orderbook::new();
+    let mut orderbook = new_orderbook();
     let  result = orderbook.delete_order(4);
-    assert_eq!(result,Err(ErrorResponse::new(CustomError::OrderDoesNotExist)));
+    assert_eq!(result,Err(CustomError::OrderDoesNotExist));
 }
 
 #[test]
 fn test_deleting_an_matched_order(){
-    let mut orderbook = Orderbook::new();
-    let limit_order_1 = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
-    let limit_order_2= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let mut orderbook = new_orderbook();
+    let limit_order_1 = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let limit_order_2= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order_1);
     orderbook.add_limit_order(limit_order_2);
     assert!(orderbook.asks.is_empty());
     assert!(orderbook.bids.is_empty());
 
     let result =orderbook.delete_order(1);
-    assert_eq!(result,Err(ErrorResponse::new(CustomError::OrderAlreadyMatched)));
+    assert_eq!(result,Err(CustomError::OrderAlreadyMatched));
+}
+
+#[test]
+fn test_deleting_an_order_the_book_lost_track_of(){
+    let mut orderbook = new_orderbook();
+    let open_order=orderbook.add_limit_order(LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None});
+    //the index still points at a slot that no longer holds the order
+    let slot=orderbook.order_map[&open_order.order_id].slot;
+    orderbook.asks.orders.remove(slot);
+    assert_eq!(orderbook.delete_order(open_order.order_id),Err(CustomError::OrderDoesNotExist));
+    assert_eq!(orderbook.modify_order(ModifyOrderRequest{price:Some(dec!(106)),quantity:None,order_id:open_order.order_id}),Err(CustomError::OrderDoesNotExist));
+    let response=orderbook.add_market_order(MarketOrder::new(dec!(10), Side::Bids, 2));
+    //nothing is left to trade against, so the whole order goes unfilled
+    assert_eq!(response,MarketOrderResponse::new(true,None,Some(dec!(0)),None).with_remaining(dec!(10)));
+    assert_eq!(orderbook.get_depth().asks.len(),1);
+}
+
+#[test]
+fn test_deleting_another_users_order(){
+    let mut orderbook = new_orderbook();
+    let open_order = orderbook.add_limit_order(LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None});
+    //another user's order is reported as missing and left alone
+    assert_eq!(orderbook.delete_user_order(2, open_order.order_id),Err(CustomError::OrderDoesNotExist));
+    assert_eq!(orderbook.get_order(open_order.order_id).unwrap().quantity,dec!(200));
+    assert_eq!(orderbook.delete_user_order(1, open_order.order_id),Ok(DeleteResponse::new(dec!(105),dec!(200),dec!(0),open_order.order_id)));
+    //once closed it is still not theirs to ask about
+    assert_eq!(orderbook.delete_user_order(2, open_order.order_id),Err(CustomError::OrderDoesNotExist));
 }
\ No newline at end of file
@@ -2,15 +2,17 @@
 use pretty_assertions::{assert_eq};
 use rust_decimal::dec;
 
-use crate::{orderbook::{types::{Order, Side}}, Depth, LimitOrder, Orderbook};
+use crate::{orderbook::{types::{Order, Side}}, CustomError, Depth, LimitOrder, MarketOrder, MarketOrderResponse, ModifyOrderRequest, OrderStatus, MAX_NOTIONAL, MAX_PRICE, MAX_QUANTITY};
+
+use super::new_orderbook;
 
 #[test]
 fn test_get_depth(){
-    let mut orderbook = Orderbook::new();
-    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
-    let limit_order_2 = LimitOrder{price:dec!(106),quantity:dec!(200),side:Side::Asks,user_id:1};
-    let limit_order_3 = LimitOrder{price:dec!(107),quantity:dec!(200),side:Side::Asks,user_id:1};
-    let limit_order_4 = LimitOrder{price:dec!(108),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let mut orderbook = new_orderbook();
+    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let limit_order_2 = LimitOrder{price:dec!(106),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let limit_order_3 = LimitOrder{price:dec!(107),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let limit_order_4 = LimitOrder{price:dec!(108),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order_1);
     orderbook.add_limit_order(limit_order_2);
     orderbook.add_limit_order(limit_order_3);
@@ -30,10 +32,10 @@ fn test_get_depth(){
     let expected_depth=Depth{asks:expected_asks,bids:expected_bids};
     assert_eq!(depth,expected_depth);
 
-    let limit_order_1= LimitOrder{price:dec!(103),quantity:dec!(200),side:Side::Bids,user_id:1};
-    let limit_order_2 = LimitOrder{price:dec!(102),quantity:dec!(200),side:Side::Bids,user_id:1};
-    let limit_order_3 = LimitOrder{price:dec!(101),quantity:dec!(200),side:Side::Bids,user_id:1};
-    let limit_order_4 = LimitOrder{price:dec!(100),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let limit_order_1= LimitOrder{price:dec!(103),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
+    let limit_order_2 = LimitOrder{price:dec!(102),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
+    let limit_order_3 = LimitOrder{price:dec!(101),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
+    let limit_order_4 = LimitOrder{price:dec!(100),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order_1);
     orderbook.add_limit_order(limit_order_2);
     orderbook.add_limit_order(limit_order_3);
@@ -62,4 +64,36 @@ fn test_get_depth(){
 
     let expected_depth=Depth{asks:expected_asks,bids:expected_bids};
     assert_eq!(depth,expected_depth);
+}
+
+#[test]
+fn test_orders_past_the_largest_price_or_quantity_are_turned_away(){
+    let mut orderbook = new_orderbook();
+    let order = orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(50_000_000_000_000_000_000_000_000_000),side:Side::Asks,user_id:1,client_order_id:None});
+    assert_eq!(order.status,OrderStatus::Rejected);
+    let order = orderbook.add_limit_order(LimitOrder{price:MAX_PRICE+dec!(1),quantity:dec!(1),side:Side::Asks,user_id:1,client_order_id:None});
+    assert_eq!(order.status,OrderStatus::Rejected);
+    let response = orderbook.add_market_order(MarketOrder::new(MAX_QUANTITY+dec!(1), Side::Bids, 2));
+    assert_eq!(response,MarketOrderResponse::new(false, None, None, Some(CustomError::InvalidQuantity)));
+    let response = orderbook.add_market_order(MarketOrder::buy_with_quote(MAX_NOTIONAL+dec!(1), 2));
+    assert_eq!(response,MarketOrderResponse::new(false, None, None, Some(CustomError::InvalidQuantity)));
+    let ask = orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Asks,user_id:1,client_order_id:None});
+    let result = orderbook.modify_order(ModifyOrderRequest{price:None,quantity:Some(MAX_QUANTITY*dec!(2)),order_id:ask.order_id});
+    assert_eq!(result,Err(CustomError::InvalidQuantity));
+}
+
+#[test]
+fn test_largest_orders_add_up_without_overflowing(){
+    let mut orderbook = new_orderbook();
+    for user_id in 1..=100{
+        orderbook.add_limit_order(LimitOrder{price:MAX_PRICE,quantity:MAX_QUANTITY,side:Side::Asks,user_id,client_order_id:None});
+    }
+    //a fixed point level holding more lots than fit in an i64 saturates
+    let asks = orderbook.get_depth().asks;
+    assert_eq!((asks.len(),asks[0].price,asks[0].order_count),(1,MAX_PRICE,100));
+    assert!(asks[0].quantity>=MAX_QUANTITY*dec!(9));
+    //a quote budget prices the whole level, past what a decimal holds
+    let response = orderbook.add_market_order(MarketOrder::buy_with_quote(MAX_NOTIONAL, 101));
+    assert!(response.is_success());
+    assert_eq!(orderbook.get_order(1).unwrap().status,OrderStatus::Filled);
 }
\ No newline at end of file
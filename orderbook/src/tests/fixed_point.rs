@@ -0,0 +1,65 @@
+use rust_decimal::{dec, Decimal};
+
+use crate::{FixedPoint, FixedPointScale, MatchingAlgorithm, Numeric};
+
+pub(super) type Number=FixedPoint;
+pub(super) type Orderbook=crate::Orderbook<Number>;
+
+//fine enough for every price and quantity the tests use
+pub(super) fn scale()->FixedPointScale{
+    FixedPointScale::new(dec!(0.01), dec!(0.001))
+}
+
+pub(super) fn new_orderbook()->Orderbook{
+    Orderbook::with_scale(scale())
+}
+
+pub(super) fn new_orderbook_with<A:MatchingAlgorithm>(allocation:A)->crate::Orderbook<Number,A>{
+    crate::Orderbook::with_algorithm(scale(), allocation)
+}
+
+//for looking inside the book, which keeps whole ticks and lots
+pub(super) fn price(price:Decimal)->Number{
+    FixedPoint::from_price(price, &scale()).unwrap()
+}
+
+pub(super) fn quantity(quantity:Decimal)->Number{
+    FixedPoint::from_quantity(quantity, &scale()).unwrap()
+}
+
+#[path="limit_order.rs"]
+mod limit_order;
+#[path="tests.rs"]
+mod tests;
+#[path="market_order.rs"]
+mod market_order;
+#[path="delete_order.rs"]
+mod delete_order;
+#[path="modify_order.rs"]
+mod modify_order;
+#[path="get_order.rs"]
+mod get_order;
+#[path="depth.rs"]
+mod depth;
+#[path="trades.rs"]
+mod trades;
+#[path="order_history.rs"]
+mod order_history;
+#[path="cancel_all.rs"]
+mod cancel_all;
+#[path="batch.rs"]
+mod batch;
+#[path="client_order_ids.rs"]
+mod client_order_ids;
+#[path="price_bands.rs"]
+mod price_bands;
+#[path="market_protection.rs"]
+mod market_protection;
+#[path="auction.rs"]
+mod auction;
+#[path="allocation.rs"]
+mod allocation;
+#[path="matching.rs"]
+mod matching;
+#[path="changes.rs"]
+mod changes;
\ No newline at end of file
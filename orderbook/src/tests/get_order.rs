@@ -2,22 +2,24 @@
 use pretty_assertions::{assert_eq};
 use rust_decimal::dec;
 
-use crate::{orderbook::{response::CustomError, types::Side}, ErrorResponse, LimitOrder, OpenOrder, Orderbook};
+use crate::{orderbook::{response::CustomError, types::Side}, LimitOrder, OpenOrder};
+
+use super::new_orderbook;
 
 #[test]
 fn test_get_existing_order(){
-    let mut orderbook = Orderbook::new();
+    let mut orderbook = new_orderbook();
     
-    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     let open_order_1 = orderbook.add_limit_order(limit_order_1);
     let result = orderbook.get_order(open_order_1.order_id);
     assert_eq!(result,Ok(open_order_1));
 
     drop(orderbook);
 
-    let mut orderbook = Orderbook::new();
+    let mut orderbook = new_orderbook();
     
-    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     let open_order_1 = orderbook.add_limit_order(limit_order_1);
     let result = orderbook.get_order(open_order_1.order_id);
     assert_eq!(result,Ok(open_order_1));
@@ -25,10 +27,10 @@ fn test_get_existing_order(){
 
 #[test]
 fn test_get_matched_order(){
-    let mut orderbook = Orderbook::new();
+    let mut orderbook = new_orderbook();
     
-    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1};
-    let limit_order_2= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
+    let limit_order_2= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     let open_order_1 = orderbook.add_limit_order(limit_order_1);
     let open_order_2=orderbook.add_limit_order(limit_order_2);
     let result = orderbook.get_order(open_order_1.order_id);
@@ -42,10 +44,10 @@ fn test_get_matched_order(){
 
 #[test]
 fn test_get_partially_filled_order(){
-    let mut orderbook = Orderbook::new();
+    let mut orderbook = new_orderbook();
     
-    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1};
-    let limit_order_2= LimitOrder{price:dec!(105),quantity:dec!(100),side:Side::Asks,user_id:1};
+    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
+    let limit_order_2= LimitOrder{price:dec!(105),quantity:dec!(100),side:Side::Asks,user_id:1,client_order_id:None};
     let open_order_1=orderbook.add_limit_order(limit_order_1);
     orderbook.add_limit_order(limit_order_2);
     let result = orderbook.get_order(open_order_1.order_id);
@@ -54,10 +56,10 @@ fn test_get_partially_filled_order(){
 
     drop(orderbook);
 
-    let mut orderbook = Orderbook::new();
+    let mut orderbook = new_orderbook();
     
-    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
-    let limit_order_2= LimitOrder{price:dec!(105),quantity:dec!(100),side:Side::Bids,user_id:1};
+    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let limit_order_2= LimitOrder{price:dec!(105),quantity:dec!(100),side:Side::Bids,user_id:1,client_order_id:None};
     let open_order_1=orderbook.add_limit_order(limit_order_1);
     orderbook.add_limit_order(limit_order_2);
     let result = orderbook.get_order(open_order_1.order_id);
@@ -67,7 +69,7 @@ fn test_get_partially_filled_order(){
 
 #[test]
 fn test_non_existent_order(){
-    let orderbook =Orderbook::new();
+    let orderbook =new_orderbook();
     let result =orderbook.get_order(1);
-    assert_eq!(result,Err(ErrorResponse::new(CustomError::OrderDoesNotExist)));
+    assert_eq!(result,Err(CustomError::OrderDoesNotExist));
 }
\ No newline at end of file
@@ -2,16 +2,17 @@ use std::collections::VecDeque;
 
 use rust_decimal::dec;
 
-use crate::{orderbook::types::{Order, Side}, LimitOrder, OpenOrder, Orderbook};
-use std::cmp::Reverse;
+use crate::{orderbook::types::{Order, Side}, BandAction, CustomError, LimitOrder, ModifyOrderRequest, OpenOrder, OrderStatus, PriceBandConfig, ReferencePrice};
+
+use super::{new_orderbook, price, quantity};
 #[cfg(test)]
 use pretty_assertions::{assert_eq, assert_ne,};
 
 #[test]
 fn create_limit_order(){
-    let mut orderbook =Orderbook::new();
+    let mut orderbook =new_orderbook();
     //Asks Order
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     let open_order=orderbook.add_limit_order(limit_order);
 
     //Wrong Side
@@ -28,7 +29,7 @@ fn create_limit_order(){
     assert!(!orderbook.asks.is_empty());
 
     //Bids Order
-    let limit_order = LimitOrder{price:dec!(100),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let limit_order = LimitOrder{price:dec!(100),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     let open_order=orderbook.add_limit_order(limit_order);
 
     //Wrong Side
@@ -47,46 +48,46 @@ fn create_limit_order(){
 
 #[test]
 fn adding_multiple_same_price_limit_order(){
-    let mut orderbook =Orderbook::new();
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let mut orderbook =new_orderbook();
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     let open_order_1= orderbook.add_limit_order(limit_order);
     assert!(orderbook.asks.len()==1); // Check if the orderbook has gained an order in the asks
 
-    let limit_order_2 = LimitOrder{price:dec!(105),quantity:dec!(800),side:Side::Asks,user_id:1};
+    let limit_order_2 = LimitOrder{price:dec!(105),quantity:dec!(800),side:Side::Asks,user_id:1,client_order_id:None};
     let open_order_2 =orderbook.add_limit_order(limit_order_2);
     assert!(orderbook.asks.len()==1); // Check if the orderbook has gained an order at the same price
 
-    let asks=orderbook.asks.get(&open_order_1.price).unwrap();
+    let asks:VecDeque<OpenOrder>=orderbook.asks.orders_at(price(open_order_1.price)).map(|o|o.to_decimal(&orderbook.scale)).collect();
     let mut expected_vec:VecDeque<OpenOrder> = VecDeque::new();
     expected_vec.push_back(open_order_1);
     expected_vec.push_back(open_order_2);
-    assert_eq!(asks,&expected_vec); // Check if the asks is same as the one we expected
+    assert_eq!(asks,expected_vec); // Check if the asks is same as the one we expected
     
     //Do the same "BIDS"
-    let limit_order = LimitOrder{price:dec!(100),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let limit_order = LimitOrder{price:dec!(100),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     let open_order_1= orderbook.add_limit_order(limit_order);
     assert!(orderbook.bids.len()==1);
 
-    let limit_order_2 = LimitOrder{price:dec!(100),quantity:dec!(800),side:Side::Bids,user_id:1};
+    let limit_order_2 = LimitOrder{price:dec!(100),quantity:dec!(800),side:Side::Bids,user_id:1,client_order_id:None};
     let open_order_2 =orderbook.add_limit_order(limit_order_2);
     assert!(orderbook.bids.len()==1);
 
-    let bids=orderbook.bids.get(&Reverse(open_order_1.price)).unwrap();
+    let bids:VecDeque<OpenOrder>=orderbook.bids.orders_at(price(open_order_1.price)).map(|o|o.to_decimal(&orderbook.scale)).collect();
     let mut expected_vec:VecDeque<OpenOrder> = VecDeque::new();
     expected_vec.push_back(open_order_1);
     expected_vec.push_back(open_order_2);
-    assert_eq!(bids,&expected_vec);
+    assert_eq!(bids,expected_vec);
 }
 
 #[test]
 fn matching_limit_orders(){
     //Match an exisiting asks order with a bids order which has the same price
-    let mut orderbook =Orderbook::new();
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let mut orderbook =new_orderbook();
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order);
     assert!(orderbook.asks.len()==1); // Check if the orderbook has gained an order in the asks
 
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(100),side:Side::Bids,user_id:1};
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(100),side:Side::Bids,user_id:1,client_order_id:None};
     let open_order_2= orderbook.add_limit_order(limit_order);
     let expected_open_order = OpenOrder::new(dec!(105), dec!(100), Side::Bids, dec!(100), 1, 2); //The quantity should have been filled
     assert_eq!(open_order_2,expected_open_order);
@@ -94,12 +95,12 @@ fn matching_limit_orders(){
     drop(orderbook);
 
     //Match an exisiting bids order with an asks order which hash the same price
-    let mut orderbook =Orderbook::new();
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let mut orderbook =new_orderbook();
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order);
     assert!(orderbook.bids.len()==1); // Check if the orderbook has gained an order in the bids
 
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(100),side:Side::Asks,user_id:1};
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(100),side:Side::Asks,user_id:1,client_order_id:None};
     let open_order_2= orderbook.add_limit_order(limit_order);
     let expected_open_order = OpenOrder::new(dec!(105), dec!(100), Side::Asks, dec!(100), 1, 2); //The quantity should have been filled
     assert_eq!(open_order_2,expected_open_order);
@@ -108,32 +109,30 @@ fn matching_limit_orders(){
 
     //Matching an exisiting Asks order with a bids order whose price is 
     //better than the Asks so it gets matched with orders until it hits the expected price
-    let mut orderbook = Orderbook::new();
-    let limit_order_1 = LimitOrder{price:dec!(105.1),quantity:dec!(200),side:Side::Asks,user_id:1};
-    let limit_order_2 = LimitOrder{price:dec!(105.2),quantity:dec!(200),side:Side::Asks,user_id:1};
-    let limit_order_3 = LimitOrder{price:dec!(105.5),quantity:dec!(200),side:Side::Asks,user_id:1};
-    let limit_order_4 = LimitOrder{price:dec!(105.8),quantity:dec!(200),side:Side::Asks,user_id:1};
-    let limit_order_5 = LimitOrder{price:dec!(105.9),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let mut orderbook = new_orderbook();
+    let limit_order_1 = LimitOrder{price:dec!(105.1),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let limit_order_2 = LimitOrder{price:dec!(105.2),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let limit_order_3 = LimitOrder{price:dec!(105.5),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let limit_order_4 = LimitOrder{price:dec!(105.8),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let limit_order_5 = LimitOrder{price:dec!(105.9),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order_1);
     orderbook.add_limit_order(limit_order_2);
     orderbook.add_limit_order(limit_order_3);
     orderbook.add_limit_order(limit_order_4);
     orderbook.add_limit_order(limit_order_5);
 
-    let limit_order_6= LimitOrder{price:dec!(105.5),quantity:dec!(100),side:Side::Bids,user_id:1};
+    let limit_order_6= LimitOrder{price:dec!(105.5),quantity:dec!(100),side:Side::Bids,user_id:1,client_order_id:None};
     let open_order=orderbook.add_limit_order(limit_order_6);
     let expected_open_order=OpenOrder::new(dec!(105.5), dec!(100), Side::Bids, dec!(100), 1, 6);
     assert_eq!(open_order,expected_open_order);
     //Check the first limit order that has been partially filled
     let entry = orderbook.asks
-                            .get(&dec!(105.1))
-                            .unwrap()
-                            .iter()
+                            .orders_at(price(dec!(105.1)))
                             .find(|v|v.order_id==1)
                             .unwrap();
-    assert_eq!(entry.quantity_filled,dec!(100));
+    assert_eq!(entry.quantity_filled,quantity(dec!(100)));
 
-    let limit_order_7= LimitOrder{price:dec!(105.5),quantity:dec!(600),side:Side::Bids,user_id:1};
+    let limit_order_7= LimitOrder{price:dec!(105.5),quantity:dec!(600),side:Side::Bids,user_id:1,client_order_id:None};
     let open_order=orderbook.add_limit_order(limit_order_7);
     let expected_open_order=OpenOrder::new(dec!(105.5), dec!(600), Side::Bids, dec!(500), 1, 7);
     assert_eq!(open_order,expected_open_order);
@@ -148,32 +147,30 @@ fn matching_limit_orders(){
 
     //Matching an exisiting Bids order with a asks order whose price is 
     //better than the Asks so it gets matched with orders until it hits the expected price
-    let mut orderbook = Orderbook::new();
-    let limit_order_1 = LimitOrder{price:dec!(105.1),quantity:dec!(200),side:Side::Bids,user_id:1};
-    let limit_order_2 = LimitOrder{price:dec!(105.2),quantity:dec!(200),side:Side::Bids,user_id:1};
-    let limit_order_3 = LimitOrder{price:dec!(105.5),quantity:dec!(200),side:Side::Bids,user_id:1};
-    let limit_order_4 = LimitOrder{price:dec!(105.8),quantity:dec!(200),side:Side::Bids,user_id:1};
-    let limit_order_5 = LimitOrder{price:dec!(105.9),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let mut orderbook = new_orderbook();
+    let limit_order_1 = LimitOrder{price:dec!(105.1),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
+    let limit_order_2 = LimitOrder{price:dec!(105.2),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
+    let limit_order_3 = LimitOrder{price:dec!(105.5),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
+    let limit_order_4 = LimitOrder{price:dec!(105.8),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
+    let limit_order_5 = LimitOrder{price:dec!(105.9),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order_1);
     orderbook.add_limit_order(limit_order_2);
     orderbook.add_limit_order(limit_order_3);
     orderbook.add_limit_order(limit_order_4);
     orderbook.add_limit_order(limit_order_5);
 
-    let limit_order_6= LimitOrder{price:dec!(105.5),quantity:dec!(100),side:Side::Asks,user_id:1};
+    let limit_order_6= LimitOrder{price:dec!(105.5),quantity:dec!(100),side:Side::Asks,user_id:1,client_order_id:None};
     let open_order=orderbook.add_limit_order(limit_order_6);
     let expected_open_order=OpenOrder::new(dec!(105.5), dec!(100), Side::Asks, dec!(100), 1, 6);
     assert_eq!(open_order,expected_open_order);
     //Check the first limit order that has been partially filled
     let entry = orderbook.bids
-                            .get(&Reverse(dec!(105.9)))
-                            .unwrap()
-                            .iter()
+                            .orders_at(price(dec!(105.9)))
                             .find(|v|v.order_id==5)
                             .unwrap();
-    assert_eq!(entry.quantity_filled,dec!(100));
+    assert_eq!(entry.quantity_filled,quantity(dec!(100)));
 
-    let limit_order_7= LimitOrder{price:dec!(105.5),quantity:dec!(600),side:Side::Asks,user_id:1};
+    let limit_order_7= LimitOrder{price:dec!(105.5),quantity:dec!(600),side:Side::Asks,user_id:1,client_order_id:None};
     let open_order=orderbook.add_limit_order(limit_order_7);
     let expected_open_order=OpenOrder::new(dec!(105.5), dec!(600), Side::Asks, dec!(500), 1, 7);
     assert_eq!(open_order,expected_open_order);
@@ -183,4 +180,37 @@ fn matching_limit_orders(){
     let order_1 = Order{price:open_order.price,quantity:dec!(100),order_count:1};
     expected_asks.push(order_1);
     assert_eq!(asks,expected_asks);
+}
+
+#[test]
+fn test_limit_order_without_a_positive_price_and_quantity_is_rejected(){
+    let mut orderbook = new_orderbook();
+    //a band that truncates would otherwise pull a negative ask up to its edge
+    orderbook.set_price_band(Some(PriceBandConfig{width_bps:1000,reference:ReferencePrice::LastTrade,action:BandAction::Truncate}));
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Asks,user_id:1,client_order_id:None});
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Bids,user_id:2,client_order_id:None});
+    for (price,quantity) in [(dec!(0),dec!(1)),(dec!(-5),dec!(1)),(dec!(100),dec!(0)),(dec!(100),dec!(-1))]{
+        let order = orderbook.add_limit_order(LimitOrder{price,quantity,side:Side::Asks,user_id:1,client_order_id:None});
+        assert_eq!(order.status,OrderStatus::Rejected);
+    }
+    assert!(orderbook.asks.is_empty());
+
+    let ask = orderbook.add_limit_order(LimitOrder{price:dec!(105),quantity:dec!(1),side:Side::Asks,user_id:1,client_order_id:None});
+    assert_eq!(orderbook.modify_order(ModifyOrderRequest{price:Some(dec!(0)),quantity:None,order_id:ask.order_id}),Err(CustomError::InvalidPrice));
+    assert_eq!(orderbook.modify_order(ModifyOrderRequest{price:None,quantity:Some(dec!(-1)),order_id:ask.order_id}),Err(CustomError::InvalidQuantity));
+    assert_eq!(orderbook.get_order(ask.order_id).unwrap().status,OrderStatus::New);
+}
+
+#[test]
+fn test_placed_limit_order_the_book_would_reject_comes_back_as_the_reason(){
+    let mut orderbook = new_orderbook();
+    orderbook.set_price_band(Some(PriceBandConfig{width_bps:1000,reference:ReferencePrice::LastTrade,action:BandAction::Reject}));
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Asks,user_id:1,client_order_id:None});
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Bids,user_id:2,client_order_id:None});
+    assert_eq!(orderbook.place_limit_order(LimitOrder{price:dec!(0),quantity:dec!(1),side:Side::Asks,user_id:1,client_order_id:None}),Err(CustomError::InvalidPrice));
+    assert_eq!(orderbook.place_limit_order(LimitOrder{price:dec!(100),quantity:dec!(0),side:Side::Asks,user_id:1,client_order_id:None}),Err(CustomError::InvalidQuantity));
+    assert_eq!(orderbook.place_limit_order(LimitOrder{price:dec!(120),quantity:dec!(1),side:Side::Bids,user_id:1,client_order_id:None}),Err(CustomError::PriceBandExceeded));
+    //none of them took an order id
+    let bid = orderbook.place_limit_order(LimitOrder{price:dec!(95),quantity:dec!(1),side:Side::Bids,user_id:1,client_order_id:None}).unwrap();
+    assert_eq!((bid.order_id,bid.status),(3,OrderStatus::New));
 }
\ No newline at end of file
@@ -1,16 +1,16 @@
-use std::collections::{BTreeMap, VecDeque};
-
 use rust_decimal::{dec, Decimal};
 
-use crate::{orderbook::{response::CustomError, types::Side}, LimitOrder, MarketOrder, MarketOrderResponse, OpenOrder, Orderbook};
+use crate::{orderbook::{response::CustomError, types::Side}, LimitOrder, MarketOrder, MarketOrderResponse, OpenOrder};
+
+use super::new_orderbook;
+use crate::Numeric;
 #[cfg(test)]
 use pretty_assertions::{assert_eq};
-use std::cmp::Reverse;
 
 #[test]
 fn create_market_order(){
-    let mut orderbook = Orderbook::new();
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let mut orderbook = new_orderbook();
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order);
     let market_order = MarketOrder::new(dec!(10), Side::Bids, 1);
     let market_order_response = orderbook.add_market_order(market_order);
@@ -20,7 +20,7 @@ fn create_market_order(){
 
 #[test]
 fn if_there_is_no_order(){
-    let mut orderbook = Orderbook::new();
+    let mut orderbook = new_orderbook();
     // There is no BIDS
     let market_order = MarketOrder::new(dec!(10), Side::Asks, 1);
     let market_order_response = orderbook.add_market_order(market_order);
@@ -37,55 +37,55 @@ fn if_there_is_no_order(){
 
 #[test]
 fn if_there_is_not_enough_quantity_in_orderbook(){
-    let mut orderbook = Orderbook::new();
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let mut orderbook = new_orderbook();
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order);
     let market_order = MarketOrder::new(dec!(300), Side::Bids, 1);
     let market_order_response = orderbook.add_market_order(market_order);
-    let expected_market_order_response = MarketOrderResponse::new(true, Some(dec!(105)), Some(dec!(200)),None);
+    let expected_market_order_response = MarketOrderResponse::new(true, Some(dec!(105)), Some(dec!(200)),None).with_remaining(dec!(100));
     assert_eq!(market_order_response,expected_market_order_response);
     
     drop(orderbook);
 
-    let mut orderbook = Orderbook::new();
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let mut orderbook = new_orderbook();
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order);
     let market_order = MarketOrder::new(dec!(300), Side::Asks, 1);
     let market_order_response = orderbook.add_market_order(market_order);
-    let expected_market_order_response = MarketOrderResponse::new(true, Some(dec!(105)), Some(dec!(200)),None);
+    let expected_market_order_response = MarketOrderResponse::new(true, Some(dec!(105)), Some(dec!(200)),None).with_remaining(dec!(100));
     assert_eq!(market_order_response,expected_market_order_response);
 }
 
 #[test]
 fn filling_multiple_orders_on_orderbook(){
     //Asks order
-    let mut orderbook = Orderbook::new();
-    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
-    let limit_order_2= LimitOrder{price:dec!(107),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let mut orderbook = new_orderbook();
+    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let limit_order_2= LimitOrder{price:dec!(107),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order_1);
     orderbook.add_limit_order(limit_order_2);
     let market_order = MarketOrder::new(dec!(300), Side::Bids, 1);
     let market_order_response = orderbook.add_market_order(market_order);
-    let expected_market_order_response = MarketOrderResponse::new(true, Some(dec!(106)), Some(dec!(300)),None);
+    //200 at 105 and 100 at 107, weighted by quantity
+    let expected_market_order_response = MarketOrderResponse::new(true, Some(dec!(31700)/dec!(300)), Some(dec!(300)),None);
     assert_eq!(market_order_response,expected_market_order_response);
     
-    let asks=orderbook.asks;
-    let mut expected_asks:BTreeMap<Decimal,VecDeque<OpenOrder>>=BTreeMap::new();
-    expected_asks.entry(dec!(107)).or_insert(VecDeque::new()).push_back(OpenOrder::new(dec!(107), dec!(200), Side::Asks, dec!(100), 1, 2));
+    let asks:Vec<(Decimal,Vec<OpenOrder>)>=orderbook.asks.iter().map(|(price,orders)|(price.to_price(&orderbook.scale),orders.map(|o|o.to_decimal(&orderbook.scale)).collect())).collect();
+    let expected_asks=vec![(dec!(107),vec![OpenOrder::new(dec!(107), dec!(200), Side::Asks, dec!(100), 1, 2)])];
     assert_eq!(asks,expected_asks);
 
     //Bids Order
-    let mut orderbook = Orderbook::new();
-    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1};
-    let limit_order_2= LimitOrder{price:dec!(107),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let mut orderbook = new_orderbook();
+    let limit_order_1= LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
+    let limit_order_2= LimitOrder{price:dec!(107),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order_1);
     orderbook.add_limit_order(limit_order_2);
     let market_order = MarketOrder::new(dec!(300), Side::Asks, 1);
     let market_order_response = orderbook.add_market_order(market_order);
-    let expected_market_order_response = MarketOrderResponse::new(true, Some(dec!(106)), Some(dec!(300)),None);
+    //200 at 107 and 100 at 105, weighted by quantity
+    let expected_market_order_response = MarketOrderResponse::new(true, Some(dec!(31900)/dec!(300)), Some(dec!(300)),None);
     assert_eq!(market_order_response,expected_market_order_response);
-    let bids = orderbook.bids;
-    let mut expected_bids:BTreeMap<Reverse<Decimal>,VecDeque<OpenOrder>>=BTreeMap::new();
-    expected_bids.entry(Reverse(dec!(105))).or_insert(VecDeque::new()).push_back(OpenOrder::new(dec!(105), dec!(200), Side::Bids, dec!(100), 1, 1));
+    let bids:Vec<(Decimal,Vec<OpenOrder>)>=orderbook.bids.iter().map(|(price,orders)|(price.0.to_price(&orderbook.scale),orders.map(|o|o.to_decimal(&orderbook.scale)).collect())).collect();
+    let expected_bids=vec![(dec!(105),vec![OpenOrder::new(dec!(105), dec!(200), Side::Bids, dec!(100), 1, 1)])];
     assert_eq!(bids,expected_bids);
 }
\ No newline at end of file
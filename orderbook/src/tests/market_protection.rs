@@ -0,0 +1,66 @@
+#[cfg(test)]
+use pretty_assertions::{assert_eq};
+use rust_decimal::{dec, Decimal};
+
+use crate::{orderbook::types::Side, CustomError, LimitOrder, MarketOrder, MarketOrderResponse};
+
+use super::{new_orderbook, Orderbook};
+
+fn limit_order(price:Decimal,quantity:Decimal,side:Side)->LimitOrder{
+    LimitOrder{price,quantity,side,user_id:1,client_order_id:None}
+}
+
+//asks of 10 at 100, 101 and 110
+fn thin_orderbook()->Orderbook{
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(10), Side::Asks));
+    orderbook.add_limit_order(limit_order(dec!(101), dec!(10), Side::Asks));
+    orderbook.add_limit_order(limit_order(dec!(110), dec!(10), Side::Asks));
+    orderbook
+}
+
+#[test]
+fn test_max_slippage_cancels_the_remainder(){
+    let mut orderbook = thin_orderbook();
+    let response = orderbook.add_market_order(MarketOrder::new(dec!(30), Side::Bids, 2).with_max_slippage_bps(200));
+    assert_eq!(response,MarketOrderResponse::new(true, Some(dec!(100.5)), Some(dec!(20)), None).with_remaining(dec!(10)));
+    assert_eq!(orderbook.get_best_ask().unwrap(),dec!(110));
+}
+
+#[test]
+fn test_protection_price(){
+    let mut orderbook = thin_orderbook();
+    let response = orderbook.add_market_order(MarketOrder::new(dec!(15), Side::Bids, 2).with_protection_price(dec!(100)));
+    assert_eq!(response,MarketOrderResponse::new(true, Some(dec!(100)), Some(dec!(10)), None).with_remaining(dec!(5)));
+
+    //the tighter of the protection price and the slippage allowance wins
+    let response = orderbook.add_market_order(MarketOrder::new(dec!(15), Side::Bids, 2).with_protection_price(dec!(120)).with_max_slippage_bps(100));
+    assert_eq!(response,MarketOrderResponse::new(true, Some(dec!(101)), Some(dec!(10)), None).with_remaining(dec!(5)));
+
+    let response = orderbook.add_market_order(MarketOrder::new(dec!(15), Side::Bids, 2).with_protection_price(dec!(105)));
+    assert_eq!(response,MarketOrderResponse::new(false, None, None, Some(CustomError::ProtectionPriceExceeded)));
+    assert_eq!(orderbook.get_asks()[0].quantity,dec!(10));
+}
+
+#[test]
+fn test_buy_with_quote_quantity(){
+    let mut orderbook = thin_orderbook();
+    let response = orderbook.add_market_order(MarketOrder::buy_with_quote(dec!(1505), 2));
+    assert_eq!(response,MarketOrderResponse::new(true, Some(dec!(1505)/dec!(15)), Some(dec!(15)), None).with_quote_quantity(dec!(1505)));
+    assert_eq!(orderbook.get_asks()[0].quantity,dec!(5));
+
+    //more than the book holds inside the protection price is reported back unspent
+    let response = orderbook.add_market_order(MarketOrder::buy_with_quote(dec!(1000), 2).with_protection_price(dec!(101)));
+    assert_eq!(response,MarketOrderResponse::new(true, Some(dec!(101)), Some(dec!(5)), None).with_quote_quantity(dec!(505)).with_remaining(dec!(495)));
+}
+
+#[test]
+fn test_invalid_quote_quantity(){
+    let mut orderbook = thin_orderbook();
+    let mut order = MarketOrder::buy_with_quote(dec!(100), 2);
+    order.side=Side::Asks;
+    let response = orderbook.add_market_order(order);
+    assert_eq!(response,MarketOrderResponse::new(false, None, None, Some(CustomError::QuoteQuantityOnlyForBuys)));
+    let response = orderbook.add_market_order(MarketOrder::buy_with_quote(dec!(0), 2));
+    assert_eq!(response,MarketOrderResponse::new(false, None, None, Some(CustomError::InvalidQuantity)));
+}
\ No newline at end of file
@@ -0,0 +1,86 @@
+use rust_decimal::dec;
+use crate::{orderbook::types::Side, Allocation, LimitOrder, LimitPolicy, MarketOrder, MarketOrderResponse, MarketPolicy, MatchingPolicy, ModifyOrderRequest, OrderStatus};
+
+use super::{new_orderbook, price, quantity, scale};
+#[cfg(test)]
+use pretty_assertions::{assert_eq};
+
+fn limit_order(price:rust_decimal::Decimal,quantity:rust_decimal::Decimal,side:Side,user_id:u64)->LimitOrder{
+    LimitOrder{price,quantity,side,user_id,client_order_id:None}
+}
+
+#[test]
+fn test_both_sides_sort_best_price_first(){
+    let mut orderbook = new_orderbook();
+    for price in [dec!(101),dec!(103),dec!(102)]{
+        orderbook.add_limit_order(limit_order(price, dec!(10), Side::Bids, 1));
+        orderbook.add_limit_order(limit_order(price+dec!(10), dec!(10), Side::Asks, 2));
+    }
+    assert_eq!(orderbook.bids.best_price(),Some(price(dec!(103))));
+    assert_eq!(orderbook.asks.best_price(),Some(price(dec!(111))));
+    assert_eq!(orderbook.bids.quantity_within(Some(price(dec!(102)))),quantity(dec!(20)));
+    assert_eq!(orderbook.asks.quantity_within(Some(price(dec!(112)))),quantity(dec!(20)));
+    assert_eq!(orderbook.asks.quantity_within(None),quantity(dec!(30)));
+    assert_eq!(orderbook.asks.quantity_within_up_to(None, quantity(dec!(15))),quantity(dec!(15)));
+    assert_eq!(orderbook.asks.quantity_within_up_to(Some(price(dec!(111))), quantity(dec!(15))),quantity(dec!(10)));
+    assert_eq!(orderbook.asks.quantity_within_up_to(None, quantity(dec!(50))),quantity(dec!(30)));
+}
+
+#[test]
+fn test_policies(){
+    let limit=LimitPolicy{price:price(dec!(100)),allocation:Allocation::Fifo,scale:scale()};
+    let market=MarketPolicy{allocation:Allocation::default(),scale:scale()};
+    assert_eq!(limit.limit_price(),Some(price(dec!(100))));
+    assert_eq!(market.limit_price(),None);
+    assert_eq!(market.allocate(&[quantity(dec!(5)),quantity(dec!(10))], quantity(dec!(8))),vec![quantity(dec!(5)),quantity(dec!(3))]);
+}
+
+#[test]
+fn test_average_price_is_volume_weighted(){
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(10), Side::Asks, 1));
+    orderbook.add_limit_order(limit_order(dec!(110), dec!(30), Side::Asks, 1));
+    let response = orderbook.add_market_order(MarketOrder::new(dec!(40), Side::Bids, 2));
+    assert_eq!(response,MarketOrderResponse::new(true, Some(dec!(107.5)), Some(dec!(40)), None));
+}
+
+#[test]
+fn test_fills_keep_book_and_index_in_step(){
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(10), Side::Bids, 1));
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(10), Side::Bids, 1));
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(15), Side::Asks, 2));
+    //the first bid is filled and gone, the second is half filled in both copies
+    assert_eq!(orderbook.get_order(1).unwrap().status,OrderStatus::Filled);
+    let resting=orderbook.get_order(2).unwrap();
+    assert_eq!(resting.quantity_filled,dec!(5));
+    assert_eq!(orderbook.bids.orders_at(price(dec!(100))).next().map(|o|o.to_decimal(&orderbook.scale)),Some(resting));
+    assert_eq!(orderbook.order_map.len(),1);
+}
+
+#[test]
+fn test_modify_price_moves_order_to_new_level(){
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(limit_order(dec!(105), dec!(10), Side::Asks, 1));
+    orderbook.add_limit_order(limit_order(dec!(107), dec!(10), Side::Asks, 1));
+    orderbook.modify_order(ModifyOrderRequest{price:Some(dec!(107)),quantity:None,order_id:1}).unwrap();
+    assert_eq!(orderbook.asks.len(),1);
+    //it joins the back of the queue at its new price
+    let level:Vec<u64>=orderbook.asks.orders_at(price(dec!(107))).map(|o|o.order_id).collect();
+    assert_eq!(level,vec![2,1]);
+    assert_eq!(orderbook.asks.orders_at(price(dec!(107))).nth(1).map(|o|o.to_decimal(&orderbook.scale)),Some(orderbook.get_order(1).unwrap()));
+}
+
+#[test]
+fn test_modify_price_across_the_spread_trades(){
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(20), Side::Bids, 1));
+    orderbook.add_limit_order(limit_order(dec!(105), dec!(5), Side::Asks, 2));
+    orderbook.modify_order(ModifyOrderRequest{price:Some(dec!(105)),quantity:None,order_id:1}).unwrap();
+    assert!(orderbook.asks.is_empty());
+    assert_eq!(orderbook.get_recent_trades(10).len(),1);
+    let order=orderbook.get_order(1).unwrap();
+    assert_eq!(order.quantity_filled,dec!(5));
+    assert_eq!(order.status,OrderStatus::PartiallyFilled);
+    assert_eq!(orderbook.bids.best_price(),Some(price(dec!(105))));
+}
\ No newline at end of file
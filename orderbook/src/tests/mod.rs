@@ -1,7 +1,5 @@
-mod limit_order;
-mod tests;
-mod market_order;
-mod delete_order;
-mod modify_order;
-mod get_order;
-mod depth;
\ No newline at end of file
+//the same tests run against a book of decimals and a fixed point one
+mod decimal;
+#[allow(clippy::duplicate_mod)]
+mod fixed_point;
+mod ticks_and_lots;
\ No newline at end of file
@@ -1,13 +1,15 @@
 use rust_decimal::{dec};
-use crate::{orderbook::{response::CustomError, types::Side}, ErrorResponse, LimitOrder, ModifyOrderRequest, ModifyOrderResponse, Orderbook};
+use crate::{orderbook::{response::CustomError, types::Side}, LimitOrder, ModifyOrderRequest, ModifyOrderResponse};
+
+use super::new_orderbook;
 #[cfg(test)]
 use pretty_assertions::{assert_eq};
 
 
 #[test]
 fn test_modify_order(){
-    let mut orderbook = Orderbook::new();
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let mut orderbook = new_orderbook();
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order);
     let modify_order_request=ModifyOrderRequest{price:Some(dec!(107)),quantity:Some(dec!(300)),order_id:1};
     let result=orderbook.modify_order(modify_order_request);
@@ -15,8 +17,8 @@ fn test_modify_order(){
 
     drop(orderbook);
 
-    let mut orderbook = Orderbook::new();
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let mut orderbook = new_orderbook();
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order);
     let modify_order_request=ModifyOrderRequest{price:Some(dec!(107)),quantity:Some(dec!(300)),order_id:1};
     let result=orderbook.modify_order(modify_order_request);
@@ -25,20 +27,30 @@ fn test_modify_order(){
 
 #[test]
 fn test_modify_order_if_order_does_not_exist(){
-    let mut orderbook = Orderbook::new();
+    let mut orderbook = new_orderbook();
     let modify_order_request=ModifyOrderRequest{price:Some(dec!(107)),quantity:Some(dec!(300)),order_id:1};
     let result=orderbook.modify_order(modify_order_request);
-    assert_eq!(result,Err(ErrorResponse::new(CustomError::OrderDoesNotExist)));
+    assert_eq!(result,Err(CustomError::OrderDoesNotExist));
 }
 
 #[test]
 fn test_modify_order_if_order_already_matched(){
-    let mut orderbook = Orderbook::new();
-    let limit_order_1 = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
-    let limit_order_2 = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let mut orderbook = new_orderbook();
+    let limit_order_1 = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let limit_order_2 = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order_1);
     orderbook.add_limit_order(limit_order_2);
     let modify_order_request=ModifyOrderRequest{price:Some(dec!(107)),quantity:Some(dec!(300)),order_id:1};
     let result=orderbook.modify_order(modify_order_request);
-    assert_eq!(result,Err(ErrorResponse::new(CustomError::OrderAlreadyMatched)));
+    assert_eq!(result,Err(CustomError::OrderAlreadyMatched));
+}
+
+#[test]
+fn test_modifying_another_users_order(){
+    let mut orderbook = new_orderbook();
+    let open_order = orderbook.add_limit_order(LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None});
+    let request = ModifyOrderRequest{price:None,quantity:Some(dec!(50)),order_id:open_order.order_id};
+    assert_eq!(orderbook.modify_user_order(2, request.clone()),Err(CustomError::OrderDoesNotExist));
+    assert_eq!(orderbook.get_order(open_order.order_id).unwrap().quantity,dec!(200));
+    assert_eq!(orderbook.modify_user_order(1, request),Ok(ModifyOrderResponse::new(dec!(105), dec!(50), open_order.order_id)));
 }
\ No newline at end of file
@@ -0,0 +1,85 @@
+#[cfg(test)]
+use pretty_assertions::{assert_eq};
+use rust_decimal::dec;
+
+use crate::{orderbook::{response::CustomError, types::Side}, LimitOrder, MarketOrder, ModifyOrderRequest, OrderStatus};
+
+use super::new_orderbook;
+
+#[test]
+fn test_order_status_transitions(){
+    let mut orderbook = new_orderbook();
+    let open_order = orderbook.add_limit_order(LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None});
+    assert_eq!(open_order.status,OrderStatus::New);
+
+    orderbook.add_market_order(MarketOrder::new(dec!(50), Side::Bids, 2));
+    let order = orderbook.get_order(open_order.order_id).unwrap();
+    assert_eq!(order.status,OrderStatus::PartiallyFilled);
+    assert_eq!(order.quantity_filled,dec!(50));
+    assert!(order.updated_at>=order.created_at);
+
+    orderbook.add_market_order(MarketOrder::new(dec!(150), Side::Bids, 2));
+    let order = orderbook.get_order(open_order.order_id).unwrap();
+    assert_eq!(order.status,OrderStatus::Filled);
+    //terminal orders leave the live order map
+    assert!(orderbook.order_map.is_empty());
+    assert!(orderbook.order_history.get(open_order.order_id).is_some());
+}
+
+#[test]
+fn test_deleted_order_is_cancelled(){
+    let mut orderbook = new_orderbook();
+    let open_order = orderbook.add_limit_order(LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None});
+    orderbook.delete_order(open_order.order_id).unwrap();
+
+    let order = orderbook.get_order(open_order.order_id).unwrap();
+    assert_eq!(order.status,OrderStatus::Cancelled);
+    assert!(!orderbook.order_map.contains_key(&open_order.order_id));
+
+    let result = orderbook.delete_order(open_order.order_id);
+    assert_eq!(result,Err(CustomError::OrderAlreadyClosed));
+    let modify_order_request = ModifyOrderRequest{price:None,quantity:Some(dec!(300)),order_id:open_order.order_id};
+    let result = orderbook.modify_order(modify_order_request);
+    assert_eq!(result,Err(CustomError::OrderAlreadyClosed));
+}
+
+#[test]
+fn test_open_orders_and_history_per_user(){
+    let mut orderbook = new_orderbook();
+    let order_1 = orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None});
+    let order_2 = orderbook.add_limit_order(LimitOrder{price:dec!(99),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None});
+    let order_3 = orderbook.add_limit_order(LimitOrder{price:dec!(98),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None});
+    orderbook.add_limit_order(LimitOrder{price:dec!(98),quantity:dec!(10),side:Side::Bids,user_id:2,client_order_id:None});
+    orderbook.delete_order(order_2.order_id).unwrap();
+    //fills order_1 completely, user 3's taker order is filled too
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Asks,user_id:3,client_order_id:None});
+
+    let open_orders = orderbook.get_open_orders(1);
+    assert_eq!(open_orders.iter().map(|o|o.order_id).collect::<Vec<u64>>(),vec![order_3.order_id]);
+
+    let history = orderbook.get_order_history(1, 10);
+    assert_eq!(history.iter().map(|o|(o.order_id,o.status.clone())).collect::<Vec<_>>(),vec![
+        (order_1.order_id,OrderStatus::Filled),
+        (order_2.order_id,OrderStatus::Cancelled)
+    ]);
+    assert_eq!(orderbook.get_order_history(1, 1).len(),1);
+    assert_eq!(orderbook.get_order_history(3, 10)[0].status,OrderStatus::Filled);
+    assert!(orderbook.get_order_history(2, 10).is_empty());
+}
+
+#[test]
+fn test_orders_archived_since(){
+    let mut orderbook = new_orderbook();
+    let order_1 = orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None});
+    let order_2 = orderbook.add_limit_order(LimitOrder{price:dec!(99),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None});
+    orderbook.delete_order(order_1.order_id).unwrap();
+    let archived_total = orderbook.order_history.archived_total;
+    assert_eq!(archived_total,1);
+
+    orderbook.delete_order(order_2.order_id).unwrap();
+    let since:Vec<u64> = orderbook.order_history.archived_since(archived_total).map(|o|o.order_id).collect();
+    assert_eq!(since,vec![order_2.order_id]);
+    let all:Vec<u64> = orderbook.order_history.archived_since(0).map(|o|o.order_id).collect();
+    assert_eq!(all,vec![order_1.order_id,order_2.order_id]);
+    assert_eq!(orderbook.order_history.archived_since(2).count(),0);
+}
\ No newline at end of file
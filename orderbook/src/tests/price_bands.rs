@@ -0,0 +1,89 @@
+#[cfg(test)]
+use pretty_assertions::{assert_eq};
+use rust_decimal::{dec, Decimal};
+
+use crate::{orderbook::types::Side, BandAction, CustomError, LimitOrder, MarketOrder, MarketOrderResponse, ModifyOrderRequest, ModifyOrderResponse, OrderStatus, PriceBandConfig, ReferencePrice};
+
+use super::{new_orderbook, Orderbook};
+
+fn limit_order(price:Decimal,quantity:Decimal,side:Side,user_id:u64)->LimitOrder{
+    LimitOrder{price,quantity,side,user_id,client_order_id:None}
+}
+
+//trades once at 100 so the band has a reference, then rests asks at 105 and 115
+fn banded_orderbook(action:BandAction)->Orderbook{
+    let mut orderbook = new_orderbook();
+    orderbook.set_price_band(Some(PriceBandConfig{width_bps:1000,reference:ReferencePrice::LastTrade,action}));
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(1), Side::Asks, 1));
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(1), Side::Bids, 2));
+    orderbook.add_limit_order(limit_order(dec!(105), dec!(1), Side::Asks, 1));
+    orderbook.add_limit_order(limit_order(dec!(115), dec!(1), Side::Asks, 1));
+    orderbook
+}
+
+#[test]
+fn test_limit_order_outside_band_is_rejected(){
+    let mut orderbook = banded_orderbook(BandAction::Reject);
+    assert_eq!(orderbook.price_band.limits(),Some((dec!(90),dec!(110))));
+    let order = orderbook.add_limit_order(limit_order(dec!(120), dec!(2), Side::Bids, 2));
+    assert_eq!(order.status,OrderStatus::Rejected);
+    assert_eq!(order.quantity_filled,dec!(0));
+    assert_eq!(orderbook.get_order(order.order_id).unwrap().status,OrderStatus::Rejected);
+    assert_eq!(orderbook.get_asks().len(),2);
+}
+
+#[test]
+fn test_limit_order_outside_band_is_truncated_to_the_band(){
+    let mut orderbook = banded_orderbook(BandAction::Truncate);
+    let order = orderbook.add_limit_order(limit_order(dec!(120), dec!(2), Side::Bids, 2));
+    assert_eq!(order.price,dec!(110));
+    assert_eq!(order.quantity_filled,dec!(1));
+    assert_eq!(orderbook.get_best_bid().unwrap(),dec!(110));
+    assert_eq!(orderbook.get_best_ask().unwrap(),dec!(115));
+}
+
+#[test]
+fn test_modified_price_is_held_to_the_band(){
+    let mut orderbook = banded_orderbook(BandAction::Reject);
+    let bid = orderbook.add_limit_order(limit_order(dec!(95), dec!(2), Side::Bids, 2));
+    let result = orderbook.modify_order(ModifyOrderRequest{price:Some(dec!(120)),quantity:None,order_id:bid.order_id});
+    assert_eq!(result,Err(CustomError::PriceBandExceeded));
+    assert_eq!(orderbook.get_order(bid.order_id).unwrap().price,dec!(95));
+    let result = orderbook.modify_order(ModifyOrderRequest{price:Some(dec!(80)),quantity:None,order_id:4});
+    assert_eq!(result,Err(CustomError::PriceBandExceeded));
+    assert_eq!(orderbook.get_asks().len(),2);
+
+    let mut orderbook = banded_orderbook(BandAction::Truncate);
+    let bid = orderbook.add_limit_order(limit_order(dec!(95), dec!(2), Side::Bids, 2));
+    let result = orderbook.modify_order(ModifyOrderRequest{price:Some(dec!(120)),quantity:None,order_id:bid.order_id});
+    assert_eq!(result,Ok(ModifyOrderResponse::new(dec!(110), dec!(2), bid.order_id)));
+    //trades the ask inside the band and rests the rest at the edge, short of the ask past it
+    let order = orderbook.get_order(bid.order_id).unwrap();
+    assert_eq!((order.price,order.quantity_filled),(dec!(110),dec!(1)));
+    assert_eq!(orderbook.get_best_ask().unwrap(),dec!(115));
+}
+
+#[test]
+fn test_market_order_stops_at_the_band(){
+    let mut orderbook = banded_orderbook(BandAction::Truncate);
+    let response = orderbook.add_market_order(MarketOrder::new(dec!(2), Side::Bids, 2));
+    assert_eq!(response,MarketOrderResponse::new(true, Some(dec!(105)), Some(dec!(1)), None).with_remaining(dec!(1)));
+    assert_eq!(orderbook.get_best_ask().unwrap(),dec!(115));
+
+    let mut orderbook = banded_orderbook(BandAction::Reject);
+    let response = orderbook.add_market_order(MarketOrder::new(dec!(2), Side::Bids, 2));
+    assert_eq!(response,MarketOrderResponse::new(false, None, None, Some(CustomError::PriceBandExceeded)));
+    assert_eq!(orderbook.get_asks().len(),2);
+}
+
+#[test]
+fn test_ema_reference_price(){
+    let mut orderbook = new_orderbook();
+    orderbook.set_price_band(Some(PriceBandConfig{width_bps:1000,reference:ReferencePrice::Ema(dec!(0.5)),action:BandAction::Reject}));
+    assert_eq!(orderbook.price_band.limits(),None);
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(1), Side::Asks, 1));
+    orderbook.add_limit_order(limit_order(dec!(100), dec!(1), Side::Bids, 2));
+    orderbook.add_limit_order(limit_order(dec!(108), dec!(1), Side::Asks, 1));
+    orderbook.add_market_order(MarketOrder::new(dec!(1), Side::Bids, 2));
+    assert_eq!(orderbook.price_band.reference_price,Some(dec!(104)));
+}
\ No newline at end of file
@@ -1,62 +1,63 @@
 use rust_decimal::dec;
 
-use crate::{orderbook::types::Side, LimitOrder,Orderbook};
-use std::cmp::Reverse;
+use crate::{orderbook::types::Side, LimitOrder};
+
+use super::new_orderbook;
 
 #[cfg(test)]
 use pretty_assertions::{assert_eq, assert_ne};
 
 #[test]
 fn test_get_best_ask(){
-    let mut orderbook = Orderbook::new();
+    let mut orderbook = new_orderbook();
 
     //Check if the asks is empty
-    assert!(orderbook.get_best_ask()==None);
+    assert!(orderbook.get_best_ask().is_none());
 
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order.clone());
     assert_ne!(orderbook.get_best_ask(),None);
-    assert_eq!(orderbook.get_best_ask(),Some(&limit_order.price));
+    assert_eq!(orderbook.get_best_ask(),Some(limit_order.price));
     
-    let limit_order_2 = LimitOrder{price:dec!(110),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let limit_order_2 = LimitOrder{price:dec!(110),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order_2.clone());
-    assert_ne!(orderbook.get_best_ask(),Some(&limit_order_2.price));
-    assert_eq!(orderbook.get_best_ask(),Some(&limit_order.price));
+    assert_ne!(orderbook.get_best_ask(),Some(limit_order_2.price));
+    assert_eq!(orderbook.get_best_ask(),Some(limit_order.price));
     
-    let limit_order_3= LimitOrder{price:dec!(100),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let limit_order_3= LimitOrder{price:dec!(100),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order_3.clone());
-    assert_ne!(orderbook.get_best_ask(),Some(&limit_order.price));
-    assert_eq!(orderbook.get_best_ask(),Some(&limit_order_3.price));
+    assert_ne!(orderbook.get_best_ask(),Some(limit_order.price));
+    assert_eq!(orderbook.get_best_ask(),Some(limit_order_3.price));
 }
 
 #[test]
 fn test_get_best_bid(){
-    let mut orderbook = Orderbook::new();
+    let mut orderbook = new_orderbook();
     
-    assert!(orderbook.get_best_bid()==None);
+    assert!(orderbook.get_best_bid().is_none());
 
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order.clone());
     assert_ne!(orderbook.get_best_bid(),None);
-    assert_eq!(orderbook.get_best_bid(),Some(&Reverse(limit_order.price)));
+    assert_eq!(orderbook.get_best_bid(),Some(limit_order.price));
 
-    let limit_order_2 = LimitOrder{price:dec!(105.5),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let limit_order_2 = LimitOrder{price:dec!(105.5),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order_2.clone());
-    assert_ne!(orderbook.get_best_bid(),Some(&Reverse(limit_order.price)));
-    assert_eq!(orderbook.get_best_bid(),Some(&Reverse(limit_order_2.price)));
+    assert_ne!(orderbook.get_best_bid(),Some(limit_order.price));
+    assert_eq!(orderbook.get_best_bid(),Some(limit_order_2.price));
 
-    let limit_order_3 = LimitOrder{price:dec!(104.5),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let limit_order_3 = LimitOrder{price:dec!(104.5),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order_3.clone());
-    assert_ne!(orderbook.get_best_bid(),Some(&Reverse(limit_order_3.price)));
-    assert_eq!(orderbook.get_best_bid(),Some(&Reverse(limit_order_2.price)));
+    assert_ne!(orderbook.get_best_bid(),Some(limit_order_3.price));
+    assert_eq!(orderbook.get_best_bid(),Some(limit_order_2.price));
 }
 
 #[test]
 fn test_get_spread(){
-    let mut orderbook = Orderbook::new();
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let mut orderbook = new_orderbook();
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order.clone());
-    let limit_order_2 = LimitOrder{price:dec!(104),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let limit_order_2 = LimitOrder{price:dec!(104),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order_2.clone());
 
     let result = orderbook.get_spread();
@@ -64,17 +65,17 @@ fn test_get_spread(){
 
     drop(orderbook);
 
-    let orderbook = Orderbook::new();
+    let orderbook = new_orderbook();
     let result = orderbook.get_spread();
     assert_eq!(result,None);
 }
 
 #[test]
 fn test_get_mid_price(){
-    let mut orderbook = Orderbook::new();
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
+    let mut orderbook = new_orderbook();
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order.clone());
-    let limit_order_2 = LimitOrder{price:dec!(104),quantity:dec!(200),side:Side::Bids,user_id:1};
+    let limit_order_2 = LimitOrder{price:dec!(104),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
     orderbook.add_limit_order(limit_order_2.clone());
 
     let result = orderbook.mid_price();
@@ -82,7 +83,7 @@ fn test_get_mid_price(){
 
     drop(orderbook);
 
-    let orderbook = Orderbook::new();
+    let orderbook = new_orderbook();
     let result = orderbook.get_spread();
     assert_eq!(result,None);
 }
\ No newline at end of file
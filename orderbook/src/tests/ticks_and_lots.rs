@@ -0,0 +1,71 @@
+#[cfg(test)]
+use pretty_assertions::{assert_eq};
+use rust_decimal::{dec, Decimal};
+
+use crate::{orderbook::types::Side, BandAction, CustomError, FixedPoint, LimitOrder, MarketOrder, ModifyOrderRequest, Numeric, OrderStatus, PriceBandConfig, ReferencePrice};
+
+use super::fixed_point::{new_orderbook, scale};
+
+fn limit_order(price:Decimal,quantity:Decimal,side:Side)->LimitOrder{
+    LimitOrder{price,quantity,side,user_id:1,client_order_id:None}
+}
+
+#[test]
+fn test_fixed_point_counts_ticks_and_lots(){
+    //ticks of 0.01 and lots of 0.001
+    assert_eq!(FixedPoint::from_price(dec!(100.05), &scale()),Some(FixedPoint(10005)));
+    assert_eq!(FixedPoint::from_price(dec!(100.005), &scale()),None);
+    assert_eq!(FixedPoint::from_quantity(dec!(1.5), &scale()),Some(FixedPoint(1500)));
+    assert_eq!(FixedPoint(10005).to_price(&scale()),dec!(100.05));
+    assert_eq!(FixedPoint::notional_to_decimal(FixedPoint::notional(FixedPoint(10005), FixedPoint(1500)), &scale()),dec!(150.075));
+}
+
+#[test]
+fn test_fixed_point_sums_saturate(){
+    assert_eq!(FixedPoint(i64::MAX)+FixedPoint(1),FixedPoint(i64::MAX));
+    assert_eq!(FixedPoint(i64::MIN)-FixedPoint(1),FixedPoint(i64::MIN));
+    assert_eq!([FixedPoint(i64::MAX),FixedPoint(i64::MAX),FixedPoint(-5)].into_iter().sum::<FixedPoint>(),FixedPoint(i64::MAX-5));
+    //a quantity in range can still be more lots than an i64 holds
+    let scale=crate::FixedPointScale::new(dec!(0.01), dec!(0.000001));
+    assert_eq!(FixedPoint::from_quantity(crate::MAX_QUANTITY, &scale),None);
+}
+
+#[test]
+fn test_orders_off_the_ticks_or_lots_are_rejected(){
+    let mut orderbook = new_orderbook();
+    let order = orderbook.add_limit_order(limit_order(dec!(100.005), dec!(1), Side::Bids));
+    assert_eq!(order.status,OrderStatus::Rejected);
+    assert_eq!(orderbook.get_order(order.order_id).unwrap().price,dec!(100.005));
+    let order = orderbook.add_limit_order(limit_order(dec!(100), dec!(1.0005), Side::Bids));
+    assert_eq!(order.status,OrderStatus::Rejected);
+    assert!(orderbook.bids.is_empty());
+
+    let order = orderbook.add_limit_order(limit_order(dec!(100), dec!(1), Side::Bids));
+    assert_eq!(orderbook.modify_order(ModifyOrderRequest{price:Some(dec!(100.005)),quantity:None,order_id:order.order_id}),Err(CustomError::InvalidPrice));
+    assert_eq!(orderbook.modify_order(ModifyOrderRequest{price:None,quantity:Some(dec!(0.0005)),order_id:order.order_id}),Err(CustomError::InvalidQuantity));
+    let response = orderbook.add_market_order(MarketOrder::new(dec!(0.0005), Side::Asks, 2));
+    assert!(!response.is_success());
+    assert_eq!(orderbook.get_order(order.order_id).unwrap().quantity_filled,dec!(0));
+}
+
+#[test]
+fn test_band_edge_between_ticks_is_rounded_inside_the_band(){
+    let mut orderbook = new_orderbook();
+    orderbook.set_price_band(Some(PriceBandConfig{width_bps:1000,reference:ReferencePrice::LastTrade,action:BandAction::Truncate}));
+    orderbook.add_limit_order(limit_order(dec!(100.01), dec!(1), Side::Asks));
+    orderbook.add_limit_order(limit_order(dec!(100.01), dec!(1), Side::Bids));
+    assert_eq!(orderbook.price_band.limits(),Some((dec!(90.009),dec!(110.011))));
+    assert_eq!(orderbook.add_limit_order(limit_order(dec!(120), dec!(1), Side::Bids)).price,dec!(110.01));
+    assert_eq!(orderbook.add_limit_order(limit_order(dec!(80), dec!(1), Side::Asks)).price,dec!(90.01));
+}
+
+#[test]
+fn test_quote_budget_buys_whole_lots(){
+    let mut orderbook = new_orderbook();
+    orderbook.add_limit_order(limit_order(dec!(3), dec!(5), Side::Asks));
+    let response = orderbook.add_market_order(MarketOrder::buy_with_quote(dec!(10), 2));
+    //3.333 lots cost 9.999, the rest of the budget buys less than a lot
+    assert!(response.is_success());
+    assert_eq!(orderbook.get_order(1).unwrap().quantity_filled,dec!(3.333));
+    assert_eq!(orderbook.get_recent_trades(1)[0].quantity,dec!(3.333));
+}
\ No newline at end of file
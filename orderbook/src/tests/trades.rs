@@ -0,0 +1,100 @@
+#[cfg(test)]
+use pretty_assertions::{assert_eq};
+use rust_decimal::dec;
+
+use crate::{orderbook::types::Side, FeeSchedule, LiquidityRole, LimitOrder, MarketOrder, TradeHistoryQuery};
+
+use super::new_orderbook;
+
+#[test]
+fn test_limit_order_match_records_trade(){
+    let mut orderbook = new_orderbook();
+    let limit_order_1 = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let limit_order_2 = LimitOrder{price:dec!(106),quantity:dec!(150),side:Side::Bids,user_id:2,client_order_id:None};
+    orderbook.add_limit_order(limit_order_1);
+    orderbook.add_limit_order(limit_order_2);
+
+    let trades = orderbook.trade_log.trades.clone();
+    assert_eq!(trades.len(),1);
+    let trade = &trades[0];
+    assert_eq!(trade.trade_id,1);
+    //trades execute at the resting order's price
+    assert_eq!(trade.price,dec!(105));
+    assert_eq!(trade.quantity,dec!(150));
+    assert_eq!(trade.taker_side,Side::Bids);
+    assert_eq!(trade.maker_order_id,1);
+    assert_eq!(trade.maker_user_id,1);
+    assert_eq!(trade.taker_order_id,Some(2));
+    assert_eq!(trade.taker_user_id,2);
+}
+
+#[test]
+fn test_market_order_records_a_trade_per_fill(){
+    let mut orderbook = new_orderbook();
+    let limit_order_1 = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
+    let limit_order_2 = LimitOrder{price:dec!(104),quantity:dec!(200),side:Side::Bids,user_id:1,client_order_id:None};
+    orderbook.add_limit_order(limit_order_1);
+    orderbook.add_limit_order(limit_order_2);
+    orderbook.add_market_order(MarketOrder::new(dec!(300), Side::Asks, 2));
+
+    let trades = orderbook.get_recent_trades(10);
+    assert_eq!(trades.len(),2);
+    //most recent trade first
+    assert_eq!((trades[0].trade_id,trades[0].price,trades[0].quantity),(2,dec!(104),dec!(100)));
+    assert_eq!((trades[1].trade_id,trades[1].price,trades[1].quantity),(1,dec!(105),dec!(200)));
+    assert_eq!(orderbook.trade_log.trades[0].taker_order_id,None);
+}
+
+#[test]
+fn test_trade_history_pagination(){
+    let mut orderbook = new_orderbook();
+    for _ in 0..5{
+        orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Asks,user_id:1,client_order_id:None});
+    }
+    for _ in 0..5{
+        orderbook.add_market_order(MarketOrder::new(dec!(10), Side::Bids, 2));
+    }
+
+    let query = TradeHistoryQuery{limit:Some(2),..Default::default()};
+    let page = orderbook.get_trade_history(query);
+    assert_eq!(page.trades.iter().map(|t|t.trade_id).collect::<Vec<u64>>(),vec![1,2]);
+    assert_eq!(page.next_cursor,Some(2));
+
+    let query = TradeHistoryQuery{from_trade_id:Some(2),limit:Some(2),..Default::default()};
+    let page = orderbook.get_trade_history(query);
+    assert_eq!(page.trades.iter().map(|t|t.trade_id).collect::<Vec<u64>>(),vec![3,4]);
+    assert_eq!(page.next_cursor,Some(4));
+
+    let query = TradeHistoryQuery{from_trade_id:Some(4),limit:Some(2),..Default::default()};
+    let page = orderbook.get_trade_history(query);
+    assert_eq!(page.trades.iter().map(|t|t.trade_id).collect::<Vec<u64>>(),vec![5]);
+    assert_eq!(page.next_cursor,None);
+
+    //nothing traded before the epoch
+    let query = TradeHistoryQuery{end_time:Some(0),..Default::default()};
+    assert!(orderbook.get_trade_history(query).trades.is_empty());
+}
+
+#[test]
+fn test_user_trades_include_fee_and_role(){
+    let mut orderbook = new_orderbook();
+    orderbook.set_fee_schedule(FeeSchedule::new(dec!(0.001), dec!(0.002)));
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Asks,user_id:1,client_order_id:None});
+    orderbook.add_limit_order(LimitOrder{price:dec!(100),quantity:dec!(4),side:Side::Bids,user_id:2,client_order_id:None});
+
+    let maker_trades = orderbook.get_user_trades(1, TradeHistoryQuery::default()).trades;
+    assert_eq!(maker_trades.len(),1);
+    assert_eq!(maker_trades[0].role,LiquidityRole::Maker);
+    assert_eq!(maker_trades[0].side,Side::Asks);
+    assert_eq!(maker_trades[0].order_id,Some(1));
+    assert_eq!(maker_trades[0].fee,dec!(0.4));
+
+    let taker_trades = orderbook.get_user_trades(2, TradeHistoryQuery::default()).trades;
+    assert_eq!(taker_trades.len(),1);
+    assert_eq!(taker_trades[0].role,LiquidityRole::Taker);
+    assert_eq!(taker_trades[0].side,Side::Bids);
+    assert_eq!(taker_trades[0].order_id,Some(2));
+    assert_eq!(taker_trades[0].fee,dec!(0.8));
+
+    assert!(orderbook.get_user_trades(3, TradeHistoryQuery::default()).trades.is_empty());
+}
\ No newline at end of file
@@ -0,0 +1,361 @@
+use std::{collections::{HashMap, HashSet, VecDeque}, sync::{Arc, Mutex, PoisonError}};
+
+use axum::{
+    body::{to_bytes, Body}, extract::{rejection::RawPathParamsRejection, RawPathParams, Request, State}, http::HeaderMap, middleware::Next, response::{IntoResponse, Response}, Extension
+};
+use hmac::{Hmac, Mac};
+use orderbook::current_timestamp;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use trading_engine::TradingPair;
+
+use crate::types::auth::AuthError;
+use crate::types::error::ErrorResponse;
+use crate::types::v2::Market;
+
+pub const API_KEY_HEADER:&str="x-api-key";
+pub const TIMESTAMP_HEADER:&str="x-api-timestamp";
+pub const NONCE_HEADER:&str="x-api-nonce";
+pub const SIGNATURE_HEADER:&str="x-api-signature";
+//how far a request timestamp may be from the server clock
+pub const RECV_WINDOW_MS:u64=5_000;
+const MAX_BODY_SIZE:usize=2*1024*1024;
+
+type HmacSha256=Hmac<Sha256>;
+
+//ordered, every role can do what the roles below it can
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,PartialOrd,Ord,Serialize,Deserialize)]
+pub enum Role{
+    //own orders, trades and history
+    #[default]
+    ReadOnly,
+    //placing, amending and cancelling orders
+    Trade,
+    //market creation, halts and configuration
+    Admin
+}
+
+#[derive(Clone,Serialize,Deserialize)]
+pub struct ApiKey{
+    pub api_key: String,
+    pub secret: String,
+    pub user_id: u64,
+    #[serde(default)]
+    pub role: Role,
+    //markets the key may act on, every market when not set
+    #[serde(default)]
+    pub markets: Option<Vec<TradingPair>>
+}
+
+//the user an api key belongs to, handlers take it from the request extensions
+#[derive(Clone,Debug)]
+pub struct AuthenticatedUser{
+    pub api_key: String,
+    pub user_id: u64,
+    pub role: Role,
+    pub markets: Option<Vec<TradingPair>>
+}
+
+impl AuthenticatedUser{
+    pub fn can_access(&self,trading_pair:&TradingPair)->bool{
+        self.markets.as_ref().is_none_or(|markets|markets.contains(trading_pair))
+    }
+}
+
+//only the market is read from the body to check a key's scope
+#[derive(Deserialize)]
+struct ScopedRequest{
+    trading_pair: Option<TradingPair>
+}
+
+#[derive(Default)]
+struct Nonces{
+    seen: HashSet<(String,String)>,
+    //arrival order, so nonces can be forgotten once their timestamp can no longer pass the window
+    arrivals: VecDeque<(u64,String,String)>
+}
+
+pub struct ApiKeys{
+    keys: Mutex<HashMap<String,ApiKey>>,
+    nonces: Mutex<Nonces>
+}
+
+impl ApiKeys{
+    pub fn new(keys:Vec<ApiKey>)->Arc<ApiKeys>{
+        let keys=keys.into_iter().map(|k|(k.api_key.clone(),k)).collect();
+        Arc::new(ApiKeys { keys:Mutex::new(keys), nonces:Mutex::new(Nonces::default()) })
+    }
+
+    //reads a json array of api keys, a missing file means no keys
+    pub fn load(path:&str)->Arc<ApiKeys>{
+        let keys = match std::fs::read_to_string(path){
+            Ok(contents)=>serde_json::from_str(&contents).unwrap_or_else(|e|{
+                tracing::error!(path, error=%e, "could not parse api keys");
+                Vec::new()
+            }),
+            Err(_)=>{
+                tracing::warn!(path, "no api keys file, every authenticated route will answer 401");
+                Vec::new()
+            }
+        };
+        ApiKeys::new(keys)
+    }
+
+    //checks the key, the timestamp window, the nonce and the signature over timestamp + nonce + method + path + body
+    pub fn verify(&self,headers:&HeaderMap,method:&str,path:&str,body:&[u8])->Result<AuthenticatedUser,AuthError>{
+        let api_key=header(headers, API_KEY_HEADER).ok_or(AuthError::MissingApiKey)?;
+        let timestamp=header(headers, TIMESTAMP_HEADER).ok_or(AuthError::MissingSignature)?;
+        let nonce=header(headers, NONCE_HEADER).ok_or(AuthError::MissingSignature)?;
+        let signature=header(headers, SIGNATURE_HEADER).ok_or(AuthError::MissingSignature)?;
+        let key=self.keys.lock().unwrap_or_else(PoisonError::into_inner).get(api_key).cloned().ok_or(AuthError::InvalidApiKey)?;
+
+        let now=current_timestamp();
+        let request_time:u64=timestamp.parse().map_err(|_|AuthError::TimestampOutsideWindow)?;
+        if request_time.abs_diff(now)>RECV_WINDOW_MS{
+            return Err(AuthError::TimestampOutsideWindow)
+        }
+
+        let signature=hex::decode(signature).map_err(|_|AuthError::InvalidSignature)?;
+        let mut mac=HmacSha256::new_from_slice(key.secret.as_bytes()).map_err(|_|AuthError::InvalidSignature)?;
+        mac.update(timestamp.as_bytes());
+        mac.update(nonce.as_bytes());
+        mac.update(method.as_bytes());
+        mac.update(path.as_bytes());
+        mac.update(body);
+        mac.verify_slice(&signature).map_err(|_|AuthError::InvalidSignature)?;
+
+        //only remember nonces of correctly signed requests so nobody can burn another key's nonces
+        let mut nonces=self.nonces.lock().unwrap_or_else(PoisonError::into_inner);
+        while nonces.arrivals.front().is_some_and(|(arrived_at,_,_)|arrived_at+2*RECV_WINDOW_MS<now)
+            && let Some((_,k,n))=nonces.arrivals.pop_front(){
+            nonces.seen.remove(&(k,n));
+        }
+        if !nonces.seen.insert((api_key.to_string(),nonce.to_string())){
+            return Err(AuthError::NonceReused)
+        }
+        nonces.arrivals.push_back((now,api_key.to_string(),nonce.to_string()));
+        Ok(AuthenticatedUser { api_key:key.api_key, user_id:key.user_id, role:key.role, markets:key.markets })
+    }
+}
+
+fn header<'a>(headers:&'a HeaderMap,name:&str)->Option<&'a str>{
+    headers.get(name)?.to_str().ok()
+}
+
+//tower middleware that rejects unsigned requests and hands the signer to the handler
+pub async fn authenticate(
+    State(api_keys):State<Arc<ApiKeys>>,
+    request:Request,
+    next:Next
+)->Response{
+    let (mut parts,body)=request.into_parts();
+    let Ok(bytes)=to_bytes(body, MAX_BODY_SIZE).await else{
+        return ErrorResponse::new(AuthError::BodyTooLarge).into_response()
+    };
+    let path=parts.uri.path_and_query().map(|p|p.as_str()).unwrap_or("/");
+    match api_keys.verify(&parts.headers, parts.method.as_str(), path, &bytes){
+        Ok(user)=>{
+            parts.extensions.insert(user);
+            next.run(Request::from_parts(parts, Body::from(bytes))).await
+        },
+        Err(e)=>{
+            tracing::warn!(error=?e, path, "rejected unauthenticated request");
+            ErrorResponse::new(e).into_response()
+        }
+    }
+}
+
+//runs after `authenticate`, turns away keys below the route's role or outside their markets
+pub async fn authorize(
+    State(required):State<Role>,
+    Extension(user):Extension<AuthenticatedUser>,
+    path_params:Result<RawPathParams,RawPathParamsRejection>,
+    request:Request,
+    next:Next
+)->Response{
+    if user.role<required{
+        return ErrorResponse::new(AuthError::InsufficientRole).into_response()
+    }
+    if user.markets.is_none(){
+        return next.run(request).await
+    }
+    //v2 routes name the market in the path, one that does not parse is left for the handler to reject
+    let path_market=path_params.ok().and_then(|params|{
+        params.iter().find(|(name,_)|*name=="market").and_then(|(_,market)|Market::try_from(market.to_string()).ok())
+    });
+    if let Some(Market(trading_pair))=path_market
+        && !user.can_access(&trading_pair){
+        return ErrorResponse::new(AuthError::MarketNotInScope).into_response()
+    }
+    let (parts,body)=request.into_parts();
+    let Ok(bytes)=to_bytes(body, MAX_BODY_SIZE).await else{
+        return ErrorResponse::new(AuthError::BodyTooLarge).into_response()
+    };
+    //bodies that do not parse are left for the handler to reject
+    if let Ok(ScopedRequest{trading_pair:Some(trading_pair)})=serde_json::from_slice::<ScopedRequest>(&bytes)
+        && !user.can_access(&trading_pair){
+        return ErrorResponse::new(AuthError::MarketNotInScope).into_response()
+    }
+    next.run(Request::from_parts(parts, Body::from(bytes))).await
+}
+
+#[cfg(test)]
+mod tests{
+    use axum::{body::to_bytes, http::{HeaderValue, StatusCode}, middleware, routing::post, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn api_keys()->Arc<ApiKeys>{
+        ApiKeys::new(vec![
+            ApiKey{api_key:"k1".to_string(),secret:"s1".to_string(),user_id:1,role:Role::Trade,markets:Some(vec![TradingPair::new("BTC".to_string(), "USD".to_string())])},
+            ApiKey{api_key:"ro".to_string(),secret:"s2".to_string(),user_id:2,role:Role::ReadOnly,markets:None}
+        ])
+    }
+
+    fn signed_headers(api_key:&str,secret:&str,timestamp:u64,nonce:&str,method:&str,path:&str,body:&[u8])->HeaderMap{
+        let timestamp=timestamp.to_string();
+        let mut mac=HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(timestamp.as_bytes());
+        mac.update(nonce.as_bytes());
+        mac.update(method.as_bytes());
+        mac.update(path.as_bytes());
+        mac.update(body);
+        let mut headers=HeaderMap::new();
+        headers.insert(API_KEY_HEADER, HeaderValue::from_str(api_key).unwrap());
+        headers.insert(TIMESTAMP_HEADER, HeaderValue::from_str(&timestamp).unwrap());
+        headers.insert(NONCE_HEADER, HeaderValue::from_str(nonce).unwrap());
+        headers.insert(SIGNATURE_HEADER, HeaderValue::from_str(&hex::encode(mac.finalize().into_bytes())).unwrap());
+        headers
+    }
+
+    fn signed_request(api_key:&str,secret:&str,nonce:&str,path:&str,body:&str)->Request{
+        let headers=signed_headers(api_key, secret, current_timestamp(), nonce, "POST", path, body.as_bytes());
+        let mut request=Request::post(path).body(Body::from(body.to_string())).unwrap();
+        *request.headers_mut()=headers;
+        request
+    }
+
+    //a route behind the same layers as the real ones, answering with the signer
+    fn router(required:Role)->Router{
+        Router::new()
+            .route("/probe", post(|Extension(user):Extension<AuthenticatedUser>|async move{user.user_id.to_string()}))
+            .route("/probe/{market}", post(|Extension(user):Extension<AuthenticatedUser>|async move{user.user_id.to_string()}))
+            .route_layer(middleware::from_fn_with_state(required, authorize))
+            .route_layer(middleware::from_fn_with_state(api_keys(), authenticate))
+    }
+
+    async fn send(router:Router,request:Request)->(StatusCode,String){
+        let response=router.oneshot(request).await.unwrap();
+        let status=response.status();
+        let body=to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status,String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[test]
+    fn test_correctly_signed_request_is_verified(){
+        let api_keys=api_keys();
+        let headers=signed_headers("k1", "s1", current_timestamp(), "n1", "POST", "/api/v1/limit-order", b"{}");
+        let user=api_keys.verify(&headers, "POST", "/api/v1/limit-order", b"{}").unwrap();
+        assert_eq!((user.user_id,user.role),(1,Role::Trade));
+    }
+
+    #[test]
+    fn test_signature_covers_the_whole_request(){
+        let api_keys=api_keys();
+        let headers=signed_headers("k1", "s1", current_timestamp(), "n1", "POST", "/api/v1/limit-order", b"{}");
+        assert_eq!(api_keys.verify(&headers, "POST", "/api/v1/limit-order", b"{\"quantity\":1}").unwrap_err(),AuthError::InvalidSignature);
+        assert_eq!(api_keys.verify(&headers, "DELETE", "/api/v1/limit-order", b"{}").unwrap_err(),AuthError::InvalidSignature);
+        assert_eq!(api_keys.verify(&headers, "POST", "/api/v1/order", b"{}").unwrap_err(),AuthError::InvalidSignature);
+        //signed with another key's secret
+        let headers=signed_headers("k1", "s2", current_timestamp(), "n2", "POST", "/api/v1/limit-order", b"{}");
+        assert_eq!(api_keys.verify(&headers, "POST", "/api/v1/limit-order", b"{}").unwrap_err(),AuthError::InvalidSignature);
+    }
+
+    #[test]
+    fn test_missing_and_unknown_keys(){
+        let api_keys=api_keys();
+        assert_eq!(api_keys.verify(&HeaderMap::new(), "GET", "/", b"").unwrap_err(),AuthError::MissingApiKey);
+        let mut headers=signed_headers("k1", "s1", current_timestamp(), "n1", "GET", "/", b"");
+        headers.remove(SIGNATURE_HEADER);
+        assert_eq!(api_keys.verify(&headers, "GET", "/", b"").unwrap_err(),AuthError::MissingSignature);
+        let headers=signed_headers("nobody", "s1", current_timestamp(), "n1", "GET", "/", b"");
+        assert_eq!(api_keys.verify(&headers, "GET", "/", b"").unwrap_err(),AuthError::InvalidApiKey);
+    }
+
+    #[test]
+    fn test_timestamp_must_be_within_the_window(){
+        let api_keys=api_keys();
+        let now=current_timestamp();
+        for (nonce,timestamp) in [("old",now-RECV_WINDOW_MS-1_000),("ahead",now+RECV_WINDOW_MS+1_000)]{
+            let headers=signed_headers("k1", "s1", timestamp, nonce, "GET", "/", b"");
+            assert_eq!(api_keys.verify(&headers, "GET", "/", b"").unwrap_err(),AuthError::TimestampOutsideWindow);
+        }
+        let headers=signed_headers("k1", "s1", now-RECV_WINDOW_MS/2, "recent", "GET", "/", b"");
+        assert!(api_keys.verify(&headers, "GET", "/", b"").is_ok());
+        let mut headers=signed_headers("k1", "s1", now, "garbled", "GET", "/", b"");
+        headers.insert(TIMESTAMP_HEADER, HeaderValue::from_static("yesterday"));
+        assert_eq!(api_keys.verify(&headers, "GET", "/", b"").unwrap_err(),AuthError::TimestampOutsideWindow);
+    }
+
+    #[test]
+    fn test_nonce_cannot_be_replayed(){
+        let api_keys=api_keys();
+        let headers=signed_headers("k1", "s1", current_timestamp(), "n1", "GET", "/", b"");
+        assert!(api_keys.verify(&headers, "GET", "/", b"").is_ok());
+        assert_eq!(api_keys.verify(&headers, "GET", "/", b"").unwrap_err(),AuthError::NonceReused);
+        //nonces are per key
+        let headers=signed_headers("ro", "s2", current_timestamp(), "n1", "GET", "/", b"");
+        assert!(api_keys.verify(&headers, "GET", "/", b"").is_ok());
+    }
+
+    #[test]
+    fn test_badly_signed_request_does_not_use_up_the_nonce(){
+        let api_keys=api_keys();
+        let headers=signed_headers("k1", "wrong", current_timestamp(), "n1", "GET", "/", b"");
+        assert_eq!(api_keys.verify(&headers, "GET", "/", b"").unwrap_err(),AuthError::InvalidSignature);
+        let headers=signed_headers("k1", "s1", current_timestamp(), "n1", "GET", "/", b"");
+        assert!(api_keys.verify(&headers, "GET", "/", b"").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unauthenticated_request_is_answered_with_401(){
+        let request=Request::post("/probe").body(Body::from("{}")).unwrap();
+        assert_eq!(send(router(Role::ReadOnly), request).await,(StatusCode::UNAUTHORIZED,"{\"error\":\"MissingApiKey\"}".to_string()));
+        let mut request=signed_request("k1", "s1", "n1", "/probe", "{}");
+        *request.body_mut()=Body::from("{\"tampered\":true}");
+        assert_eq!(send(router(Role::ReadOnly), request).await,(StatusCode::UNAUTHORIZED,"{\"error\":\"InvalidSignature\"}".to_string()));
+        let request=signed_request("k1", "s1", "n2", "/probe", "{}");
+        assert_eq!(send(router(Role::ReadOnly), request).await,(StatusCode::OK,"1".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_key_below_the_routes_role_is_answered_with_403(){
+        let request=signed_request("ro", "s2", "n1", "/probe", "{}");
+        assert_eq!(send(router(Role::Trade), request).await,(StatusCode::FORBIDDEN,"{\"error\":\"InsufficientRole\"}".to_string()));
+        let request=signed_request("ro", "s2", "n2", "/probe", "{}");
+        assert_eq!(send(router(Role::ReadOnly), request).await,(StatusCode::OK,"2".to_string()));
+        let request=signed_request("k1", "s1", "n1", "/probe", "{}");
+        assert_eq!(send(router(Role::Admin), request).await,(StatusCode::FORBIDDEN,"{\"error\":\"InsufficientRole\"}".to_string()));
+        let request=signed_request("k1", "s1", "n2", "/probe", "{}");
+        assert_eq!(send(router(Role::Trade), request).await,(StatusCode::OK,"1".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_key_outside_its_markets_is_answered_with_403(){
+        let eth_usd="{\"trading_pair\":{\"base\":\"ETH\",\"quote\":\"USD\"}}";
+        let btc_usd="{\"trading_pair\":{\"base\":\"BTC\",\"quote\":\"USD\"}}";
+        let request=signed_request("k1", "s1", "n1", "/probe", eth_usd);
+        assert_eq!(send(router(Role::Trade), request).await,(StatusCode::FORBIDDEN,"{\"error\":\"MarketNotInScope\"}".to_string()));
+        let request=signed_request("k1", "s1", "n2", "/probe", btc_usd);
+        assert_eq!(send(router(Role::Trade), request).await,(StatusCode::OK,"1".to_string()));
+        //v2 names the market in the path
+        let request=signed_request("k1", "s1", "n3", "/probe/ETH-USD", "{}");
+        assert_eq!(send(router(Role::Trade), request).await,(StatusCode::FORBIDDEN,"{\"error\":\"MarketNotInScope\"}".to_string()));
+        let request=signed_request("k1", "s1", "n4", "/probe/BTC-USD", "{}");
+        assert_eq!(send(router(Role::Trade), request).await,(StatusCode::OK,"1".to_string()));
+        //an unscoped key reaches every market
+        let request=signed_request("ro", "s2", "n1", "/probe/ETH-USD", eth_usd);
+        assert_eq!(send(router(Role::ReadOnly), request).await,(StatusCode::OK,"2".to_string()));
+    }
+}
\ No newline at end of file
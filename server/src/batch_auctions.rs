@@ -0,0 +1,22 @@
+use std::{sync::Arc, time::Duration};
+
+use orderbook::current_timestamp;
+use tokio::task::JoinHandle;
+use trading_engine::TradingEngine;
+
+//resolution of the batch clock, intervals shorter than this clear every tick
+pub const CLEARING_TICK:Duration=Duration::from_millis(10);
+
+//clears the batch auction markets whose interval ran out, continuous markets are left alone
+pub fn spawn_batch_clock(engine:Arc<TradingEngine>)->JoinHandle<()>{
+    tokio::spawn(async move{
+        let mut interval=tokio::time::interval(CLEARING_TICK);
+        loop{
+            interval.tick().await;
+            let clearings=engine.clear_due_batch_auctions(current_timestamp()).await;
+            for clearing in clearings{
+                tracing::debug!(base=clearing.trading_pair.base, quote=clearing.trading_pair.quote, price=%clearing.uncross.price, volume=%clearing.uncross.volume, "batch auction cleared");
+            }
+        }
+    })
+}
\ No newline at end of file
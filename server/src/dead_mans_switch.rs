@@ -0,0 +1,96 @@
+use std::{collections::HashMap, sync::{Arc, Mutex, MutexGuard, PoisonError}, time::{Duration, Instant}};
+
+use orderbook::current_timestamp;
+use tokio::task::JoinHandle;
+use trading_engine::{MarketCancellations, TradingEngine};
+
+use crate::types::dead_mans_switch::DeadMansSwitchError;
+
+pub const SWEEP_INTERVAL:Duration=Duration::from_millis(50);
+pub const MIN_TIMEOUT_MS:u64=100;
+//a websocket session is pinged this often and counts as disconnected once nothing arrived for the idle timeout,
+//so a peer that vanished without closing its connection still gets its orders cancelled
+pub const SESSION_PING_INTERVAL:Duration=Duration::from_secs(10);
+pub const SESSION_IDLE_TIMEOUT:Duration=Duration::from_secs(30);
+
+struct Timer{
+    timeout:Duration,
+    deadline:Instant
+}
+
+//cancels every open order of a user whose heartbeats stop arriving
+pub struct DeadMansSwitch{
+    engine:Arc<TradingEngine>,
+    timers:Mutex<HashMap<u64,Timer>>
+}
+
+impl DeadMansSwitch{
+    pub fn new(engine:Arc<TradingEngine>)->Arc<DeadMansSwitch>{
+        Arc::new(DeadMansSwitch { engine, timers:Mutex::new(HashMap::new()) })
+    }
+
+    //every update leaves the timers whole, so they are still good after a panic elsewhere
+    fn timers(&self)->MutexGuard<'_,HashMap<u64,Timer>>{
+        self.timers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    //returns the unix millisecond timestamp the switch fires at
+    pub fn arm(&self,user_id:u64,timeout_ms:u64)->Result<u64,DeadMansSwitchError>{
+        if timeout_ms<MIN_TIMEOUT_MS{
+            return Err(DeadMansSwitchError::TimeoutTooShort)
+        }
+        let timeout=Duration::from_millis(timeout_ms);
+        let mut timers=self.timers();
+        timers.insert(user_id, Timer { timeout, deadline:Instant::now()+timeout });
+        Ok(current_timestamp()+timeout_ms)
+    }
+
+    pub fn disarm(&self,user_id:u64)->bool{
+        let mut timers=self.timers();
+        timers.remove(&user_id).is_some()
+    }
+
+    pub fn heartbeat(&self,user_id:u64)->Result<u64,DeadMansSwitchError>{
+        let mut timers=self.timers();
+        match timers.get_mut(&user_id){
+            Some(timer)=>{
+                timer.deadline=Instant::now()+timer.timeout;
+                Ok(current_timestamp()+timer.timeout.as_millis() as u64)
+            },
+            None=>Err(DeadMansSwitchError::NotArmed)
+        }
+    }
+
+    pub async fn cancel_user_orders(&self,user_id:u64)->Vec<MarketCancellations>{
+        self.engine.cancel_all_for_user(user_id).await
+    }
+
+    //removes the expired timers and hands back their users
+    fn take_expired(&self)->Vec<u64>{
+        let now=Instant::now();
+        let mut timers=self.timers();
+        let expired:Vec<u64>=timers
+            .iter()
+            .filter(|(_,timer)|timer.deadline<=now)
+            .map(|(user_id,_)|*user_id)
+            .collect();
+        for user_id in expired.iter(){
+            timers.remove(user_id);
+        }
+        expired
+    }
+
+    pub fn spawn_sweeper(self:Arc<Self>)->JoinHandle<()>{
+        tokio::spawn(async move{
+            let mut interval=tokio::time::interval(SWEEP_INTERVAL);
+            loop{
+                interval.tick().await;
+                for user_id in self.take_expired(){
+                    let cancellations=self.cancel_user_orders(user_id).await;
+                    let cancelled:usize=cancellations.iter().map(|c|c.cancelled.len()).sum();
+                    tracing::warn!(user_id, cancelled, "dead man's switch expired, cancelled open orders");
+                }
+            }
+        })
+    }
+}
\ No newline at end of file
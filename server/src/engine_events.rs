@@ -0,0 +1,100 @@
+use std::{fs::OpenOptions, io::{BufWriter, Write}, thread};
+
+use tokio::sync::broadcast;
+use trading_engine::{EngineEvent, RingSubscriber, SequencedEvent, TradingEngine};
+
+//slots in each of the sequencer's rings, the matching thread stalls once the slowest consumer is this far behind
+pub const RING_CAPACITY:usize=1<<16;
+//events held for market data websockets, a client further behind than this skips ahead instead of holding anything up
+pub const MARKET_DATA_BUFFER:usize=4096;
+
+//every command goes through one sequencer and all markets match on one thread, the events it
+//publishes feed the market data websockets and, when a file is given, the journal
+pub fn sequenced_engine(journal_file:Option<String>,market_data:broadcast::Sender<SequencedEvent>)->TradingEngine{
+    let (engine,mut subscribers)=TradingEngine::sequenced(RING_CAPACITY, 1+journal_file.is_some() as usize);
+    if let Some(path)=journal_file
+        && let Some(subscriber)=subscribers.pop(){
+        spawn_journal(subscriber, path);
+    }
+    if let Some(subscriber)=subscribers.pop(){
+        spawn_market_data(subscriber, market_data);
+    }
+    engine
+}
+
+//appends every event as a json line and flushes whenever the ring runs dry
+fn spawn_journal(mut subscriber:RingSubscriber<SequencedEvent>,path:String){
+    thread::Builder::new()
+        .name("journal".to_string())
+        .spawn(move||{
+            let file=match OpenOptions::new().create(true).append(true).open(&path){
+                Ok(file)=>file,
+                Err(e)=>{
+                    tracing::error!(path, error=%e, "cannot open the event journal, events are not persisted");
+                    //keeps reading so the matching thread is not held up
+                    while subscriber.recv().is_some(){}
+                    return
+                }
+            };
+            let mut writer=BufWriter::new(file);
+            loop{
+                let event=match subscriber.try_recv(){
+                    Some(event)=>event,
+                    None=>{
+                        let _=writer.flush();
+                        match subscriber.recv(){
+                            Some(event)=>event,
+                            None=>break
+                        }
+                    }
+                };
+                if let Ok(line)=serde_json::to_string(&event){
+                    let _=writeln!(writer, "{line}");
+                }
+            }
+            let _=writer.flush();
+        })
+        .expect("failed to spawn journal thread");
+}
+
+//hands every event to whoever is subscribed to the market data, with nobody listening it is dropped
+fn spawn_market_data(mut subscriber:RingSubscriber<SequencedEvent>,market_data:broadcast::Sender<SequencedEvent>){
+    thread::Builder::new()
+        .name("market-data".to_string())
+        .spawn(move||{
+            while let Some(event)=subscriber.recv(){
+                if let EngineEvent::StatusChanged(status)=&event.event{
+                    tracing::info!(sequence=event.sequence, base=event.trading_pair.base, quote=event.trading_pair.quote, ?status, "market status changed");
+                }
+                let _=market_data.send(event);
+            }
+        })
+        .expect("failed to spawn market data thread");
+}
+
+#[cfg(test)]
+mod tests{
+    use orderbook::{LimitOrder, Side};
+    use rust_decimal::dec;
+    use trading_engine::TradingPair;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_market_data_reaches_subscribers_in_sequence(){
+        let (market_data,mut events)=broadcast::channel(MARKET_DATA_BUFFER);
+        let engine=sequenced_engine(None, market_data);
+        let pair=TradingPair::new("BTC".to_string(), "USD".to_string());
+        engine.create_market(pair.clone()).unwrap();
+        let _=engine.add_limit_order_into_market(pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Asks,user_id:1,client_order_id:None}).await;
+        let _=engine.add_limit_order_into_market(pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Bids,user_id:2,client_order_id:None}).await;
+        let mut received=Vec::new();
+        while !received.iter().any(|e:&SequencedEvent|matches!(e.event,EngineEvent::Trade(_))){
+            received.push(events.recv().await.unwrap());
+        }
+        assert!(received.iter().all(|e|e.trading_pair==pair));
+        assert!(received.windows(2).all(|w|w[0].sequence<=w[1].sequence));
+        let EngineEvent::Trade(trade)=&received.last().unwrap().event else{unreachable!()};
+        assert_eq!((trade.price,trade.quantity),(dec!(100),dec!(1)));
+    }
+}
\ No newline at end of file
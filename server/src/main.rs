@@ -1,18 +1,44 @@
-use std::sync::{Arc, Mutex};
+use std::{net::SocketAddr, sync::Arc};
 
 mod routes;
 mod router;
 mod types;
+mod dead_mans_switch;
+mod auth;
+mod rate_limit;
+mod batch_auctions;
+mod engine_events;
 
 use router::init_router;
+use dead_mans_switch::DeadMansSwitch;
+use auth::ApiKeys;
+use rate_limit::RateLimiter;
 
+use tokio::sync::broadcast;
+use tracing_subscriber::EnvFilter;
 use trading_engine::TradingEngine;
 
 #[tokio::main]
-async fn main() {
-    let trading_engine = Arc::new(Mutex::new(TradingEngine::new()));
-    let app = init_router(trading_engine);
+async fn main()->std::io::Result<()> {
+    //RUST_LOG picks what is logged, info and above by default
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_|EnvFilter::new("info")))
+        .init();
+    //published by the engine in sequenced mode and streamed to market data websockets
+    let (market_data,_) = broadcast::channel(engine_events::MARKET_DATA_BUFFER);
+    //ENGINE_MODE=sequenced puts every market behind one sequencer, by default each market matches on its own thread
+    let trading_engine = Arc::new(match std::env::var("ENGINE_MODE").as_deref(){
+        Ok("sequenced")=>engine_events::sequenced_engine(std::env::var("EVENT_JOURNAL_FILE").ok(), market_data.clone()),
+        _=>TradingEngine::new()
+    });
+    let dead_mans_switch = DeadMansSwitch::new(trading_engine.clone());
+    dead_mans_switch.clone().spawn_sweeper();
+    batch_auctions::spawn_batch_clock(trading_engine.clone());
+    let api_keys = ApiKeys::load(&std::env::var("API_KEYS_FILE").unwrap_or("api_keys.json".to_string()));
+    let rate_limiter = RateLimiter::load(&std::env::var("RATE_LIMITS_FILE").unwrap_or("rate_limits.json".to_string()));
+    let app = init_router(trading_engine, market_data, dead_mans_switch, api_keys, rate_limiter);
     // run our app with hyper, listening globally on port 3000
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:8000").await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:8000").await?;
+    tracing::info!(address=%listener.local_addr()?, "listening");
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await
 }
\ No newline at end of file
@@ -0,0 +1,341 @@
+use std::{collections::HashMap, net::SocketAddr, sync::{Arc, Mutex, PoisonError}, time::Instant};
+
+use axum::{
+    body::{to_bytes, Body}, extract::{ConnectInfo, MatchedPath, Request, State}, http::{HeaderMap, HeaderValue, StatusCode}, middleware::Next, response::{IntoResponse, Response}
+};
+use orderbook::MAX_BATCH_SIZE;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::AuthenticatedUser;
+use crate::types::error::ErrorResponse;
+use crate::types::rate_limit::RateLimitError;
+
+//idle buckets that have refilled are dropped once this many callers are tracked
+const MAX_TRACKED_BUCKETS:usize=10_000;
+const MAX_BODY_SIZE:usize=2*1024*1024;
+//routes that place new orders and count towards `orders_per_second`
+const ORDER_ENTRY_ROUTES:[(&str,&str);4]=[
+    ("POST","/api/v1/limit-order"),
+    ("POST","/api/v1/market-order"),
+    ("POST","/api/v1/batch-orders"),
+    ("POST","/api/v2/markets/{market}/orders")
+];
+
+#[derive(Clone,Serialize,Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig{
+    //burst size of a caller's bucket
+    pub capacity: u32,
+    pub refill_per_second: u32,
+    //new orders a user may place per second, on top of the request weight
+    pub orders_per_second: u32,
+    //burst size of a user's order bucket, a batch placing more orders than this is refused outright
+    pub orders_burst: u32,
+    //cost of a request by route, as written in the router, anything not listed costs `default_weight`
+    pub endpoint_weights: HashMap<String,u32>,
+    pub default_weight: u32,
+    //burst size and refill of an ip address's bucket, which every request pays into before it is authenticated
+    pub ip_capacity: u32,
+    pub ip_refill_per_second: u32
+}
+
+impl Default for RateLimitConfig{
+    fn default()->Self{
+        let endpoint_weights=[
+            ("/api/v1/depth",5),
+            ("/api/v1/trade-history",5),
+            ("/api/v1/my-trades",5),
+            ("/api/v1/order-history",5),
+            ("/api/v1/open-orders",2),
+            ("/api/v1/cancel-all",5),
+            ("/api/v1/batch-orders",10),
+            ("/api/v2/markets/{market}/depth",5)
+        ].into_iter().map(|(path,weight)|(path.to_string(),weight)).collect();
+        RateLimitConfig { capacity:100, refill_per_second:50, orders_per_second:20, orders_burst:MAX_BATCH_SIZE as u32, endpoint_weights, default_weight:1, ip_capacity:200, ip_refill_per_second:100 }
+    }
+}
+
+struct Bucket{
+    capacity: f64,
+    refill_per_second: f64,
+    tokens: f64,
+    updated_at: Instant
+}
+
+impl Bucket{
+    fn new(capacity:u32,refill_per_second:u32)->Bucket{
+        let capacity=capacity as f64;
+        Bucket { capacity, refill_per_second:(refill_per_second as f64).max(f64::MIN_POSITIVE), tokens:capacity, updated_at:Instant::now() }
+    }
+
+    fn refill(&mut self){
+        let now=Instant::now();
+        self.tokens=(self.tokens+now.duration_since(self.updated_at).as_secs_f64()*self.refill_per_second).min(self.capacity);
+        self.updated_at=now;
+    }
+}
+
+//what a caller's bucket looked like after a request, for the rate limit headers
+struct Usage{
+    allowed: bool,
+    limit: u32,
+    remaining: u32,
+    //seconds until the bucket is full again, or until the request would fit when it was refused
+    reset_after: u64
+}
+
+pub struct RateLimiter{
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<String,Bucket>>
+}
+
+impl RateLimiter{
+    pub fn new(config:RateLimitConfig)->Arc<RateLimiter>{
+        Arc::new(RateLimiter { config, buckets:Mutex::new(HashMap::new()) })
+    }
+
+    //reads the limits from a json file, falling back to the defaults
+    pub fn load(path:&str)->Arc<RateLimiter>{
+        let config = match std::fs::read_to_string(path){
+            Ok(contents)=>serde_json::from_str(&contents).unwrap_or_else(|e|{
+                tracing::error!(path, error=%e, "could not parse rate limits, using the defaults");
+                RateLimitConfig::default()
+            }),
+            Err(_)=>RateLimitConfig::default()
+        };
+        RateLimiter::new(config)
+    }
+
+    fn weight(&self,path:&str)->u32{
+        self.config.endpoint_weights.get(path).copied().unwrap_or(self.config.default_weight)
+    }
+
+    fn take(&self,key:String,cost:u32,capacity:u32,refill_per_second:u32)->Usage{
+        let mut buckets=self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+        if buckets.len()>MAX_TRACKED_BUCKETS{
+            buckets.retain(|_,b|{
+                b.refill();
+                b.tokens<b.capacity
+            });
+        }
+        let bucket=buckets.entry(key).or_insert_with(||Bucket::new(capacity, refill_per_second));
+        bucket.refill();
+        let allowed=bucket.tokens>=cost as f64;
+        if allowed{
+            bucket.tokens-=cost as f64;
+        }
+        let missing=if allowed{bucket.capacity-bucket.tokens}else{cost as f64-bucket.tokens};
+        Usage { allowed, limit:capacity, remaining:bucket.tokens.floor() as u32, reset_after:(missing/bucket.refill_per_second).ceil() as u64 }
+    }
+}
+
+fn client_ip(request:&Request)->String{
+    match request.extensions().get::<ConnectInfo<SocketAddr>>(){
+        Some(ConnectInfo(addr))=>format!("ip:{}",addr.ip()),
+        None=>"ip:unknown".to_string()
+    }
+}
+
+//the route rather than the path, so every market and order of a v2 resource is weighed alike
+fn route(request:&Request)->String{
+    request.extensions().get::<MatchedPath>().map_or(request.uri().path(), |p|p.as_str()).to_string()
+}
+
+//only the placements of a batch count as new orders
+#[derive(Deserialize)]
+struct BatchRequest{
+    operations: Vec<serde_json::Value>
+}
+
+fn set_headers(headers:&mut HeaderMap,usage:&Usage){
+    headers.insert("x-ratelimit-limit", HeaderValue::from(usage.limit));
+    headers.insert("x-ratelimit-remaining", HeaderValue::from(usage.remaining));
+    headers.insert("x-ratelimit-reset", HeaderValue::from(usage.reset_after));
+    if !usage.allowed{
+        headers.insert("retry-after", HeaderValue::from(usage.reset_after.max(1)));
+    }
+}
+
+fn refuse(usage:&Usage,error:RateLimitError)->Response{
+    let mut response=ErrorResponse::new(error).into_response();
+    set_headers(response.headers_mut(), usage);
+    response
+}
+
+//token bucket per ip address charged by the route's weight, outside authentication so requests that fail it are paid for too
+pub async fn ip_rate_limit(
+    State(limiter):State<Arc<RateLimiter>>,
+    request:Request,
+    next:Next
+)->Response{
+    let config=&limiter.config;
+    let usage=limiter.take(client_ip(&request), limiter.weight(&route(&request)), config.ip_capacity, config.ip_refill_per_second);
+    if !usage.allowed{
+        return refuse(&usage, RateLimitError::TooManyRequests)
+    }
+    let mut response=next.run(request).await;
+    //a signed request reports the bucket of its api key, set further in
+    if !response.headers().contains_key("x-ratelimit-limit"){
+        set_headers(response.headers_mut(), &usage);
+    }
+    response
+}
+
+//token bucket per api key charged by the route's weight, inside authentication
+pub async fn rate_limit(
+    State(limiter):State<Arc<RateLimiter>>,
+    request:Request,
+    next:Next
+)->Response{
+    let Some(user)=request.extensions().get::<AuthenticatedUser>().cloned() else{
+        return next.run(request).await
+    };
+    let path=route(&request);
+    let method=request.method().to_string();
+    let config=&limiter.config;
+    let usage=limiter.take(format!("key:{}",user.api_key), limiter.weight(&path), config.capacity, config.refill_per_second);
+    if !usage.allowed{
+        return refuse(&usage, RateLimitError::TooManyRequests)
+    }
+
+    let mut request=request;
+    if ORDER_ENTRY_ROUTES.contains(&(method.as_str(),path.as_str())){
+        let orders_key=format!("orders:{}",user.user_id);
+        let orders=if path=="/api/v1/batch-orders"{
+            let (parts,body)=request.into_parts();
+            let Ok(bytes)=to_bytes(body, MAX_BODY_SIZE).await else{
+                return StatusCode::PAYLOAD_TOO_LARGE.into_response()
+            };
+            let placed=serde_json::from_slice::<BatchRequest>(&bytes)
+                .map(|b|b.operations.iter().filter(|o|o.get("Place").is_some()).count() as u32)
+                .unwrap_or(0);
+            request=Request::from_parts(parts, Body::from(bytes));
+            placed
+        }else{
+            1
+        };
+        //waiting would never make room for such a batch, so it is told it is too large rather than when to retry
+        if orders>config.orders_burst{
+            return ErrorResponse::new(RateLimitError::BatchLargerThanOrderBurst).into_response()
+        }
+        let order_usage=limiter.take(orders_key, orders, config.orders_burst, config.orders_per_second);
+        if !order_usage.allowed{
+            return refuse(&order_usage, RateLimitError::TooManyOrders)
+        }
+    }
+
+    let mut response=next.run(request).await;
+    set_headers(response.headers_mut(), &usage);
+    response
+}
+
+#[cfg(test)]
+mod tests{
+    use std::time::Duration;
+
+    use axum::{middleware, routing::post, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::auth::Role;
+
+    fn batch(places:usize)->String{
+        let place="{\"Place\":{\"price\":\"100\",\"quantity\":\"1\",\"side\":\"Bids\",\"user_id\":1}}";
+        format!("{{\"operations\":[{}]}}",vec![place;places].join(","))
+    }
+
+    async fn send_batch(limiter:Arc<RateLimiter>,places:usize)->Response{
+        let router=Router::new()
+            .route("/api/v1/batch-orders", post(||async{"ok"}))
+            .route_layer(middleware::from_fn_with_state(limiter, rate_limit));
+        let mut request=Request::post("/api/v1/batch-orders").body(Body::from(batch(places))).unwrap();
+        request.extensions_mut().insert(AuthenticatedUser{api_key:"k1".to_string(),user_id:1,role:Role::Trade,markets:None});
+        router.oneshot(request).await.unwrap()
+    }
+
+    async fn error_of(response:Response)->String{
+        String::from_utf8(to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_bucket_refills_at_its_rate_up_to_capacity(){
+        let mut bucket=Bucket::new(10, 5);
+        bucket.tokens=0.0;
+        bucket.updated_at-=Duration::from_secs(1);
+        bucket.refill();
+        assert_eq!(bucket.tokens.floor(),5.0);
+        bucket.updated_at-=Duration::from_secs(10);
+        bucket.refill();
+        assert_eq!(bucket.tokens,10.0);
+    }
+
+    #[test]
+    fn test_usage_reports_what_is_left_and_when_it_refills(){
+        let limiter=RateLimiter::new(RateLimitConfig::default());
+        let usage=limiter.take("key:k1".to_string(), 3, 10, 2);
+        assert_eq!((usage.allowed,usage.limit,usage.remaining,usage.reset_after),(true,10,7,2));
+        //refused requests take nothing and say when they would fit
+        let usage=limiter.take("key:k1".to_string(), 8, 10, 2);
+        assert_eq!((usage.allowed,usage.limit,usage.remaining,usage.reset_after),(false,10,7,1));
+        let usage=limiter.take("key:k1".to_string(), 7, 10, 2);
+        assert_eq!((usage.allowed,usage.remaining,usage.reset_after),(true,0,5));
+    }
+
+    #[tokio::test]
+    async fn test_headers_report_the_request_bucket(){
+        let response=send_batch(RateLimiter::new(RateLimitConfig::default()), 1).await;
+        assert_eq!(response.status(),StatusCode::OK);
+        let headers=response.headers();
+        assert_eq!(headers["x-ratelimit-limit"],"100");
+        assert_eq!(headers["x-ratelimit-remaining"],"90");
+        assert_eq!(headers["x-ratelimit-reset"],"1");
+        assert!(headers.get("retry-after").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_batch_larger_than_orders_per_second_fits_the_burst(){
+        let limiter=RateLimiter::new(RateLimitConfig::default());
+        assert_eq!(send_batch(limiter.clone(), MAX_BATCH_SIZE).await.status(),StatusCode::OK);
+        //the burst is spent until it refills
+        let response=send_batch(limiter, 1).await;
+        assert_eq!(response.status(),StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["retry-after"],"1");
+        assert_eq!(error_of(response).await,"{\"error\":\"TooManyOrders\"}");
+    }
+
+    #[tokio::test]
+    async fn test_requests_failing_authentication_still_pay_their_ip(){
+        let limiter=RateLimiter::new(RateLimitConfig{ip_capacity:2,..RateLimitConfig::default()});
+        //stands in for authentication turning every request away
+        let router=Router::new()
+            .route("/api/v1/limit-order", post(||async{"ok"}))
+            .route_layer(middleware::from_fn(|_:Request,_:Next|async{StatusCode::UNAUTHORIZED.into_response()}))
+            .route_layer(middleware::from_fn_with_state(limiter, ip_rate_limit));
+        let send=|ip:[u8;4]|{
+            let mut request=Request::post("/api/v1/limit-order").body(Body::empty()).unwrap();
+            request.extensions_mut().insert(ConnectInfo(SocketAddr::from((ip,4000))));
+            router.clone().oneshot(request)
+        };
+        for _ in 0..2{
+            let response=send([10,0,0,1]).await.unwrap();
+            assert_eq!(response.status(),StatusCode::UNAUTHORIZED);
+            assert_eq!(response.headers()["x-ratelimit-limit"],"2");
+        }
+        let response=send([10,0,0,1]).await.unwrap();
+        assert_eq!(response.status(),StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(error_of(response).await,"{\"error\":\"TooManyRequests\"}");
+        //another address has its own bucket
+        assert_eq!(send([10,0,0,2]).await.unwrap().status(),StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_batch_larger_than_the_burst_is_told_so(){
+        let limiter=RateLimiter::new(RateLimitConfig{orders_burst:5,..RateLimitConfig::default()});
+        let response=send_batch(limiter.clone(), 6).await;
+        assert_eq!(response.status(),StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().get("retry-after").is_none());
+        assert_eq!(error_of(response).await,"{\"error\":\"BatchLargerThanOrderBurst\"}");
+        //nothing was taken from the order bucket
+        assert_eq!(send_batch(limiter, 5).await.status(),StatusCode::OK);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,14 @@
+use std::sync::Arc;
+
+use axum::{routing::{post}, Router};
+use trading_engine::TradingEngine;
+
+use crate::routes::batch_orders::{
+    create_batch_orders
+};
+
+pub fn batch_orders_router(state:Arc<TradingEngine>)->Router{
+    Router::new()
+        .route("/api/v1/batch-orders", post(create_batch_orders))
+        .with_state(state)
+}
\ No newline at end of file
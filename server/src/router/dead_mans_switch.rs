@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use axum::{routing::{any, post}, Router};
+
+use crate::dead_mans_switch::DeadMansSwitch;
+use crate::routes::dead_mans_switch::{
+    arm_dead_mans_switch,
+    heartbeat,
+    websocket_session
+};
+
+pub fn dead_mans_switch_router(state:Arc<DeadMansSwitch>)->Router{
+    Router::new()
+        .route("/api/v1/dead-mans-switch", post(arm_dead_mans_switch))
+        .route("/api/v1/heartbeat", post(heartbeat))
+        .route("/api/v1/ws", any(websocket_session))
+        .with_state(state)
+}
\ No newline at end of file
@@ -1,4 +1,4 @@
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use axum::{routing::{get}, Router};
 use trading_engine::TradingEngine;
@@ -8,7 +8,7 @@ use crate::routes::depth::{
     get_market_mid_price
 };
 
-pub fn market_depth_router(state:Arc<Mutex<TradingEngine>>)->Router{
+pub fn market_depth_router(state:Arc<TradingEngine>)->Router{
     Router::new()
         .route("/api/v1/depth", get(get_market_depth))
         .route("/api/v1/mid-price", get(get_market_mid_price))
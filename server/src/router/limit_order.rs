@@ -1,4 +1,4 @@
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use axum::{routing::{post}, Router};
 use trading_engine::TradingEngine;
@@ -7,7 +7,7 @@ use crate::routes::limit_order::{
     create_limit_order
 };
 
-pub fn limit_order_router(state:Arc<Mutex<TradingEngine>>)->Router{
+pub fn limit_order_router(state:Arc<TradingEngine>)->Router{
     Router::new()
         .route("/api/v1/limit-order", post(create_limit_order))
         .with_state(state)
@@ -0,0 +1,11 @@
+use axum::{routing::any, Router};
+use tokio::sync::broadcast;
+use trading_engine::SequencedEvent;
+
+use crate::routes::market_data::market_data_stream;
+
+pub fn market_data_router(market_data:broadcast::Sender<SequencedEvent>)->Router{
+    Router::new()
+        .route("/api/v1/market-data", any(market_data_stream))
+        .with_state(market_data)
+}
\ No newline at end of file
@@ -1,4 +1,4 @@
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use axum::{routing::{post}, Router};
 use trading_engine::TradingEngine;
@@ -7,7 +7,7 @@ use crate::routes::market_order::{
     create_market_order
 };
 
-pub fn market_order_router(state:Arc<Mutex<TradingEngine>>)->Router{
+pub fn market_order_router(state:Arc<TradingEngine>)->Router{
     Router::new()
         .route("/api/v1/market-order", post(create_market_order))
         .with_state(state)
@@ -2,12 +2,31 @@ use axum::{routing::{get, post}, Router};
 
 use crate::routes::markets::{
     create_market,
-    get_markets
+    get_markets,
+    halt_market,
+    resume_market,
+    configure_market,
+    configure_market_protection,
+    get_market_status,
+    set_market_status,
+    get_auction
 };
 
-pub fn markets_router(state:std::sync::Arc<std::sync::Mutex<trading_engine::TradingEngine>>)->Router{
+pub fn markets_router(state:std::sync::Arc<trading_engine::TradingEngine>)->Router{
     Router::new()
-        .route("/api/v1/create-market", post(create_market))
         .route("/api/v1/get-market", get(get_markets))
+        .route("/api/v1/market-status", get(get_market_status))
+        .route("/api/v1/auction", get(get_auction))
+        .with_state(state)
+}
+
+pub fn admin_markets_router(state:std::sync::Arc<trading_engine::TradingEngine>)->Router{
+    Router::new()
+        .route("/api/v1/create-market", post(create_market))
+        .route("/api/v1/halt-market", post(halt_market))
+        .route("/api/v1/resume-market", post(resume_market))
+        .route("/api/v1/set-market-status", post(set_market_status))
+        .route("/api/v1/market-config", post(configure_market))
+        .route("/api/v1/market-protection", post(configure_market_protection))
         .with_state(state)
 }
\ No newline at end of file
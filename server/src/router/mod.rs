@@ -1,25 +1,77 @@
-use std::sync::{Arc, Mutex};
+use std::{any::Any, sync::Arc};
 
-use axum::Router;
-use trading_engine::TradingEngine;
+use axum::{middleware, response::{IntoResponse, Response}, Router};
+use tokio::sync::broadcast;
+use tower_http::catch_panic::CatchPanicLayer;
+use trading_engine::{SequencedEvent, TradingEngine};
 
-use crate::router::markets::markets_router;
+use crate::router::markets::{markets_router, admin_markets_router};
 use crate::router::limit_order::limit_order_router;
 use crate::router::market_order::market_order_router;
 use crate::router::depth::market_depth_router;
-use crate::router::order::order_router;
+use crate::router::order::{order_router, order_query_router};
+use crate::router::trades::{trades_router, user_trades_router};
+use crate::router::batch_orders::batch_orders_router;
+use crate::router::dead_mans_switch::dead_mans_switch_router;
+use crate::router::v2::{v2_market_data_router, v2_order_query_router, v2_order_router};
+use crate::router::market_data::market_data_router;
+use crate::dead_mans_switch::DeadMansSwitch;
+use crate::auth::{authenticate, authorize, ApiKeys, Role};
+use crate::rate_limit::{ip_rate_limit, rate_limit, RateLimiter};
+use crate::types::error::{ApiError, ErrorResponse};
 
 mod markets;
 mod limit_order;
 mod market_order;
 mod depth;
 mod order;
+mod trades;
+mod batch_orders;
+mod dead_mans_switch;
+mod v2;
+mod market_data;
 
-pub fn init_router(state: Arc<Mutex<TradingEngine>>)->Router{
-    Router::new()
+pub fn init_router(state: Arc<TradingEngine>,market_data: broadcast::Sender<SequencedEvent>,dead_mans_switch: Arc<DeadMansSwitch>,api_keys: Arc<ApiKeys>,rate_limiter: Arc<RateLimiter>)->Router{
+    //public market data
+    let public = Router::new()
         .merge(markets_router(state.clone()))
+        .merge(market_depth_router(state.clone()))
+        .merge(trades_router(state.clone()))
+        .merge(v2_market_data_router(state.clone()))
+        .merge(market_data_router(market_data));
+    let read_only = Router::new()
+        .merge(order_query_router(state.clone()))
+        .merge(user_trades_router(state.clone()))
+        .merge(v2_order_query_router(state.clone()))
+        .route_layer(middleware::from_fn_with_state(Role::ReadOnly, authorize));
+    let trade = Router::new()
         .merge(limit_order_router(state.clone()))
         .merge(market_order_router(state.clone()))
-        .merge(market_depth_router(state.clone()))
         .merge(order_router(state.clone()))
+        .merge(batch_orders_router(state.clone()))
+        .merge(dead_mans_switch_router(dead_mans_switch))
+        .merge(v2_order_router(state.clone()))
+        .route_layer(middleware::from_fn_with_state(Role::Trade, authorize));
+    let admin = Router::new()
+        .merge(admin_markets_router(state.clone()))
+        .route_layer(middleware::from_fn_with_state(Role::Admin, authorize));
+    //everything acting as a user has to be signed with that user's api key
+    let authenticated = Router::new()
+        .merge(read_only)
+        .merge(trade)
+        .merge(admin)
+        //limited per api key, so it sits inside authentication
+        .route_layer(middleware::from_fn_with_state(rate_limiter.clone(), rate_limit))
+        .route_layer(middleware::from_fn_with_state(api_keys, authenticate));
+    //and every request per ip address, outside it, so unsigned and badly signed requests are limited as well
+    public.merge(authenticated)
+        .route_layer(middleware::from_fn_with_state(rate_limiter, ip_rate_limit))
+        .layer(CatchPanicLayer::custom(internal_error))
+}
+
+//a handler that panicked answers 500 rather than dropping the connection
+fn internal_error(panic:Box<dyn Any+Send+'static>)->Response{
+    let message=panic.downcast_ref::<&str>().copied().or(panic.downcast_ref::<String>().map(String::as_str)).unwrap_or("unknown");
+    tracing::error!(panic=message, "handler panicked");
+    ErrorResponse::new(ApiError::Internal).into_response()
 }
\ No newline at end of file
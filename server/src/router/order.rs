@@ -1,4 +1,4 @@
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use axum::{routing::{delete, get, post}, Router};
 use trading_engine::TradingEngine;
@@ -6,13 +6,24 @@ use trading_engine::TradingEngine;
 use crate::routes::order::{
     delete_order,
     modify_order,
-    get_order
+    get_order,
+    get_open_orders,
+    get_order_history,
+    cancel_all
 };
 
-pub fn order_router(state:Arc<Mutex<TradingEngine>>)->Router{
+pub fn order_router(state:Arc<TradingEngine>)->Router{
     Router::new()
         .route("/api/v1/delete-order", delete(delete_order))
+        .route("/api/v1/cancel-all", delete(cancel_all))
         .route("/api/v1/modify-order", post(modify_order))
+        .with_state(state)
+}
+
+pub fn order_query_router(state:Arc<TradingEngine>)->Router{
+    Router::new()
         .route("/api/v1/get-order",get(get_order))
+        .route("/api/v1/open-orders",get(get_open_orders))
+        .route("/api/v1/order-history",get(get_order_history))
         .with_state(state)
 }
\ No newline at end of file
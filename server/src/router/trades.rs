@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use axum::{routing::{get}, Router};
+use trading_engine::TradingEngine;
+
+use crate::routes::trades::{
+    get_recent_trades,
+    get_trade_history,
+    get_user_trades
+};
+
+pub fn trades_router(state:Arc<TradingEngine>)->Router{
+    Router::new()
+        .route("/api/v1/trades", get(get_recent_trades))
+        .route("/api/v1/trade-history", get(get_trade_history))
+        .with_state(state)
+}
+
+pub fn user_trades_router(state:Arc<TradingEngine>)->Router{
+    Router::new()
+        .route("/api/v1/my-trades", get(get_user_trades))
+        .with_state(state)
+}
\ No newline at end of file
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use axum::{routing::{delete, get, post}, Router};
+use trading_engine::TradingEngine;
+
+use crate::routes::v2::{
+    get_markets,
+    get_market_depth,
+    get_market_mid_price,
+    get_recent_trades,
+    get_open_orders,
+    create_order,
+    get_order,
+    delete_order
+};
+
+//markets are named in the path as `BASE-QUOTE` and order ids are counted per market, so orders live under their market
+pub fn v2_market_data_router(state:Arc<TradingEngine>)->Router{
+    Router::new()
+        .route("/api/v2/markets", get(get_markets))
+        .route("/api/v2/markets/{market}/depth", get(get_market_depth))
+        .route("/api/v2/markets/{market}/mid-price", get(get_market_mid_price))
+        .route("/api/v2/markets/{market}/trades", get(get_recent_trades))
+        .with_state(state)
+}
+
+pub fn v2_order_query_router(state:Arc<TradingEngine>)->Router{
+    Router::new()
+        .route("/api/v2/markets/{market}/orders", get(get_open_orders))
+        .route("/api/v2/markets/{market}/orders/{order_id}", get(get_order))
+        .with_state(state)
+}
+
+pub fn v2_order_router(state:Arc<TradingEngine>)->Router{
+    Router::new()
+        .route("/api/v2/markets/{market}/orders", post(create_order))
+        .route("/api/v2/markets/{market}/orders/{order_id}", delete(delete_order))
+        .with_state(state)
+}
\ No newline at end of file
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use  axum::{
+    extract::State, http::StatusCode, Extension, Json
+};
+use orderbook::BatchResult;
+use trading_engine::{TradingEngine, TradingEngineError};
+
+use crate::auth::AuthenticatedUser;
+use crate::types::error::ApiError;
+use crate::types::batch_orders::{
+    BatchOrdersRequest,
+    BatchOrdersResponse
+};
+
+pub async fn create_batch_orders(
+    State(state):State<Arc<TradingEngine>>,
+    Extension(user):Extension<AuthenticatedUser>,
+    Json(payload):Json<BatchOrdersRequest>,
+)->(StatusCode,Json<BatchOrdersResponse>){
+    //orders placed are the user's and a batch touching someone else's order is turned away as a whole
+    let result = state.apply_user_batch_for_market(payload.trading_pair, user.user_id, payload.operations, payload.all_or_nothing).await;
+    match result{
+        Ok(r)=>{
+            //a partly failed batch still applied its other items, one that applied nothing answers with the status of what stopped it
+            let failure = r.error.clone().or_else(||r.results.iter().find_map(|result|match result{
+                BatchResult::Failed(e) if payload.all_or_nothing=>Some(e.clone()),
+                _=>None
+            }));
+            let status = failure.map_or(StatusCode::OK,|e|ApiError::from(TradingEngineError::from(e)).status());
+            let response = BatchOrdersResponse::new(Some(r), None);
+            (status,Json(response))
+        },
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(BatchOrdersResponse::new(None, Some(error))))
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use  axum::{
+    body::Bytes, extract::{ws::{Message, WebSocket, WebSocketUpgrade}, Query, State}, http::StatusCode, response::Response, Extension, Json
+};
+use tokio::time::{interval_at, sleep, Instant};
+
+use crate::auth::AuthenticatedUser;
+use crate::types::error::ApiError;
+use crate::dead_mans_switch::{DeadMansSwitch, SESSION_IDLE_TIMEOUT, SESSION_PING_INTERVAL};
+use crate::types::dead_mans_switch::{
+    ArmDeadMansSwitchRequest,
+    DeadMansSwitchResponse,
+    SessionParams
+};
+
+pub async fn arm_dead_mans_switch(
+    State(state):State<Arc<DeadMansSwitch>>,
+    Extension(user):Extension<AuthenticatedUser>,
+    Json(payload):Json<ArmDeadMansSwitchRequest>,
+)->(StatusCode,Json<DeadMansSwitchResponse>){
+    if payload.timeout_ms==0{
+        state.disarm(user.user_id);
+        return (StatusCode::OK,Json(DeadMansSwitchResponse::new(user.user_id, None, None)))
+    }
+    match state.arm(user.user_id, payload.timeout_ms){
+        Ok(expires_at)=>{
+            let response = DeadMansSwitchResponse::new(user.user_id, Some(expires_at), None);
+            (StatusCode::OK,Json(response))
+        },
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(DeadMansSwitchResponse::new(user.user_id, None, Some(error))))
+        }
+    }
+}
+
+pub async fn heartbeat(
+    State(state):State<Arc<DeadMansSwitch>>,
+    Extension(user):Extension<AuthenticatedUser>,
+)->(StatusCode,Json<DeadMansSwitchResponse>){
+    match state.heartbeat(user.user_id){
+        Ok(expires_at)=>{
+            let response = DeadMansSwitchResponse::new(user.user_id, Some(expires_at), None);
+            (StatusCode::OK,Json(response))
+        },
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(DeadMansSwitchResponse::new(user.user_id, None, Some(error))))
+        }
+    }
+}
+
+pub async fn websocket_session(
+    ws:WebSocketUpgrade,
+    State(state):State<Arc<DeadMansSwitch>>,
+    Extension(user):Extension<AuthenticatedUser>,
+    Query(params):Query<SessionParams>,
+)->Response{
+    ws.on_upgrade(move |socket|run_session(socket, state, user, params))
+}
+
+//every frame received counts as a heartbeat for an armed switch, the pongs to the server's pings included
+async fn run_session(mut socket:WebSocket,state:Arc<DeadMansSwitch>,user:AuthenticatedUser,params:SessionParams){
+    let mut pings = interval_at(Instant::now()+SESSION_PING_INTERVAL, SESSION_PING_INTERVAL);
+    let idle = sleep(SESSION_IDLE_TIMEOUT);
+    tokio::pin!(idle);
+    loop{
+        let reply = tokio::select!{
+            message=socket.recv()=>{
+                let Some(Ok(message))=message else{
+                    break
+                };
+                let _ = state.heartbeat(user.user_id);
+                idle.as_mut().reset(Instant::now()+SESSION_IDLE_TIMEOUT);
+                match message{
+                    Message::Close(_)=>break,
+                    Message::Text(text) if text.as_str()=="ping"=>Message::Text("pong".into()),
+                    _=>continue
+                }
+            },
+            _=pings.tick()=>Message::Ping(Bytes::new()),
+            _=&mut idle=>{
+                tracing::info!(user_id=user.user_id, "websocket session went quiet, treating it as disconnected");
+                break
+            }
+        };
+        if socket.send(reply).await.is_err(){
+            break
+        }
+    }
+    if params.cancel_on_disconnect{
+        let cancellations = state.cancel_user_orders(user.user_id).await;
+        let cancelled:usize=cancellations.iter().map(|c|c.cancelled.len()).sum();
+        tracing::info!(user_id=user.user_id, cancelled, "websocket session closed, cancelled open orders");
+    }
+}
\ No newline at end of file
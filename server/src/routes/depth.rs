@@ -1,10 +1,11 @@
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use  axum::{
     extract::State, http::StatusCode, Json
 };
 use trading_engine::TradingEngine;
 
+use crate::types::error::ApiError;
 use crate::types::depth::{
     MarketDepthRequest,
     MarketDepthResponse,
@@ -13,38 +14,36 @@ use crate::types::depth::{
 };
 
 pub async fn get_market_depth(
-    State(state):State<Arc<Mutex<TradingEngine>>>,
+    State(state):State<Arc<TradingEngine>>,
     Json(payload):Json<MarketDepthRequest>,
 )->(StatusCode,Json<MarketDepthResponse>){
-    let mut trading_engine = state.lock().unwrap();
-    let result =trading_engine.get_market_depth(payload.trading_pair);
+    let result =state.snapshot(&payload.trading_pair);
     match result{
         Ok(r)=>{
-            let response = MarketDepthResponse::new(Some(r),None);
-            return (StatusCode::OK,Json(response))
+            let response = MarketDepthResponse::new(Some(r.depth(None)),None,Some(r.staleness));
+            (StatusCode::OK,Json(response))
         },
         Err(r)=>{
-            let response = MarketDepthResponse::new(None,Some(r));
-            return (StatusCode::BAD_REQUEST,Json(response))
+            let error = ApiError::from(r);
+            (error.status(),Json(MarketDepthResponse::new(None,Some(error),None)))
         }
     }
 }
 
 pub async fn get_market_mid_price(
-    State(state):State<Arc<Mutex<TradingEngine>>>,
+    State(state):State<Arc<TradingEngine>>,
     Json(payload):Json<MarketMidPriceRequest>,
 )->(StatusCode,Json<MarketMidPriceResponse>){
-    let mut trading_engine = state.lock().unwrap();
-    let result = trading_engine.get_mid_price_for_market(payload.trading_pair);
+    let result = state.snapshot(&payload.trading_pair);
     match result{
         Ok(r)=>{
-            let response = MarketMidPriceResponse::new(r, None);
-            return (StatusCode::OK,Json(response))
+            let response = MarketMidPriceResponse::new(r.mid_price, None, Some(r.staleness));
+            (StatusCode::OK,Json(response))
 
         },
         Err(r)=>{
-            let response = MarketMidPriceResponse::new(None, Some(r));
-            return (StatusCode::BAD_REQUEST,Json(response))
+            let error = ApiError::from(r);
+            (error.status(),Json(MarketMidPriceResponse::new(None, Some(error), None)))
         }
     }
 }
\ No newline at end of file
@@ -1,29 +1,32 @@
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use  axum::{
-    extract::State, http::StatusCode, Json
+    extract::State, http::StatusCode, Extension, Json
 };
 use trading_engine::TradingEngine;
 
+use crate::types::error::ApiError;
+use crate::auth::AuthenticatedUser;
 use crate::types::limit_order::{
     LimitOrderRequest,
     LimitOrderResponse
 };
 
 pub async fn create_limit_order(
-    State(state):State<Arc<Mutex<TradingEngine>>>,
-    Json(payload):Json<LimitOrderRequest>,
+    State(state):State<Arc<TradingEngine>>,
+    Extension(user):Extension<AuthenticatedUser>,
+    Json(mut payload):Json<LimitOrderRequest>,
 )->(StatusCode,Json<LimitOrderResponse>){
-    let mut trading_engine = state.lock().unwrap();
-    let result = trading_engine.add_limit_order_into_market(payload.trading_pair, payload.order);
+    payload.order.user_id=user.user_id;
+    let result = state.add_limit_order_into_market(payload.trading_pair, payload.order).await;
     match result{
         Ok(o)=>{
             let response = LimitOrderResponse::new(Some(o), None);
-            return (StatusCode::CREATED,Json(response))
+            (StatusCode::CREATED,Json(response))
         }
         Err(e)=>{
-            let response = LimitOrderResponse::new(None, Some(e));
-            return (StatusCode::BAD_REQUEST,Json(response))
+            let error = ApiError::from(e);
+            (error.status(),Json(LimitOrderResponse::new(None, Some(error))))
         }
     }
 }
\ No newline at end of file
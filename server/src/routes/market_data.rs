@@ -0,0 +1,47 @@
+use axum::{
+    extract::{ws::{Message, WebSocket, WebSocketUpgrade}, State}, response::Response
+};
+use tokio::sync::broadcast::{self, error::RecvError};
+use trading_engine::SequencedEvent;
+
+use crate::types::market_data::{MarketDataLagged, MarketDataParams};
+use crate::types::v2::{ApiQuery, Market};
+
+pub async fn market_data_stream(
+    ws:WebSocketUpgrade,
+    State(market_data):State<broadcast::Sender<SequencedEvent>>,
+    ApiQuery(params):ApiQuery<MarketDataParams>,
+)->Response{
+    //subscribed before the upgrade so nothing published in between is missed
+    let events=market_data.subscribe();
+    ws.on_upgrade(move |socket|stream_market_data(socket, events, params.market))
+}
+
+//one json text frame per event, in sequence. a client too slow to keep up is told how many events it missed
+//and carries on from the oldest one still held
+async fn stream_market_data(mut socket:WebSocket,mut events:broadcast::Receiver<SequencedEvent>,market:Option<Market>){
+    loop{
+        let text = tokio::select!{
+            event=events.recv()=>match event{
+                Ok(event)=>{
+                    if market.as_ref().is_some_and(|Market(trading_pair)|*trading_pair!=event.trading_pair){
+                        continue
+                    }
+                    serde_json::to_string(&event)
+                },
+                Err(RecvError::Lagged(missed))=>serde_json::to_string(&MarketDataLagged{missed}),
+                Err(RecvError::Closed)=>break
+            },
+            message=socket.recv()=>match message{
+                Some(Ok(Message::Close(_)))|Some(Err(_))|None=>break,
+                _=>continue
+            }
+        };
+        let Ok(text)=text else{
+            continue
+        };
+        if socket.send(Message::Text(text.into())).await.is_err(){
+            break
+        }
+    }
+}
\ No newline at end of file
@@ -1,30 +1,33 @@
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use  axum::{
-    extract::State, http::StatusCode, Json
+    extract::State, http::StatusCode, Extension, Json
 };
 use trading_engine::TradingEngine;
 
+use crate::types::error::ApiError;
+use crate::auth::AuthenticatedUser;
 use crate::types::market_order::{
     MarketOrderRequest,
     MarketOrderResponse
 };
 
 pub async fn create_market_order(
-    State(state):State<Arc<Mutex<TradingEngine>>>,
-    Json(payload):Json<MarketOrderRequest>,
+    State(state):State<Arc<TradingEngine>>,
+    Extension(user):Extension<AuthenticatedUser>,
+    Json(mut payload):Json<MarketOrderRequest>,
 )->(StatusCode,Json<MarketOrderResponse>){
-    let mut trading_engine = state.lock().unwrap();
-    let result = trading_engine.add_market_order_into_market(payload.trading_pair, payload.order);
+    payload.order.user_id=user.user_id;
+    let result = state.add_market_order_into_market(payload.trading_pair, payload.order).await;
     
     match result{
         Ok(r)=>{
             let response =MarketOrderResponse::new(Some(r), None);
-            return (StatusCode::OK,Json(response))
+            (StatusCode::OK,Json(response))
         },
         Err(e)=>{
-            let  response = MarketOrderResponse::new(None, Some(e));
-            return (StatusCode::BAD_REQUEST,Json(response))
+            let error = ApiError::from(e);
+            (error.status(),Json(MarketOrderResponse::new(None, Some(error))))
         }
 
     }
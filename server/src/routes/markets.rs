@@ -1,32 +1,200 @@
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use  axum::{
     extract::State, http::StatusCode, Json
 };
-use trading_engine::TradingEngine;
+use trading_engine::{MarketStatus, TradingEngine};
 
+use crate::types::error::ApiError;
 use crate::types::markets::{
+    AuctionRequest,
+    AuctionResponse,
     CreateMarketRequest,
     CreateMarketResponse,
-    GetMarketsResponse
+    GetMarketsResponse,
+    HaltMarketRequest,
+    HaltMarketResponse,
+    MarketConfigRequest,
+    MarketConfigResponse,
+    MarketProtectionRequest,
+    MarketProtectionResponse,
+    MarketStatusRequest,
+    MarketStatusResponse,
+    SetMarketStatusRequest
 };
 
 pub async fn create_market(
-    State(state):State<Arc<Mutex<TradingEngine>>>,
+    State(state):State<Arc<TradingEngine>>,
     Json(payload):Json<CreateMarketRequest>,
 )->(StatusCode,Json<CreateMarketResponse>){
-    let mut trading_engine = state.lock().unwrap();
-    match trading_engine.create_market(payload.trading_pair.clone()){
-        Ok(_)=>{(StatusCode::CREATED,Json(CreateMarketResponse::new(true, payload.trading_pair)))},
-        Err(_)=>{(StatusCode::BAD_REQUEST,Json(CreateMarketResponse::new(false, payload.trading_pair)))}
+    let created=payload.numeric_mode()
+        .and_then(|numeric_mode|state.create_market_with_modes(payload.trading_pair.clone(), payload.matching_mode, numeric_mode).map(|_|numeric_mode));
+    match created{
+        Ok(numeric_mode)=>{(StatusCode::CREATED,Json(CreateMarketResponse::new(true, payload.trading_pair, payload.matching_mode, Some(numeric_mode), None)))},
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(CreateMarketResponse::new(false, payload.trading_pair, payload.matching_mode, None, Some(error))))
+        }
     }
 }
 
 pub async fn get_markets(
-    State(state):State<Arc<Mutex<TradingEngine>>>
+    State(state):State<Arc<TradingEngine>>
 )->(StatusCode,Json<GetMarketsResponse>){
-    let trading_engine=state.lock().unwrap();
-    let markets = trading_engine.get_markets();
+    let markets = state.get_markets().await;
     let response =GetMarketsResponse::new(markets);
-    return (StatusCode::OK,Json(response))
+    (StatusCode::OK,Json(response))
+}
+
+pub async fn halt_market(
+    State(state):State<Arc<TradingEngine>>,
+    Json(payload):Json<HaltMarketRequest>,
+)->(StatusCode,Json<HaltMarketResponse>){
+    match state.halt_market(payload.trading_pair.clone()).await{
+        Ok(_)=>{
+            tracing::warn!(base=payload.trading_pair.base, quote=payload.trading_pair.quote, "market halted");
+            (StatusCode::OK,Json(HaltMarketResponse::new(payload.trading_pair, true, None)))
+        },
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(HaltMarketResponse::new(payload.trading_pair, false, Some(error))))
+        }
+    }
+}
+
+pub async fn resume_market(
+    State(state):State<Arc<TradingEngine>>,
+    Json(payload):Json<HaltMarketRequest>,
+)->(StatusCode,Json<HaltMarketResponse>){
+    match state.resume_market(payload.trading_pair.clone()).await{
+        Ok(_)=>{
+            tracing::info!(base=payload.trading_pair.base, quote=payload.trading_pair.quote, "market resumed");
+            (StatusCode::OK,Json(HaltMarketResponse::new(payload.trading_pair, false, None)))
+        },
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(HaltMarketResponse::new(payload.trading_pair, false, Some(error))))
+        }
+    }
+}
+
+pub async fn get_market_status(
+    State(state):State<Arc<TradingEngine>>,
+    Json(payload):Json<MarketStatusRequest>,
+)->(StatusCode,Json<MarketStatusResponse>){
+    match state.get_market_status(&payload.trading_pair).await{
+        Ok(status)=>(StatusCode::OK,Json(MarketStatusResponse::new(payload.trading_pair, Some(status), None))),
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(MarketStatusResponse::new(payload.trading_pair, None, Some(error))))
+        }
+    }
+}
+
+pub async fn get_auction(
+    State(state):State<Arc<TradingEngine>>,
+    Json(payload):Json<AuctionRequest>,
+)->(StatusCode,Json<AuctionResponse>){
+    match state.get_indicative_uncross_for_market(&payload.trading_pair).await{
+        Ok(indicative)=>(StatusCode::OK,Json(AuctionResponse::new(payload.trading_pair, indicative, None))),
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(AuctionResponse::new(payload.trading_pair, None, Some(error))))
+        }
+    }
+}
+
+pub async fn set_market_status(
+    State(state):State<Arc<TradingEngine>>,
+    Json(payload):Json<SetMarketStatusRequest>,
+)->(StatusCode,Json<MarketStatusResponse>){
+    match state.set_market_status(payload.trading_pair.clone(), payload.status).await{
+        Ok(change)=>{
+            if change.status==MarketStatus::Open{
+                tracing::info!(base=payload.trading_pair.base, quote=payload.trading_pair.quote, uncross=?change.uncross, "market opened");
+            }else{
+                tracing::warn!(base=payload.trading_pair.base, quote=payload.trading_pair.quote, status=?change.status, cancelled=change.cancelled.len(), "market status changed");
+            }
+            (StatusCode::OK,Json(MarketStatusResponse::changed(change)))
+        },
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(MarketStatusResponse::new(payload.trading_pair, None, Some(error))))
+        }
+    }
+}
+
+pub async fn configure_market_protection(
+    State(state):State<Arc<TradingEngine>>,
+    Json(payload):Json<MarketProtectionRequest>,
+)->(StatusCode,Json<MarketProtectionResponse>){
+    let (price_band,circuit_breaker)=(payload.price_band.clone(),payload.circuit_breaker.clone());
+    //both are set by one command so no order matches under only one of them
+    let result = state.on_market(&payload.trading_pair, move|market|{
+        market.orderbook.set_price_band(price_band);
+        market.set_circuit_breaker(circuit_breaker);
+    }).await;
+    match result{
+        Ok(_)=>(StatusCode::OK,Json(MarketProtectionResponse::new(payload.trading_pair, payload.price_band, payload.circuit_breaker, None))),
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(MarketProtectionResponse::new(payload.trading_pair, None, None, Some(error))))
+        }
+    }
+}
+
+pub async fn configure_market(
+    State(state):State<Arc<TradingEngine>>,
+    Json(payload):Json<MarketConfigRequest>,
+)->(StatusCode,Json<MarketConfigResponse>){
+    let (fee_schedule,allocation)=(payload.fee_schedule,payload.allocation);
+    //answers with the settings the market ends up with, including the ones left unchanged
+    let result = state.on_market(&payload.trading_pair, move|market|{
+        if let Some(fee_schedule)=fee_schedule{
+            market.orderbook.set_fee_schedule(fee_schedule);
+        }
+        if let Some(allocation)=allocation{
+            market.orderbook.set_allocation(allocation);
+        }
+        (market.orderbook.fee_schedule(),market.orderbook.allocation())
+    }).await;
+    match result{
+        Ok((fee_schedule,allocation))=>(StatusCode::OK,Json(MarketConfigResponse::new(payload.trading_pair, Some(fee_schedule), Some(allocation), None))),
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(MarketConfigResponse::new(payload.trading_pair, None, None, Some(error))))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use orderbook::ProRata;
+    use rust_decimal::dec;
+    use serde_json::json;
+    use trading_engine::TradingPair;
+
+    use super::*;
+
+    async fn configure(state:Arc<TradingEngine>,body:serde_json::Value)->serde_json::Value{
+        let (status,Json(response))=configure_market(State(state), Json(serde_json::from_value(body).unwrap())).await;
+        assert_eq!(status,StatusCode::OK);
+        serde_json::to_value(response).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_market_config_only_changes_what_is_sent(){
+        let engine=Arc::new(TradingEngine::new());
+        let _=engine.create_market(TradingPair::new("BTC".to_string(), "USD".to_string()));
+        let pair=json!({"base":"BTC","quote":"USD"});
+        let fees=json!({"maker_fee_rate":"0.001","taker_fee_rate":"0.002"});
+        let response=configure(engine.clone(), json!({"trading_pair":pair,"fee_schedule":fees})).await;
+        assert_eq!((&response["fee_schedule"],&response["allocation"]),(&fees,&json!("Fifo")));
+
+        //changing the allocation alone keeps the fees set before
+        let pro_rata=ProRata{min_allocation:dec!(1),lot_size:dec!(1)};
+        let response=configure(engine.clone(), json!({"trading_pair":pair,"allocation":{"ProRata":pro_rata}})).await;
+        assert_eq!(response["fee_schedule"],fees);
+        assert_eq!(response["allocation"],json!({"ProRata":pro_rata}));
+    }
 }
\ No newline at end of file
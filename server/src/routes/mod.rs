@@ -2,4 +2,9 @@ pub mod markets;
 pub mod limit_order;
 pub mod market_order;
 pub mod depth;
-pub mod order;
\ No newline at end of file
+pub mod order;
+pub mod trades;
+pub mod dead_mans_switch;
+pub mod batch_orders;
+pub mod v2;
+pub mod market_data;
\ No newline at end of file
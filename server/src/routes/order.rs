@@ -1,94 +1,205 @@
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use  axum::{
-    extract::State, http::StatusCode, Json
+    extract::State, http::StatusCode, Extension, Json
 };
-use trading_engine::TradingEngine;
+use orderbook::DEFAULT_ORDER_HISTORY_LIMIT;
+use trading_engine::{MarketCancellations, TradingEngine, TradingEngineError};
 
+use crate::types::error::ApiError;
+use crate::auth::AuthenticatedUser;
 use crate::types::order::{
     DeleteOrderRequest,
     DeleteOrderResponse, 
     ModifyOrderRequest,
     ModifyOrderResponse,
     GetOrderRequest,
-    GetOrderResponse
+    GetOrderResponse,
+    OpenOrdersRequest,
+    OrderHistoryRequest,
+    OrderListResponse,
+    CancelAllRequest,
+    CancelAllResponse,
+    OrderReference
 };
 
 pub async fn delete_order(
-    State(state):State<Arc<Mutex<TradingEngine>>>,
+    State(state):State<Arc<TradingEngine>>,
+    Extension(user):Extension<AuthenticatedUser>,
     Json(payload):Json<DeleteOrderRequest>,
 )->(StatusCode,Json<DeleteOrderResponse>){
-    let mut trading_engine = state.lock().unwrap();
-    let result =trading_engine.delete_order_for_market(payload.trading_pair, payload.order_id);
+    let result =match payload.order{
+        OrderReference::OrderId{order_id}=>state.delete_user_order_for_market(payload.trading_pair, user.user_id, order_id).await,
+        OrderReference::ClientOrderId{client_order_id}=>state.delete_order_by_client_order_id_for_market(payload.trading_pair, user.user_id, &client_order_id).await
+    };
     match result{
         Ok(r)=>{
-            match r{
-                Ok(res)=>{
-                    let response = DeleteOrderResponse::new(Some(res),None,None);
-                    return (StatusCode::OK,Json(response))
-                },
-                Err(e)=>{
-                    let response = DeleteOrderResponse::new(None, None, Some(e.error));
-                    return (StatusCode::BAD_REQUEST,Json(response))
-                }
-            }
+            let response = DeleteOrderResponse::new(Some(r),None);
+            (StatusCode::OK,Json(response))
         },
-        Err(r)=>{
-            let response = DeleteOrderResponse::new(None,Some(r),None);
-            return (StatusCode::BAD_REQUEST,Json(response))
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(DeleteOrderResponse::new(None,Some(error))))
         }
     }
 }
 
 pub async fn modify_order(
-    State(state):State<Arc<Mutex<TradingEngine>>>,
+    State(state):State<Arc<TradingEngine>>,
+    Extension(user):Extension<AuthenticatedUser>,
     Json(payload):Json<ModifyOrderRequest>,
 )->(StatusCode,Json<ModifyOrderResponse>){
-    let mut trading_engine = state.lock().unwrap();
-    let result = trading_engine.modify_order_for_market(payload.trading_pair, payload.order_request);
+    let result = state.modify_user_order_for_market(payload.trading_pair, user.user_id, payload.order_request).await;
     match result{
         Ok(r)=>{
-            match r{
-                Ok(res)=>{
-                    let response = ModifyOrderResponse::new(Some(res),None,None);
-                    return (StatusCode::OK,Json(response))
-
-                },
-                Err(res)=>{
-                    let response = ModifyOrderResponse::new(None,None, Some(res.error));
-                    return (StatusCode::BAD_REQUEST,Json(response))
-                }
-            }
+            let response = ModifyOrderResponse::new(Some(r),None);
+            (StatusCode::OK,Json(response))
         },
-        Err(r)=>{
-            let response = ModifyOrderResponse::new(None,Some(r), None);
-            return (StatusCode::BAD_REQUEST,Json(response))
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(ModifyOrderResponse::new(None,Some(error))))
         }
     }
 }
 
 pub async fn get_order(
-    State(state):State<Arc<Mutex<TradingEngine>>>,
+    State(state):State<Arc<TradingEngine>>,
+    Extension(user):Extension<AuthenticatedUser>,
     Json(payload):Json<GetOrderRequest>
 )->(StatusCode,Json<GetOrderResponse>){
-    let mut trading_engine = state.lock().unwrap();
-    let result = trading_engine.get_order_by_id_for_market(payload.trading_pair, payload.order_id);
+    //read from the market's snapshot, so an order just placed may take up to the reported staleness to show
+    let result = state.snapshot(&payload.trading_pair).map(|snapshot|{
+        let order = match payload.order{
+            OrderReference::OrderId{order_id}=>snapshot.get_order(order_id),
+            OrderReference::ClientOrderId{client_order_id}=>snapshot.get_order_by_client_order_id(user.user_id, &client_order_id)
+        };
+        //another user's order is reported as missing, as when cancelling it
+        (order.and_then(|o|if o.user_id==user.user_id{Ok(o)}else{Err(TradingEngineError::OrderDoesNotExist)}),snapshot.staleness)
+    });
+    match result{
+        Ok((Ok(r),staleness))=>{
+            let response = GetOrderResponse::new(Some(r), None, Some(staleness));
+            (StatusCode::OK,Json(response))
+        },
+        Ok((Err(e),staleness))=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(GetOrderResponse::new(None, Some(error), Some(staleness))))
+        },
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(GetOrderResponse::new(None, Some(error), None)))
+        }
+    }
+}
+
+pub async fn get_open_orders(
+    State(state):State<Arc<TradingEngine>>,
+    Extension(user):Extension<AuthenticatedUser>,
+    Json(payload):Json<OpenOrdersRequest>
+)->(StatusCode,Json<OrderListResponse>){
+    let result = state.get_open_orders_for_market(payload.trading_pair, user.user_id).await;
     match result{
         Ok(r)=>{
-            match r{
-                Ok(res)=>{
-                    let response = GetOrderResponse::new(Some(res), None, None);
-                    return (StatusCode::OK,Json(response))
-                }
-                Err(res)=>{
-                    let response = GetOrderResponse::new(None, None, Some(res.error));
-                    return (StatusCode::BAD_REQUEST,Json(response))
+            let response = OrderListResponse::new(Some(r), None);
+            (StatusCode::OK,Json(response))
+        },
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(OrderListResponse::new(None, Some(error))))
+        }
+    }
+}
+
+pub async fn get_order_history(
+    State(state):State<Arc<TradingEngine>>,
+    Extension(user):Extension<AuthenticatedUser>,
+    Json(payload):Json<OrderHistoryRequest>
+)->(StatusCode,Json<OrderListResponse>){
+    let limit = payload.limit.unwrap_or(DEFAULT_ORDER_HISTORY_LIMIT);
+    let result = state.get_order_history_for_market(payload.trading_pair, user.user_id, limit).await;
+    match result{
+        Ok(r)=>{
+            let response = OrderListResponse::new(Some(r), None);
+            (StatusCode::OK,Json(response))
+        },
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(OrderListResponse::new(None, Some(error))))
+        }
+    }
+}
+
+pub async fn cancel_all(
+    State(state):State<Arc<TradingEngine>>,
+    Extension(user):Extension<AuthenticatedUser>,
+    Json(mut payload):Json<CancelAllRequest>
+)->(StatusCode,Json<CancelAllResponse>){
+    //a user can only mass-cancel their own orders
+    payload.filter.user_id=Some(user.user_id);
+    match payload.trading_pair{
+        Some(trading_pair)=>{
+            let result = state.cancel_all_for_market(trading_pair.clone(), payload.filter).await;
+            match result{
+                Ok(r)=>{
+                    let response = CancelAllResponse::new(Some(vec![MarketCancellations::new(trading_pair, r)]), None);
+                    (StatusCode::OK,Json(response))
+                },
+                Err(e)=>{
+                    let error = ApiError::from(e);
+                    (error.status(),Json(CancelAllResponse::new(None, Some(error))))
                 }
             }
         },
-        Err(r)=>{
-            let response = GetOrderResponse::new(None, Some(r), None);
-            return (StatusCode::BAD_REQUEST,Json(response))
+        None=>{
+            let cancellations = match &user.markets{
+                //a market scoped key only sweeps the markets it may trade
+                Some(markets)=>{
+                    let mut cancellations = Vec::new();
+                    for m in markets.iter(){
+                        if let Ok(c)=state.cancel_all_for_market(m.clone(), payload.filter.clone()).await
+                            && !c.is_empty(){
+                            cancellations.push(MarketCancellations::new(m.clone(), c));
+                        }
+                    }
+                    cancellations
+                },
+                None=>state.cancel_all(payload.filter).await
+            };
+            let response = CancelAllResponse::new(Some(cancellations), None);
+            (StatusCode::OK,Json(response))
         }
     }
+}
+
+#[cfg(test)]
+mod tests{
+    use orderbook::{LimitOrder, MarketOrder, Side};
+    use rust_decimal::dec;
+    use serde_json::json;
+    use trading_engine::TradingPair;
+
+    use super::*;
+    use crate::auth::Role;
+
+    async fn delete(state:Arc<TradingEngine>,body:serde_json::Value)->(StatusCode,serde_json::Value){
+        let user=AuthenticatedUser{api_key:"k1".to_string(),user_id:1,role:Role::Trade,markets:None};
+        let (status,Json(response))=delete_order(State(state), Extension(user), Json(serde_json::from_value(body).unwrap())).await;
+        (status,serde_json::to_value(response).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_v1_failures_answer_with_the_status_of_their_error(){
+        let engine=Arc::new(TradingEngine::new());
+        let pair=TradingPair::new("BTC".to_string(), "USD".to_string());
+        let _=engine.create_market(pair.clone());
+        let ask=engine.add_limit_order_into_market(pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Asks,user_id:1,client_order_id:None}).await.unwrap();
+        let _=engine.add_market_order_into_market(pair, MarketOrder::new(dec!(1), Side::Bids, 2)).await;
+
+        let (status,response)=delete(engine.clone(), json!({"trading_pair":{"base":"ETH","quote":"USD"},"order_id":ask.order_id})).await;
+        assert_eq!((status,&response["error"]),(StatusCode::NOT_FOUND,&json!("TradingPairDoesNotExist")));
+        let (status,response)=delete(engine.clone(), json!({"trading_pair":{"base":"BTC","quote":"USD"},"order_id":99})).await;
+        assert_eq!((status,&response["error"]),(StatusCode::NOT_FOUND,&json!("OrderDoesNotExist")));
+        let (status,response)=delete(engine.clone(), json!({"trading_pair":{"base":"BTC","quote":"USD"},"order_id":ask.order_id})).await;
+        assert_eq!((status,&response["error"]),(StatusCode::CONFLICT,&json!("OrderAlreadyMatched")));
+    }
 }
\ No newline at end of file
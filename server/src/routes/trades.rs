@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use  axum::{
+    extract::State, http::StatusCode, Extension, Json
+};
+use orderbook::DEFAULT_TRADE_QUERY_LIMIT;
+use trading_engine::TradingEngine;
+
+use crate::types::error::ApiError;
+use crate::auth::AuthenticatedUser;
+use crate::types::trades::{
+    RecentTradesRequest,
+    RecentTradesResponse,
+    TradeHistoryRequest,
+    TradeHistoryResp,
+    UserTradesRequest,
+    UserTradesResponse
+};
+
+pub async fn get_recent_trades(
+    State(state):State<Arc<TradingEngine>>,
+    Json(payload):Json<RecentTradesRequest>,
+)->(StatusCode,Json<RecentTradesResponse>){
+    let limit = payload.limit.unwrap_or(DEFAULT_TRADE_QUERY_LIMIT);
+    let result = state.get_recent_trades_for_market(payload.trading_pair, limit).await;
+    match result{
+        Ok(r)=>{
+            let response = RecentTradesResponse::new(Some(r), None);
+            (StatusCode::OK,Json(response))
+        },
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(RecentTradesResponse::new(None, Some(error))))
+        }
+    }
+}
+
+pub async fn get_trade_history(
+    State(state):State<Arc<TradingEngine>>,
+    Json(payload):Json<TradeHistoryRequest>,
+)->(StatusCode,Json<TradeHistoryResp>){
+    let result = state.get_trade_history_for_market(payload.trading_pair, payload.query).await;
+    match result{
+        Ok(r)=>{
+            let response = TradeHistoryResp::new(Some(r), None);
+            (StatusCode::OK,Json(response))
+        },
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(TradeHistoryResp::new(None, Some(error))))
+        }
+    }
+}
+
+pub async fn get_user_trades(
+    State(state):State<Arc<TradingEngine>>,
+    Extension(user):Extension<AuthenticatedUser>,
+    Json(payload):Json<UserTradesRequest>,
+)->(StatusCode,Json<UserTradesResponse>){
+    let result = state.get_user_trades_for_market(payload.trading_pair, user.user_id, payload.query).await;
+    match result{
+        Ok(r)=>{
+            let response = UserTradesResponse::new(Some(r), None);
+            (StatusCode::OK,Json(response))
+        },
+        Err(e)=>{
+            let error = ApiError::from(e);
+            (error.status(),Json(UserTradesResponse::new(None, Some(error))))
+        }
+    }
+}
\ No newline at end of file
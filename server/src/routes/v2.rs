@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use  axum::{
+    extract::State, http::StatusCode, Extension, Json
+};
+use orderbook::{DeleteResponse, LimitOrder, OpenOrder, DEFAULT_TRADE_QUERY_LIMIT};
+use trading_engine::{TradingEngine, TradingEngineError};
+
+use crate::auth::AuthenticatedUser;
+use crate::types::error::ErrorResponse;
+use crate::types::v2::{
+    ApiJson,
+    ApiPath,
+    ApiQuery,
+    DepthQuery,
+    Market,
+    MarketDepth,
+    MarketList,
+    MidPrice,
+    OrderList,
+    OrderResource,
+    TradeList,
+    TradesQuery
+};
+
+pub async fn get_markets(
+    State(state):State<Arc<TradingEngine>>
+)->Json<MarketList>{
+    let markets = state.get_markets().await;
+    Json(MarketList::new(markets.markets))
+}
+
+pub async fn get_market_depth(
+    State(state):State<Arc<TradingEngine>>,
+    ApiPath(Market(trading_pair)):ApiPath<Market>,
+    ApiQuery(query):ApiQuery<DepthQuery>
+)->Result<Json<MarketDepth>,ErrorResponse>{
+    let snapshot = state.snapshot(&trading_pair)?;
+    let depth = snapshot.depth(query.levels.map(|levels|levels.get()));
+    Ok(Json(MarketDepth::new(depth, snapshot.staleness)))
+}
+
+pub async fn get_market_mid_price(
+    State(state):State<Arc<TradingEngine>>,
+    ApiPath(Market(trading_pair)):ApiPath<Market>
+)->Result<Json<MidPrice>,ErrorResponse>{
+    let snapshot = state.snapshot(&trading_pair)?;
+    Ok(Json(MidPrice::new(snapshot.mid_price, snapshot.staleness)))
+}
+
+pub async fn get_recent_trades(
+    State(state):State<Arc<TradingEngine>>,
+    ApiPath(Market(trading_pair)):ApiPath<Market>,
+    ApiQuery(query):ApiQuery<TradesQuery>
+)->Result<Json<TradeList>,ErrorResponse>{
+    let limit = query.limit.unwrap_or(DEFAULT_TRADE_QUERY_LIMIT);
+    let trades = state.get_recent_trades_for_market(trading_pair, limit).await?;
+    Ok(Json(TradeList::new(trades)))
+}
+
+pub async fn get_open_orders(
+    State(state):State<Arc<TradingEngine>>,
+    Extension(user):Extension<AuthenticatedUser>,
+    ApiPath(Market(trading_pair)):ApiPath<Market>
+)->Result<Json<OrderList>,ErrorResponse>{
+    let orders = state.get_open_orders_for_market(trading_pair, user.user_id).await?;
+    Ok(Json(OrderList::new(orders)))
+}
+
+pub async fn create_order(
+    State(state):State<Arc<TradingEngine>>,
+    Extension(user):Extension<AuthenticatedUser>,
+    ApiPath(Market(trading_pair)):ApiPath<Market>,
+    ApiJson(mut order):ApiJson<LimitOrder>
+)->Result<(StatusCode,Json<OpenOrder>),ErrorResponse>{
+    //an order the book would reject is turned away with the reason before it gets an order id: not positive,
+    //off the market's ticks or lots, or past a price band that rejects
+    order.user_id=user.user_id;
+    let order = state.place_limit_order_into_market(trading_pair, order).await?;
+    Ok((StatusCode::CREATED,Json(order)))
+}
+
+pub async fn get_order(
+    State(state):State<Arc<TradingEngine>>,
+    Extension(user):Extension<AuthenticatedUser>,
+    ApiPath((Market(trading_pair),order_id)):ApiPath<(Market,u64)>
+)->Result<Json<OrderResource>,ErrorResponse>{
+    //read from the market's snapshot, so an order just placed may take up to the reported staleness to show
+    let snapshot = state.snapshot(&trading_pair)?;
+    //another user's order is reported as missing, as when cancelling it
+    let order = snapshot.get_order(order_id)?;
+    if order.user_id!=user.user_id{
+        return Err(TradingEngineError::OrderDoesNotExist.into())
+    }
+    Ok(Json(OrderResource::new(order, snapshot.staleness)))
+}
+
+pub async fn delete_order(
+    State(state):State<Arc<TradingEngine>>,
+    Extension(user):Extension<AuthenticatedUser>,
+    ApiPath((Market(trading_pair),order_id)):ApiPath<(Market,u64)>
+)->Result<Json<DeleteResponse>,ErrorResponse>{
+    let cancelled = state.delete_user_order_for_market(trading_pair, user.user_id, order_id).await?;
+    Ok(Json(cancelled))
+}
+
+#[cfg(test)]
+mod tests{
+    use axum::{body::{to_bytes, Body}, extract::Request, routing::{delete, get, post}, Router};
+    use orderbook::{BandAction, MarketOrder, PriceBandConfig, ReferencePrice, Side};
+    use rust_decimal::dec;
+    use tower::ServiceExt;
+    use trading_engine::{MatchingMode, NumericMode, TradingPair};
+
+    use super::*;
+    use crate::auth::Role;
+
+    async fn send(state:Arc<TradingEngine>,method:&str,uri:&str,body:&str)->(StatusCode,String){
+        let router=Router::new()
+            .route("/api/v2/markets/{market}/depth", get(get_market_depth))
+            .route("/api/v2/markets/{market}/orders", post(create_order))
+            .route("/api/v2/markets/{market}/orders/{order_id}", delete(delete_order))
+            .with_state(state);
+        let mut request=Request::builder().method(method).uri(uri).header("content-type", "application/json").body(Body::from(body.to_string())).unwrap();
+        request.extensions_mut().insert(AuthenticatedUser{api_key:"k1".to_string(),user_id:1,role:Role::Trade,markets:None});
+        let response=router.oneshot(request).await.unwrap();
+        let status=response.status();
+        let body=to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status,String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    fn engine()->Arc<TradingEngine>{
+        let engine=Arc::new(TradingEngine::new());
+        let _=engine.create_market(TradingPair::new("BTC".to_string(), "USD".to_string()));
+        engine
+    }
+
+    #[tokio::test]
+    async fn test_unknown_market_or_order_is_404(){
+        let engine=engine();
+        assert_eq!(send(engine.clone(), "GET", "/api/v2/markets/ETH-USD/depth", "").await,(StatusCode::NOT_FOUND,"{\"error\":\"TradingPairDoesNotExist\"}".to_string()));
+        assert_eq!(send(engine, "DELETE", "/api/v2/markets/BTC-USD/orders/7", "").await,(StatusCode::NOT_FOUND,"{\"error\":\"OrderDoesNotExist\"}".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_order_in_the_wrong_state_is_409(){
+        let engine=engine();
+        let trading_pair=TradingPair::new("BTC".to_string(), "USD".to_string());
+        let ask=engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Asks,user_id:1,client_order_id:None}).await.unwrap();
+        let _=engine.add_market_order_into_market(trading_pair, MarketOrder::new(dec!(1), Side::Bids, 2)).await;
+        let uri=format!("/api/v2/markets/BTC-USD/orders/{}",ask.order_id);
+        assert_eq!(send(engine, "DELETE", &uri, "").await,(StatusCode::CONFLICT,"{\"error\":\"OrderAlreadyMatched\"}".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_order_the_book_would_reject_is_422(){
+        let engine=Arc::new(TradingEngine::new());
+        let trading_pair=TradingPair::new("BTC".to_string(), "USD".to_string());
+        let _=engine.create_market_with_modes(trading_pair.clone(), MatchingMode::Continuous, NumericMode::FixedPoint{tick_size:dec!(0.5),lot_size:dec!(0.1)});
+        let order=|price:&str,quantity:&str|format!("{{\"price\":\"{price}\",\"quantity\":\"{quantity}\",\"side\":\"Bids\",\"user_id\":1}}");
+        assert_eq!(send(engine.clone(), "POST", "/api/v2/markets/BTC-USD/orders", &order("100.25","1")).await,(StatusCode::UNPROCESSABLE_ENTITY,"{\"error\":\"InvalidPrice\"}".to_string()));
+        assert_eq!(send(engine.clone(), "POST", "/api/v2/markets/BTC-USD/orders", &order("100","0.05")).await,(StatusCode::UNPROCESSABLE_ENTITY,"{\"error\":\"InvalidQuantity\"}".to_string()));
+
+        //a band 10% either side of the last trade, at 100
+        let band=PriceBandConfig{width_bps:1000,reference:ReferencePrice::LastTrade,action:BandAction::Reject};
+        let _=engine.on_market(&trading_pair, move|market|market.orderbook.set_price_band(Some(band))).await;
+        let _=engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Asks,user_id:2,client_order_id:None}).await;
+        let _=engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Bids,user_id:3,client_order_id:None}).await;
+        assert_eq!(send(engine.clone(), "POST", "/api/v2/markets/BTC-USD/orders", &order("120","1")).await,(StatusCode::UNPROCESSABLE_ENTITY,"{\"error\":\"PriceBandExceeded\"}".to_string()));
+        //none of them took an order id, the next order follows the two that traded
+        let (status,body)=send(engine, "POST", "/api/v2/markets/BTC-USD/orders", &order("100","1")).await;
+        assert_eq!(status,StatusCode::CREATED);
+        assert!(body.contains("\"order_id\":3,"),"{body}");
+    }
+
+    #[tokio::test]
+    async fn test_request_that_can_never_succeed_is_422(){
+        let engine=engine();
+        let (status,body)=send(engine.clone(), "GET", "/api/v2/markets/BTCUSD/depth", "").await;
+        assert_eq!(status,StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(body.starts_with("{\"error\":\"InvalidRequest\",\"message\":"));
+        let order="{\"price\":\"0\",\"quantity\":\"1\",\"side\":\"Bids\",\"user_id\":1}";
+        assert_eq!(send(engine.clone(), "POST", "/api/v2/markets/BTC-USD/orders", order).await,(StatusCode::UNPROCESSABLE_ENTITY,"{\"error\":\"InvalidPrice\"}".to_string()));
+        let (status,_)=send(engine, "POST", "/api/v2/markets/BTC-USD/orders", "{\"price\":").await;
+        assert_eq!(status,StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+
+#[derive(Debug,PartialEq,Serialize,Deserialize)]
+pub enum AuthError{
+    MissingApiKey,
+    MissingSignature,
+    InvalidApiKey,
+    InvalidSignature,
+    TimestampOutsideWindow,
+    NonceReused,
+    BodyTooLarge,
+    InsufficientRole,
+    MarketNotInScope
+}
\ No newline at end of file
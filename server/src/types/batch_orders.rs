@@ -0,0 +1,26 @@
+use orderbook::{BatchOperation, BatchResponse};
+use serde::{Deserialize, Serialize};
+use trading_engine::{TradingPair};
+
+use crate::types::error::ApiError;
+
+
+#[derive(Serialize,Deserialize)]
+pub struct BatchOrdersRequest{
+    pub trading_pair: TradingPair,
+    pub operations: Vec<BatchOperation>,
+    #[serde(default)]
+    pub all_or_nothing: bool
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct BatchOrdersResponse{
+    response: Option<BatchResponse>,
+    error: Option<ApiError>
+}
+
+impl BatchOrdersResponse{
+    pub fn new(response:Option<BatchResponse>,error:Option<ApiError>)->BatchOrdersResponse{
+        BatchOrdersResponse { response, error }
+    }
+}
\ No newline at end of file
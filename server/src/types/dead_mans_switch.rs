@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::error::ApiError;
+
+
+#[derive(Debug,PartialEq,Serialize,Deserialize)]
+pub enum DeadMansSwitchError{
+    NotArmed,
+    TimeoutTooShort
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct ArmDeadMansSwitchRequest{
+    //0 disarms the switch
+    pub timeout_ms: u64
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct DeadMansSwitchResponse{
+    user_id: u64,
+    armed: bool,
+    //unix milliseconds after which every open order of the user is cancelled
+    expires_at: Option<u64>,
+    error: Option<ApiError>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct SessionParams{
+    #[serde(default)]
+    pub cancel_on_disconnect: bool
+}
+
+impl DeadMansSwitchResponse{
+    pub fn new(user_id:u64,expires_at:Option<u64>,error:Option<ApiError>)->DeadMansSwitchResponse{
+        DeadMansSwitchResponse { user_id, armed:expires_at.is_some(), expires_at, error }
+    }
+}
\ No newline at end of file
@@ -1,7 +1,9 @@
 use orderbook::Depth;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use trading_engine::{TradingEngineError, TradingPair};
+use trading_engine::{Staleness, TradingPair};
+
+use crate::types::error::ApiError;
 
 
 #[derive(Serialize,Deserialize)]
@@ -12,7 +14,9 @@ pub struct MarketDepthRequest{
 #[derive(Serialize,Deserialize)]
 pub struct MarketDepthResponse{
     depth:Option<Depth>,
-    error:Option<TradingEngineError>
+    error:Option<ApiError>,
+    //depth is read from the market's latest snapshot
+    staleness:Option<Staleness>
 }
 
 #[derive(Serialize,Deserialize)]
@@ -23,17 +27,18 @@ pub struct MarketMidPriceRequest{
 #[derive(Serialize,Deserialize)]
 pub struct MarketMidPriceResponse{
     price:Option<Decimal>,
-    error:Option<TradingEngineError>
+    error:Option<ApiError>,
+    staleness:Option<Staleness>
 }
 
 impl MarketDepthResponse {
-    pub fn new(depth:Option<Depth>,error:Option<TradingEngineError>)->MarketDepthResponse{
-        MarketDepthResponse { depth, error}
+    pub fn new(depth:Option<Depth>,error:Option<ApiError>,staleness:Option<Staleness>)->MarketDepthResponse{
+        MarketDepthResponse { depth, error, staleness }
     }
 }
 
 impl MarketMidPriceResponse{
-    pub fn new(price:Option<Decimal>,error:Option<TradingEngineError>)->MarketMidPriceResponse{
-        MarketMidPriceResponse { price, error }
+    pub fn new(price:Option<Decimal>,error:Option<ApiError>,staleness:Option<Staleness>)->MarketMidPriceResponse{
+        MarketMidPriceResponse { price, error, staleness }
     }
 }
\ No newline at end of file
@@ -0,0 +1,193 @@
+use axum::{
+    extract::rejection::{JsonRejection, PathRejection, QueryRejection}, http::StatusCode, response::{IntoResponse, Response}, Json
+};
+use serde::{Deserialize, Serialize};
+use trading_engine::TradingEngineError;
+
+use crate::types::auth::AuthError;
+use crate::types::dead_mans_switch::DeadMansSwitchError;
+use crate::types::rate_limit::RateLimitError;
+
+//every error the api answers with, serialized as the bare name of the error whichever layer raised it
+#[derive(Debug,PartialEq,Serialize,Deserialize)]
+pub enum ApiError{
+    //a handler panicked, the request was dropped and the server carries on
+    Internal,
+    //a path, query or body the server could not read
+    InvalidRequest,
+    //the book's errors come back as the engine's, so everything below the server is this one flat enum
+    #[serde(untagged)]
+    Engine(TradingEngineError),
+    #[serde(untagged)]
+    Auth(AuthError),
+    #[serde(untagged)]
+    RateLimit(RateLimitError),
+    #[serde(untagged)]
+    DeadMansSwitch(DeadMansSwitchError)
+}
+
+//body of every failed request that has no response of its own to carry the error
+#[derive(Serialize,Deserialize)]
+pub struct ErrorResponse{
+    error: ApiError,
+    //what was wrong with an invalid request
+    #[serde(default,skip_serializing_if="Option::is_none")]
+    message: Option<String>
+}
+
+impl ApiError{
+    //404 for a market or order that is not there, 409 for one in the wrong state and 422 for a request that can never succeed
+    pub fn status(&self)->StatusCode{
+        match self{
+            ApiError::Internal=>StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::InvalidRequest=>StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::Engine(error)=>match error{
+                TradingEngineError::TradingPairDoesNotExist
+                |TradingEngineError::OrderDoesNotExist
+                |TradingEngineError::LimitOrderDoesNotExist=>StatusCode::NOT_FOUND,
+                TradingEngineError::TradingPairAlreadyExists
+                |TradingEngineError::MarketNotOpen
+                |TradingEngineError::MarketCancelOnly
+                |TradingEngineError::MarketHalted
+                |TradingEngineError::MarketDelisted
+                |TradingEngineError::OrderAlreadyMatched
+                |TradingEngineError::OrderAlreadyClosed
+                |TradingEngineError::AuctionInProgress=>StatusCode::CONFLICT,
+                TradingEngineError::InvalidBatchInterval
+                |TradingEngineError::InvalidTickOrLotSize
+                |TradingEngineError::ModifyQuantityCannotBeLesserThanFilledQuantity
+                |TradingEngineError::BatchTooLarge
+                |TradingEngineError::PriceBandExceeded
+                |TradingEngineError::ProtectionPriceExceeded
+                |TradingEngineError::QuoteQuantityOnlyForBuys
+                |TradingEngineError::InvalidQuantity
+                |TradingEngineError::InvalidPrice=>StatusCode::UNPROCESSABLE_ENTITY,
+                TradingEngineError::MarketUnavailable=>StatusCode::SERVICE_UNAVAILABLE
+            },
+            ApiError::Auth(error)=>match error{
+                AuthError::BodyTooLarge=>StatusCode::PAYLOAD_TOO_LARGE,
+                AuthError::InsufficientRole|AuthError::MarketNotInScope=>StatusCode::FORBIDDEN,
+                _=>StatusCode::UNAUTHORIZED
+            },
+            ApiError::RateLimit(_)=>StatusCode::TOO_MANY_REQUESTS,
+            ApiError::DeadMansSwitch(error)=>match error{
+                DeadMansSwitchError::NotArmed=>StatusCode::CONFLICT,
+                DeadMansSwitchError::TimeoutTooShort=>StatusCode::UNPROCESSABLE_ENTITY
+            }
+        }
+    }
+}
+
+impl ErrorResponse{
+    pub fn new(error:impl Into<ApiError>)->ErrorResponse{
+        ErrorResponse { error:error.into(), message:None }
+    }
+
+    pub fn invalid_request(message:String)->ErrorResponse{
+        ErrorResponse { error:ApiError::InvalidRequest, message:Some(message) }
+    }
+}
+
+//answers with the status the error maps to
+impl IntoResponse for ErrorResponse{
+    fn into_response(self)->Response{
+        (self.error.status(),Json(self)).into_response()
+    }
+}
+
+impl From<TradingEngineError> for ErrorResponse{
+    fn from(error:TradingEngineError)->Self{
+        ErrorResponse::new(error)
+    }
+}
+
+impl From<PathRejection> for ErrorResponse{
+    fn from(rejection:PathRejection)->Self{
+        ErrorResponse::invalid_request(rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for ErrorResponse{
+    fn from(rejection:QueryRejection)->Self{
+        ErrorResponse::invalid_request(rejection.body_text())
+    }
+}
+
+impl From<JsonRejection> for ErrorResponse{
+    fn from(rejection:JsonRejection)->Self{
+        ErrorResponse::invalid_request(rejection.body_text())
+    }
+}
+
+impl From<TradingEngineError> for ApiError{
+    fn from(error:TradingEngineError)->Self{
+        ApiError::Engine(error)
+    }
+}
+
+impl From<AuthError> for ApiError{
+    fn from(error:AuthError)->Self{
+        ApiError::Auth(error)
+    }
+}
+
+impl From<RateLimitError> for ApiError{
+    fn from(error:RateLimitError)->Self{
+        ApiError::RateLimit(error)
+    }
+}
+
+impl From<DeadMansSwitchError> for ApiError{
+    fn from(error:DeadMansSwitchError)->Self{
+        ApiError::DeadMansSwitch(error)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use axum::body::to_bytes;
+
+    use super::*;
+
+    #[test]
+    fn test_missing_things_are_404_wrong_state_is_409_and_bad_requests_are_422(){
+        let cases=[
+            (ApiError::from(TradingEngineError::TradingPairDoesNotExist),StatusCode::NOT_FOUND),
+            (ApiError::from(TradingEngineError::OrderDoesNotExist),StatusCode::NOT_FOUND),
+            (ApiError::from(TradingEngineError::LimitOrderDoesNotExist),StatusCode::NOT_FOUND),
+            (ApiError::from(TradingEngineError::TradingPairAlreadyExists),StatusCode::CONFLICT),
+            (ApiError::from(TradingEngineError::MarketHalted),StatusCode::CONFLICT),
+            (ApiError::from(TradingEngineError::OrderAlreadyMatched),StatusCode::CONFLICT),
+            (ApiError::from(TradingEngineError::AuctionInProgress),StatusCode::CONFLICT),
+            (ApiError::from(DeadMansSwitchError::NotArmed),StatusCode::CONFLICT),
+            (ApiError::InvalidRequest,StatusCode::UNPROCESSABLE_ENTITY),
+            (ApiError::from(TradingEngineError::InvalidPrice),StatusCode::UNPROCESSABLE_ENTITY),
+            (ApiError::from(TradingEngineError::PriceBandExceeded),StatusCode::UNPROCESSABLE_ENTITY),
+            (ApiError::from(TradingEngineError::InvalidBatchInterval),StatusCode::UNPROCESSABLE_ENTITY),
+            (ApiError::from(TradingEngineError::InvalidTickOrLotSize),StatusCode::UNPROCESSABLE_ENTITY),
+            (ApiError::from(TradingEngineError::MarketUnavailable),StatusCode::SERVICE_UNAVAILABLE),
+            (ApiError::from(AuthError::InvalidSignature),StatusCode::UNAUTHORIZED),
+            (ApiError::from(AuthError::MarketNotInScope),StatusCode::FORBIDDEN),
+            (ApiError::from(AuthError::BodyTooLarge),StatusCode::PAYLOAD_TOO_LARGE),
+            (ApiError::from(RateLimitError::TooManyOrders),StatusCode::TOO_MANY_REQUESTS),
+            (ApiError::Internal,StatusCode::INTERNAL_SERVER_ERROR)
+        ];
+        for (error,status) in cases{
+            assert_eq!(error.status(),status,"{error:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_body_holds_the_bare_name_whichever_layer_raised_it(){
+        for (error,body) in [
+            (ErrorResponse::from(TradingEngineError::OrderAlreadyMatched),"{\"error\":\"OrderAlreadyMatched\"}"),
+            (ErrorResponse::from(TradingEngineError::MarketHalted),"{\"error\":\"MarketHalted\"}"),
+            (ErrorResponse::new(RateLimitError::TooManyRequests),"{\"error\":\"TooManyRequests\"}"),
+            (ErrorResponse::invalid_request("bad market".to_string()),"{\"error\":\"InvalidRequest\",\"message\":\"bad market\"}")
+        ]{
+            let response=error.into_response();
+            let bytes=to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            assert_eq!(String::from_utf8(bytes.to_vec()).unwrap(),body);
+        }
+    }
+}
\ No newline at end of file
@@ -1,6 +1,8 @@
 use orderbook::{LimitOrder, OpenOrder};
 use serde::{Deserialize, Serialize};
-use trading_engine::{TradingEngineError, TradingPair};
+use trading_engine::{TradingPair};
+
+use crate::types::error::ApiError;
 
 
 #[derive(Serialize,Deserialize)]
@@ -12,11 +14,11 @@ pub struct LimitOrderRequest{
 #[derive(Serialize,Deserialize)]
 pub struct LimitOrderResponse{
     open_order: Option<OpenOrder>,
-    error: Option<TradingEngineError>
+    error: Option<ApiError>
 }
 
 impl LimitOrderResponse{
-    pub fn new(o:Option<OpenOrder>,error:Option<TradingEngineError>)->LimitOrderResponse{
-        LimitOrderResponse { open_order: o, error:error }
+    pub fn new(o:Option<OpenOrder>,error:Option<ApiError>)->LimitOrderResponse{
+        LimitOrderResponse { open_order: o, error }
     }
 }
\ No newline at end of file
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::v2::Market;
+
+
+#[derive(Serialize,Deserialize)]
+pub struct MarketDataParams{
+    //`BTC-USD`, every market when left out
+    #[serde(default)]
+    pub market: Option<Market>
+}
+
+//sent in place of the events a client fell too far behind to receive
+#[derive(Serialize,Deserialize)]
+pub struct MarketDataLagged{
+    pub missed: u64
+}
\ No newline at end of file
@@ -1,6 +1,8 @@
 use orderbook::{MarketOrder, MarketOrderResponse as MarketOrderResp};
 use serde::{Deserialize, Serialize};
-use trading_engine::{TradingEngineError, TradingPair};
+use trading_engine::{TradingPair};
+
+use crate::types::error::ApiError;
 
 
 #[derive(Serialize,Deserialize)]
@@ -12,11 +14,11 @@ pub struct MarketOrderRequest{
 #[derive(Serialize,Deserialize)]
 pub struct MarketOrderResponse{
     response:Option<MarketOrderResp>,
-    error: Option<TradingEngineError>
+    error: Option<ApiError>
 }
 
 impl MarketOrderResponse{
-    pub fn new(response:Option<MarketOrderResp>,e:Option<TradingEngineError>)->MarketOrderResponse{
+    pub fn new(response:Option<MarketOrderResp>,e:Option<ApiError>)->MarketOrderResponse{
         MarketOrderResponse { response, error: e }
     }
 }
\ No newline at end of file
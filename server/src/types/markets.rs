@@ -1,15 +1,30 @@
-use trading_engine::{Markets, TradingPair};
+use orderbook::{Allocation, DeleteResponse, FeeSchedule, PriceBandConfig, Uncross};
+use rust_decimal::Decimal;
+use trading_engine::{CircuitBreakerConfig, MarketStatus, MarketStatusChange, Markets, MatchingMode, NumericMode, TradingEngineError, TradingPair};
 use serde::{Deserialize, Serialize};
 
+use crate::types::error::ApiError;
+
 #[derive(Serialize,Deserialize)]
 pub struct CreateMarketRequest{
-    pub trading_pair: TradingPair
+    pub trading_pair: TradingPair,
+    //continuous matching when left out
+    #[serde(default)]
+    pub matching_mode: MatchingMode,
+    //given together the market matches in whole ticks and lots, left out it matches in decimals
+    #[serde(default)]
+    pub tick_size: Option<Decimal>,
+    #[serde(default)]
+    pub lot_size: Option<Decimal>
 }
 
 #[derive(Deserialize,Serialize)]
 pub struct CreateMarketResponse{
     created:bool,
     trading_pair:TradingPair,
+    matching_mode:MatchingMode,
+    numeric_mode:Option<NumericMode>,
+    error:Option<ApiError>
 }
 
 #[derive(Deserialize,Serialize)]
@@ -17,11 +32,106 @@ pub struct GetMarketsResponse{
     markets:Vec<TradingPair>
 }
 
+#[derive(Serialize,Deserialize)]
+pub struct HaltMarketRequest{
+    pub trading_pair: TradingPair
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct HaltMarketResponse{
+    trading_pair: TradingPair,
+    halted: bool,
+    error: Option<ApiError>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct MarketStatusRequest{
+    pub trading_pair: TradingPair
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct SetMarketStatusRequest{
+    pub trading_pair: TradingPair,
+    pub status: MarketStatus
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct MarketStatusResponse{
+    trading_pair: TradingPair,
+    status: Option<MarketStatus>,
+    //orders cancelled by a delisting
+    cancelled: Vec<DeleteResponse>,
+    //how the opening auction cleared
+    uncross: Option<Uncross>,
+    error: Option<ApiError>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct AuctionRequest{
+    pub trading_pair: TradingPair
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct AuctionResponse{
+    trading_pair: TradingPair,
+    //none outside an auction or while the book does not cross
+    indicative: Option<Uncross>,
+    error: Option<ApiError>
+}
+
+//a missing band or breaker switches it off
+#[derive(Serialize,Deserialize)]
+pub struct MarketProtectionRequest{
+    pub trading_pair: TradingPair,
+    pub price_band: Option<PriceBandConfig>,
+    pub circuit_breaker: Option<CircuitBreakerConfig>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct MarketProtectionResponse{
+    trading_pair: TradingPair,
+    price_band: Option<PriceBandConfig>,
+    circuit_breaker: Option<CircuitBreakerConfig>,
+    error: Option<ApiError>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct MarketConfigRequest{
+    pub trading_pair: TradingPair,
+    //each setting is left unchanged when not sent
+    #[serde(default)]
+    pub fee_schedule: Option<FeeSchedule>,
+    #[serde(default)]
+    pub allocation: Option<Allocation>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct MarketConfigResponse{
+    trading_pair: TradingPair,
+    fee_schedule: Option<FeeSchedule>,
+    allocation: Option<Allocation>,
+    error: Option<ApiError>
+}
+
+impl CreateMarketRequest{
+    //a tick size without a lot size, or the other way round, is not a market either way
+    pub fn numeric_mode(&self)->Result<NumericMode,TradingEngineError>{
+        match (self.tick_size,self.lot_size){
+            (None,None)=>Ok(NumericMode::Decimal),
+            (Some(tick_size),Some(lot_size))=>Ok(NumericMode::FixedPoint { tick_size, lot_size }),
+            _=>Err(TradingEngineError::InvalidTickOrLotSize)
+        }
+    }
+}
+
 impl CreateMarketResponse{
-    pub fn new(status:bool,trading_pair:TradingPair)->CreateMarketResponse{
+    pub fn new(status:bool,trading_pair:TradingPair,matching_mode:MatchingMode,numeric_mode:Option<NumericMode>,error:Option<ApiError>)->CreateMarketResponse{
         CreateMarketResponse{
             created:status,
-            trading_pair
+            trading_pair,
+            matching_mode,
+            numeric_mode,
+            error
         }
     }
 }
@@ -30,4 +140,61 @@ impl GetMarketsResponse{
     pub fn new(markets:Markets)->GetMarketsResponse{
         GetMarketsResponse { markets:markets.markets }
     }
+}
+
+impl HaltMarketResponse{
+    pub fn new(trading_pair:TradingPair,halted:bool,error:Option<ApiError>)->HaltMarketResponse{
+        HaltMarketResponse { trading_pair, halted, error }
+    }
+}
+
+impl MarketStatusResponse{
+    pub fn new(trading_pair:TradingPair,status:Option<MarketStatus>,error:Option<ApiError>)->MarketStatusResponse{
+        MarketStatusResponse { trading_pair, status, cancelled:Vec::new(), uncross:None, error }
+    }
+
+    pub fn changed(change:MarketStatusChange)->MarketStatusResponse{
+        MarketStatusResponse { trading_pair:change.trading_pair, status:Some(change.status), cancelled:change.cancelled, uncross:change.uncross, error:None }
+    }
+}
+
+impl AuctionResponse{
+    pub fn new(trading_pair:TradingPair,indicative:Option<Uncross>,error:Option<ApiError>)->AuctionResponse{
+        AuctionResponse { trading_pair, indicative, error }
+    }
+}
+
+impl MarketProtectionResponse{
+    pub fn new(trading_pair:TradingPair,price_band:Option<PriceBandConfig>,circuit_breaker:Option<CircuitBreakerConfig>,error:Option<ApiError>)->MarketProtectionResponse{
+        MarketProtectionResponse { trading_pair, price_band, circuit_breaker, error }
+    }
+}
+
+impl MarketConfigResponse{
+    pub fn new(trading_pair:TradingPair,fee_schedule:Option<FeeSchedule>,allocation:Option<Allocation>,error:Option<ApiError>)->MarketConfigResponse{
+        MarketConfigResponse { trading_pair, fee_schedule, allocation, error }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use rust_decimal::dec;
+
+    use super::*;
+
+    fn request(body:&str)->CreateMarketRequest{
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[test]
+    fn test_tick_and_lot_size_come_together_or_not_at_all(){
+        let pair=r#""trading_pair":{"base":"BTC","quote":"USD"}"#;
+        assert_eq!(request(&format!("{{{pair}}}")).numeric_mode(),Ok(NumericMode::Decimal));
+        assert_eq!(
+            request(&format!(r#"{{{pair},"tick_size":"0.01","lot_size":"0.001"}}"#)).numeric_mode(),
+            Ok(NumericMode::FixedPoint { tick_size:dec!(0.01), lot_size:dec!(0.001) })
+        );
+        assert_eq!(request(&format!(r#"{{{pair},"tick_size":"0.01"}}"#)).numeric_mode(),Err(TradingEngineError::InvalidTickOrLotSize));
+        assert_eq!(request(&format!(r#"{{{pair},"lot_size":"0.001"}}"#)).numeric_mode(),Err(TradingEngineError::InvalidTickOrLotSize));
+    }
 }
\ No newline at end of file
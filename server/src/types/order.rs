@@ -1,20 +1,30 @@
-use orderbook::{CustomError, DeleteResponse,ModifyOrderRequest as OrderBookModifyOrderRequest,ModifyOrderResponse as OrderBookModifyOrderResponse, OpenOrder};
+use orderbook::{CancelFilter, DeleteResponse,ModifyOrderRequest as OrderBookModifyOrderRequest,ModifyOrderResponse as OrderBookModifyOrderResponse, OpenOrder};
 use serde::{Deserialize, Serialize};
-use trading_engine::{TradingEngineError, TradingPair};
+use trading_engine::{MarketCancellations, Staleness, TradingPair};
 
+use crate::types::error::ApiError;
 
 
+
+//an order is named either by its order id or by the client order id the caller placed it with
+#[derive(Serialize,Deserialize)]
+#[serde(untagged)]
+pub enum OrderReference{
+    OrderId{order_id:u64},
+    ClientOrderId{client_order_id:String}
+}
+
 #[derive(Serialize,Deserialize)]
 pub struct DeleteOrderRequest{
     pub trading_pair:TradingPair,
-    pub order_id: u64
+    #[serde(flatten)]
+    pub order: OrderReference
 }
 
 #[derive(Serialize,Deserialize)]
 pub struct DeleteOrderResponse{
     response: Option<DeleteResponse>,
-    engine_error: Option<TradingEngineError>,
-    orderbook_error:Option<CustomError>
+    error: Option<ApiError>
 }
 
 #[derive(Serialize,Deserialize)]
@@ -26,37 +36,81 @@ pub struct ModifyOrderRequest{
 #[derive(Serialize,Deserialize)]
 pub struct ModifyOrderResponse{
     response:Option<OrderBookModifyOrderResponse>,
-    engine_error: Option<TradingEngineError>,
-    orderbook_error:Option<CustomError>
+    error: Option<ApiError>
 }
 
 #[derive(Serialize,Deserialize)]
 pub struct GetOrderRequest{
     pub trading_pair:TradingPair,
-    pub order_id:u64
+    #[serde(flatten)]
+    pub order:OrderReference
 }
 
 #[derive(Serialize,Deserialize)]
 pub struct GetOrderResponse{
     response: Option<OpenOrder>,
-    engine_error: Option<TradingEngineError>,
-    orderbook_error:Option<CustomError>
+    error: Option<ApiError>,
+    //the order is read from the market's latest snapshot
+    staleness:Option<Staleness>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct OpenOrdersRequest{
+    pub trading_pair:TradingPair
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct OrderHistoryRequest{
+    pub trading_pair:TradingPair,
+    pub limit:Option<usize>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct OrderListResponse{
+    orders: Option<Vec<OpenOrder>>,
+    error: Option<ApiError>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct CancelAllRequest{
+    //cancels across every market when not set
+    pub trading_pair:Option<TradingPair>,
+    #[serde(default)]
+    pub filter:CancelFilter
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct CancelAllResponse{
+    response: Option<Vec<MarketCancellations>>,
+    error: Option<ApiError>
 }
 
 impl DeleteOrderResponse{
-    pub fn new(response:Option<DeleteResponse>,engine_error:Option<TradingEngineError>,orderbook_error:Option<CustomError>)->DeleteOrderResponse{
-        DeleteOrderResponse{ response,engine_error,orderbook_error }
+    pub fn new(response:Option<DeleteResponse>,error:Option<ApiError>)->DeleteOrderResponse{
+        DeleteOrderResponse{ response,error }
     }
 }
 
 impl ModifyOrderResponse{
-    pub fn new(response:Option<OrderBookModifyOrderResponse>,engine_error: Option<TradingEngineError>,orderbook_error:Option<CustomError>)->ModifyOrderResponse{
-        ModifyOrderResponse {response, engine_error, orderbook_error }
+    pub fn new(response:Option<OrderBookModifyOrderResponse>,error: Option<ApiError>)->ModifyOrderResponse{
+        ModifyOrderResponse {response, error }
     }
 }
 
 impl GetOrderResponse{
-    pub fn new(response:Option<OpenOrder>,engine_error: Option<TradingEngineError>,orderbook_error:Option<CustomError>)->GetOrderResponse{
-        GetOrderResponse { response, engine_error, orderbook_error }
+    pub fn new(response:Option<OpenOrder>,error: Option<ApiError>,staleness:Option<Staleness>)->GetOrderResponse{
+        GetOrderResponse { response, error, staleness }
+    }
+}
+
+impl OrderListResponse{
+    pub fn new(orders:Option<Vec<OpenOrder>>,error:Option<ApiError>)->OrderListResponse{
+        OrderListResponse { orders, error }
+    }
+}
+
+impl CancelAllResponse{
+    pub fn new(response:Option<Vec<MarketCancellations>>,error:Option<ApiError>)->CancelAllResponse{
+        CancelAllResponse { response, error }
     }
 }
\ No newline at end of file
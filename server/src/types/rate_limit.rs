@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+
+#[derive(Debug,PartialEq,Serialize,Deserialize)]
+pub enum RateLimitError{
+    TooManyRequests,
+    TooManyOrders,
+    //a batch places more orders than the order bucket can ever hold
+    BatchLargerThanOrderBurst
+}
\ No newline at end of file
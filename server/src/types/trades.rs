@@ -0,0 +1,62 @@
+use orderbook::{PublicTrade, TradeHistoryQuery, TradeHistoryResponse, UserTrade};
+use serde::{Deserialize, Serialize};
+use trading_engine::{TradingPair};
+
+use crate::types::error::ApiError;
+
+
+#[derive(Serialize,Deserialize)]
+pub struct RecentTradesRequest{
+    pub trading_pair: TradingPair,
+    pub limit: Option<usize>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct RecentTradesResponse{
+    trades: Option<Vec<PublicTrade>>,
+    error: Option<ApiError>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct TradeHistoryRequest{
+    pub trading_pair: TradingPair,
+    #[serde(default)]
+    pub query: TradeHistoryQuery
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct TradeHistoryResp{
+    response: Option<TradeHistoryResponse<PublicTrade>>,
+    error: Option<ApiError>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct UserTradesRequest{
+    pub trading_pair: TradingPair,
+    #[serde(default)]
+    pub query: TradeHistoryQuery
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct UserTradesResponse{
+    response: Option<TradeHistoryResponse<UserTrade>>,
+    error: Option<ApiError>
+}
+
+impl RecentTradesResponse{
+    pub fn new(trades:Option<Vec<PublicTrade>>,error:Option<ApiError>)->RecentTradesResponse{
+        RecentTradesResponse { trades, error }
+    }
+}
+
+impl TradeHistoryResp{
+    pub fn new(response:Option<TradeHistoryResponse<PublicTrade>>,error:Option<ApiError>)->TradeHistoryResp{
+        TradeHistoryResp { response, error }
+    }
+}
+
+impl UserTradesResponse{
+    pub fn new(response:Option<TradeHistoryResponse<UserTrade>>,error:Option<ApiError>)->UserTradesResponse{
+        UserTradesResponse { response, error }
+    }
+}
\ No newline at end of file
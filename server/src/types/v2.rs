@@ -0,0 +1,133 @@
+use std::{fmt, num::NonZeroUsize};
+
+use axum::extract::{FromRequest, FromRequestParts};
+use orderbook::{Depth, OpenOrder, PublicTrade};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use trading_engine::{Staleness, TradingPair};
+
+use crate::types::error::ErrorResponse;
+
+//path, query and body extractors whose rejections answer with the api's error body
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Path), rejection(ErrorResponse))]
+pub struct ApiPath<T>(pub T);
+
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(ErrorResponse))]
+pub struct ApiQuery<T>(pub T);
+
+#[derive(FromRequest)]
+#[from_request(via(axum::Json), rejection(ErrorResponse))]
+pub struct ApiJson<T>(pub T);
+
+//a market as it appears in a path, `BTC-USD`
+#[derive(Clone,Debug,PartialEq,Serialize,Deserialize)]
+#[serde(try_from="String",into="String")]
+pub struct Market(pub TradingPair);
+
+impl TryFrom<String> for Market{
+    type Error=String;
+
+    fn try_from(market:String)->Result<Self,Self::Error>{
+        match market.split_once('-'){
+            Some((base,quote)) if !base.is_empty() && !quote.is_empty()=>Ok(Market(TradingPair::new(base.to_string(), quote.to_string()))),
+            _=>Err(format!("market `{market}` is not written as BASE-QUOTE"))
+        }
+    }
+}
+
+impl fmt::Display for Market{
+    fn fmt(&self,f:&mut fmt::Formatter<'_>)->fmt::Result{
+        write!(f, "{}-{}", self.0.base, self.0.quote)
+    }
+}
+
+impl From<Market> for String{
+    fn from(market:Market)->Self{
+        market.to_string()
+    }
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct MarketList{
+    markets: Vec<Market>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct DepthQuery{
+    //every level when left out
+    pub levels: Option<NonZeroUsize>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct MarketDepth{
+    #[serde(flatten)]
+    depth: Depth,
+    staleness: Staleness
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct MidPrice{
+    price: Option<Decimal>,
+    staleness: Staleness
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct TradesQuery{
+    pub limit: Option<usize>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct TradeList{
+    trades: Vec<PublicTrade>
+}
+
+#[derive(Serialize,Deserialize)]
+pub struct OrderList{
+    orders: Vec<OpenOrder>
+}
+
+//an order read from the market's latest snapshot
+#[derive(Serialize,Deserialize)]
+pub struct OrderResource{
+    #[serde(flatten)]
+    order: OpenOrder,
+    staleness: Staleness
+}
+
+impl MarketList{
+    pub fn new(markets:Vec<TradingPair>)->MarketList{
+        MarketList { markets:markets.into_iter().map(Market).collect() }
+    }
+}
+
+impl MarketDepth{
+    pub fn new(depth:Depth,staleness:Staleness)->MarketDepth{
+        MarketDepth { depth, staleness }
+    }
+}
+
+impl MidPrice{
+    pub fn new(price:Option<Decimal>,staleness:Staleness)->MidPrice{
+        MidPrice { price, staleness }
+    }
+}
+
+impl TradeList{
+    pub fn new(trades:Vec<PublicTrade>)->TradeList{
+        TradeList { trades }
+    }
+}
+
+impl OrderList{
+    pub fn new(orders:Vec<OpenOrder>)->OrderList{
+        OrderList { orders }
+    }
+}
+
+impl OrderResource{
+    pub fn new(order:OpenOrder,staleness:Staleness)->OrderResource{
+        OrderResource { order, staleness }
+    }
+}
\ No newline at end of file
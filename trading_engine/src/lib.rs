@@ -4,7 +4,25 @@ mod trading_engine;
 
 pub use trading_engine::types::{
     TradingEngine,
+    EngineMode,
+    Market,
     TradingPair,
     TradingEngineError,
-    Markets
-};
\ No newline at end of file
+    Markets,
+    MarketStatus,
+    MarketStatusChange,
+    CircuitBreaker,
+    CircuitBreakerConfig,
+    MarketCancellations,
+    MatchingMode,
+    NumericMode,
+    MarketBook,
+    BatchAuction,
+    BatchClearing,
+    SequencedEvent,
+    EngineEvent,
+    BookSnapshot,
+    Staleness
+};
+pub use trading_engine::ring::RingSubscriber;
+pub use trading_engine::snapshot::MAX_SNAPSHOT_STALENESS;
\ No newline at end of file
@@ -0,0 +1,24 @@
+use orderbook::{BatchOperation, BatchResult, LimitOrder, Side};
+use rust_decimal::dec;
+
+use crate::trading_engine::types::{TradingEngine, TradingEngineError, TradingPair};
+
+#[tokio::test]
+async fn test_apply_batch_for_market(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market(trading_pair.clone());
+    let operations = (0..20).map(|i|BatchOperation::Place(LimitOrder{price:dec!(100)-rust_decimal::Decimal::from(i),quantity:dec!(1),side:Side::Bids,user_id:1,client_order_id:None})).collect();
+    let response = engine.apply_batch_for_market(trading_pair.clone(), operations, true).await.unwrap();
+    assert!(response.success);
+    assert!(response.results.iter().all(|r|matches!(r,BatchResult::Placed(_))));
+    assert_eq!(engine.get_market_depth(trading_pair).await.unwrap().bids.len(),20);
+}
+
+#[tokio::test]
+async fn test_apply_batch_for_non_existent_market(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let result = engine.apply_batch_for_market(trading_pair, Vec::new(), false).await;
+    assert_eq!(result,Err(TradingEngineError::TradingPairDoesNotExist));
+}
\ No newline at end of file
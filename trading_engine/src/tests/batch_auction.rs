@@ -0,0 +1,58 @@
+use orderbook::{LimitOrder, Side};
+use rust_decimal::{dec, Decimal};
+
+use crate::trading_engine::types::{MarketStatus, MatchingMode, TradingEngine, TradingEngineError, TradingPair};
+
+fn limit_order(price:Decimal,quantity:Decimal,side:Side,user_id:u64)->LimitOrder{
+    LimitOrder{price,quantity,side,user_id,client_order_id:None}
+}
+
+#[tokio::test]
+async fn test_batch_auction_market_clears_every_interval(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    assert_eq!(engine.create_market_with_matching_mode(trading_pair.clone(), MatchingMode::BatchAuction{interval_ms:100}),Ok(()));
+    assert_eq!(engine.get_matching_mode(&trading_pair),Ok(MatchingMode::BatchAuction{interval_ms:100}));
+
+    let _ =engine.add_limit_order_into_market(trading_pair.clone(), limit_order(dec!(99), dec!(2), Side::Asks, 1)).await;
+    let _ =engine.add_limit_order_into_market(trading_pair.clone(), limit_order(dec!(101), dec!(3), Side::Bids, 2)).await;
+    assert!(engine.get_recent_trades_for_market(trading_pair.clone(), 10).await.unwrap().is_empty());
+
+    let clearings = engine.clear_due_batch_auctions(1_000).await;
+    assert_eq!(clearings.len(),1);
+    assert_eq!(clearings[0].uncross.volume,dec!(2));
+    assert_eq!(engine.get_recent_trades_for_market(trading_pair.clone(), 10).await.unwrap().len(),1);
+
+    //the next batch collects again and is not due before the interval is over
+    let _ =engine.add_limit_order_into_market(trading_pair.clone(), limit_order(dec!(100), dec!(1), Side::Asks, 1)).await;
+    assert!(engine.clear_due_batch_auctions(1_050).await.is_empty());
+    assert_eq!(engine.get_recent_trades_for_market(trading_pair.clone(), 10).await.unwrap().len(),1);
+    assert_eq!(engine.clear_due_batch_auctions(1_100).await.len(),1);
+    assert_eq!(engine.get_recent_trades_for_market(trading_pair, 10).await.unwrap().len(),2);
+}
+
+#[tokio::test]
+async fn test_batch_auction_only_clears_open_markets(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market_with_matching_mode(trading_pair.clone(), MatchingMode::BatchAuction{interval_ms:100});
+    let _ =engine.add_limit_order_into_market(trading_pair.clone(), limit_order(dec!(99), dec!(2), Side::Asks, 1)).await;
+    let _ =engine.add_limit_order_into_market(trading_pair.clone(), limit_order(dec!(101), dec!(2), Side::Bids, 2)).await;
+    let _ =engine.set_market_status(trading_pair.clone(), MarketStatus::Halted).await;
+    assert!(engine.clear_due_batch_auctions(1_000).await.is_empty());
+
+    //reopening clears the batch and keeps collecting afterwards
+    let change = engine.set_market_status(trading_pair.clone(), MarketStatus::Open).await.unwrap();
+    assert_eq!(change.uncross.unwrap().volume,dec!(2));
+    assert!(engine.on_market(&trading_pair, |market|market.orderbook.in_auction()).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_create_market_rejects_zero_batch_interval(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    assert_eq!(engine.create_market_with_matching_mode(trading_pair.clone(), MatchingMode::BatchAuction{interval_ms:0}),Err(TradingEngineError::InvalidBatchInterval));
+    assert_eq!(engine.get_matching_mode(&trading_pair),Err(TradingEngineError::TradingPairDoesNotExist));
+    let _ =engine.create_market(trading_pair.clone());
+    assert_eq!(engine.get_matching_mode(&trading_pair),Ok(MatchingMode::Continuous));
+}
\ No newline at end of file
@@ -0,0 +1,100 @@
+use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+
+use orderbook::{CancelFilter, LimitOrder, Side};
+use rust_decimal::dec;
+
+use crate::trading_engine::types::{TradingEngine, TradingEngineError, TradingPair};
+
+#[tokio::test]
+async fn test_cancel_all_for_user_across_markets(){
+    let engine = TradingEngine::new();
+    let btc_usdt =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let eth_usdt =TradingPair::new("ETH".to_string(),"USDT".to_string());
+    let sol_usdt =TradingPair::new("SOL".to_string(),"USDT".to_string());
+    for trading_pair in [&btc_usdt,&eth_usdt,&sol_usdt]{
+        let _ = engine.create_market(trading_pair.clone());
+    }
+    let _ = engine.add_limit_order_into_market(btc_usdt.clone(), LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None}).await;
+    let _ = engine.add_limit_order_into_market(btc_usdt.clone(), LimitOrder{price:dec!(100),quantity:dec!(200),side:Side::Bids,user_id:2,client_order_id:None}).await;
+    let _ = engine.add_limit_order_into_market(eth_usdt.clone(), LimitOrder{price:dec!(10),quantity:dec!(5),side:Side::Bids,user_id:1,client_order_id:None}).await;
+    let _ = engine.add_limit_order_into_market(sol_usdt.clone(), LimitOrder{price:dec!(1),quantity:dec!(5),side:Side::Bids,user_id:2,client_order_id:None}).await;
+
+    let cancellations = engine.cancel_all_for_user(1).await;
+    //markets without any of the user's orders are left out
+    assert_eq!(cancellations.len(),2);
+    assert_eq!(cancellations[0].trading_pair,btc_usdt);
+    assert_eq!(cancellations[0].cancelled.len(),1);
+    assert_eq!(cancellations[1].trading_pair,eth_usdt);
+
+    assert!(engine.get_open_orders_for_market(btc_usdt.clone(), 1).await.unwrap().is_empty());
+    assert_eq!(engine.get_open_orders_for_market(btc_usdt, 2).await.unwrap().len(),1);
+}
+
+#[tokio::test]
+async fn test_cancel_all_for_non_existent_market(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let result = engine.cancel_all_for_market(trading_pair, CancelFilter::default()).await;
+    assert_eq!(result,Err(TradingEngineError::TradingPairDoesNotExist));
+}
+
+//the user places every order in BTC before its twin in ETH, so at a consistent cut the sweep takes as many orders
+//from ETH as from BTC or one fewer, never an ETH order whose earlier BTC twin survived
+async fn check_cancel_all_for_user_cuts_every_market_at_once(engine:TradingEngine){
+    let engine=Arc::new(engine);
+    let btc_usdt =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let eth_usdt =TradingPair::new("ETH".to_string(),"USDT".to_string());
+    for trading_pair in [&btc_usdt,&eth_usdt]{
+        let _ = engine.create_market(trading_pair.clone());
+    }
+    let placed=Arc::new(AtomicUsize::new(0));
+    let client={
+        let (engine,placed,btc_usdt,eth_usdt)=(engine.clone(),placed.clone(),btc_usdt.clone(),eth_usdt.clone());
+        tokio::spawn(async move{
+            for _ in 0..500{
+                for trading_pair in [&btc_usdt,&eth_usdt]{
+                    engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Bids,user_id:1,client_order_id:None}).await.unwrap();
+                }
+                placed.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+    };
+    while placed.load(Ordering::SeqCst)<100{
+        tokio::task::yield_now().await;
+    }
+    let cancellations = engine.cancel_all_for_user(1).await;
+    client.await.unwrap();
+
+    let cancelled=|trading_pair:&TradingPair|cancellations.iter().find(|c|&c.trading_pair==trading_pair).map_or(0, |c|c.cancelled.len());
+    let (btc,eth)=(cancelled(&btc_usdt),cancelled(&eth_usdt));
+    assert!(eth>=100);
+    assert!(btc==eth || btc==eth+1,"{btc} cancelled in BTC against {eth} in ETH");
+    assert_eq!(engine.get_open_orders_for_market(btc_usdt, 1).await.unwrap().len(),500-btc);
+}
+
+#[tokio::test]
+async fn test_cancel_all_for_user_cuts_every_market_at_once(){
+    check_cancel_all_for_user_cuts_every_market_at_once(TradingEngine::new()).await;
+    check_cancel_all_for_user_cuts_every_market_at_once(TradingEngine::sequenced(1024, 0).0).await;
+}
+
+//a market that stopped drops the sweep, which must not leave the others waiting for it
+async fn check_cancel_all_for_user_skips_a_stopped_market(engine:TradingEngine){
+    let btc_usdt =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let eth_usdt =TradingPair::new("ETH".to_string(),"USDT".to_string());
+    for trading_pair in [&btc_usdt,&eth_usdt]{
+        let _ = engine.create_market(trading_pair.clone());
+        let _ = engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Bids,user_id:1,client_order_id:None}).await;
+    }
+    let _ = engine.on_market(&eth_usdt, |_|panic!("matching bug")).await;
+
+    let cancellations = engine.cancel_all_for_user(1).await;
+    assert_eq!(cancellations.len(),1);
+    assert_eq!(cancellations[0].trading_pair,btc_usdt);
+}
+
+#[tokio::test]
+async fn test_cancel_all_for_user_skips_a_stopped_market(){
+    check_cancel_all_for_user_skips_a_stopped_market(TradingEngine::new()).await;
+    check_cancel_all_for_user_skips_a_stopped_market(TradingEngine::sequenced(16, 0).0).await;
+}
\ No newline at end of file
@@ -0,0 +1,96 @@
+use orderbook::{LimitOrder, MarketOrder, ModifyOrderRequest, Side};
+use rust_decimal::{dec, Decimal};
+
+use crate::trading_engine::types::{CircuitBreakerConfig, MarketStatus, TradingEngine, TradingEngineError, TradingPair};
+
+async fn trade_at(engine:&TradingEngine,trading_pair:&TradingPair,price:Decimal)->Result<(),TradingEngineError>{
+    engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price,quantity:dec!(1),side:Side::Asks,user_id:1,client_order_id:None}).await?;
+    engine.add_market_order_into_market(trading_pair.clone(), MarketOrder::new(dec!(1), Side::Bids, 2)).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_halts_market_on_large_move(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market(trading_pair.clone());
+    let config = CircuitBreakerConfig{max_move_bps:500,window_ms:60_000,trip_status:MarketStatus::Halted};
+    assert_eq!(engine.set_circuit_breaker_for_market(trading_pair.clone(), Some(config)).await,Ok(()));
+
+    assert_eq!(trade_at(&engine, &trading_pair, dec!(100)).await,Ok(()));
+    assert_eq!(trade_at(&engine, &trading_pair, dec!(104)).await,Ok(()));
+    assert_eq!(engine.get_market_status(&trading_pair).await,Ok(MarketStatus::Open));
+
+    assert_eq!(trade_at(&engine, &trading_pair, dec!(106)).await,Ok(()));
+    assert_eq!(engine.get_market_status(&trading_pair).await,Ok(MarketStatus::Halted));
+    assert_eq!(trade_at(&engine, &trading_pair, dec!(106)).await,Err(TradingEngineError::MarketHalted));
+
+    //the move that tripped the breaker does not trip it again once the market resumes
+    assert_eq!(engine.resume_market(trading_pair.clone()).await,Ok(()));
+    assert_eq!(trade_at(&engine, &trading_pair, dec!(107)).await,Ok(()));
+    assert_eq!(engine.get_market_status(&trading_pair).await,Ok(MarketStatus::Open));
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_reopens_through_an_auction_by_default(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market(trading_pair.clone());
+    let config = CircuitBreakerConfig{max_move_bps:500,window_ms:60_000,trip_status:CircuitBreakerConfig::default_trip_status()};
+    let _ =engine.set_circuit_breaker_for_market(trading_pair.clone(), Some(config)).await;
+    assert_eq!(trade_at(&engine, &trading_pair, dec!(100)).await,Ok(()));
+    assert_eq!(trade_at(&engine, &trading_pair, dec!(110)).await,Ok(()));
+    assert_eq!(engine.get_market_status(&trading_pair).await,Ok(MarketStatus::PreOpen));
+
+    //crossing orders are collected without trading until the market reopens
+    let ask = engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(104),quantity:dec!(2),side:Side::Asks,user_id:1,client_order_id:None}).await.unwrap();
+    let bid = engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(106),quantity:dec!(2),side:Side::Bids,user_id:2,client_order_id:None}).await.unwrap();
+    assert_eq!((ask.quantity_filled,bid.quantity_filled),(dec!(0),dec!(0)));
+    assert_eq!(trade_at(&engine, &trading_pair, dec!(104)).await,Err(TradingEngineError::MarketNotOpen));
+
+    let change = engine.set_market_status(trading_pair.clone(), MarketStatus::Open).await.unwrap();
+    let uncross = change.uncross.unwrap();
+    assert_eq!(uncross.volume,dec!(2));
+    assert_eq!(engine.get_market_status(&trading_pair).await,Ok(MarketStatus::Open));
+    assert_eq!(engine.get_order_by_id_for_market(trading_pair.clone(), bid.order_id).await.unwrap().quantity_filled,dec!(2));
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_trips_on_a_trade_made_by_a_modify(){
+    for by_user in [false,true]{
+        let engine = TradingEngine::new();
+        let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+        let _ =engine.create_market(trading_pair.clone());
+        let config = CircuitBreakerConfig{max_move_bps:500,window_ms:60_000,trip_status:MarketStatus::Halted};
+        let _ =engine.set_circuit_breaker_for_market(trading_pair.clone(), Some(config)).await;
+        assert_eq!(trade_at(&engine, &trading_pair, dec!(100)).await,Ok(()));
+
+        let _ =engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(110),quantity:dec!(1),side:Side::Asks,user_id:1,client_order_id:None}).await;
+        let bid = engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(90),quantity:dec!(1),side:Side::Bids,user_id:2,client_order_id:None}).await.unwrap();
+        assert_eq!(engine.get_market_status(&trading_pair).await,Ok(MarketStatus::Open));
+        //repricing the bid through the ask trades at 110, ten percent above the last trade
+        let request = ModifyOrderRequest{price:Some(dec!(110)),quantity:None,order_id:bid.order_id};
+        let modified = match by_user{
+            true=>engine.modify_user_order_for_market(trading_pair.clone(), 2, request).await,
+            false=>engine.modify_order_for_market(trading_pair.clone(), request).await
+        };
+        assert!(modified.is_ok());
+        assert_eq!(engine.get_recent_trades_for_market(trading_pair.clone(), 1).await.unwrap()[0].price,dec!(110));
+        assert_eq!(engine.get_market_status(&trading_pair).await,Ok(MarketStatus::Halted));
+    }
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_ignores_earlier_trades(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market(trading_pair.clone());
+    assert_eq!(trade_at(&engine, &trading_pair, dec!(100)).await,Ok(()));
+
+    let config = CircuitBreakerConfig{max_move_bps:500,window_ms:60_000,trip_status:MarketStatus::CancelOnly};
+    let _ =engine.set_circuit_breaker_for_market(trading_pair.clone(), Some(config)).await;
+    assert_eq!(trade_at(&engine, &trading_pair, dec!(120)).await,Ok(()));
+    assert_eq!(engine.get_market_status(&trading_pair).await,Ok(MarketStatus::Open));
+    assert_eq!(trade_at(&engine, &trading_pair, dec!(90)).await,Ok(()));
+    assert_eq!(engine.get_market_status(&trading_pair).await,Ok(MarketStatus::CancelOnly));
+}
\ No newline at end of file
@@ -0,0 +1,58 @@
+use orderbook::{LimitOrder, MarketOrder, ModifyOrderRequest, OrderStatus, Side};
+use rust_decimal::dec;
+
+use crate::trading_engine::types::{TradingEngine, TradingEngineError, TradingPair};
+
+#[tokio::test]
+async fn test_book_errors_come_back_as_engine_errors(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market(trading_pair.clone());
+    let ask = engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Asks,user_id:1,client_order_id:None}).await.unwrap();
+    let _ =engine.add_market_order_into_market(trading_pair.clone(), MarketOrder::new(dec!(10), Side::Bids, 2)).await;
+
+    assert_eq!(engine.delete_order_for_market(trading_pair.clone(), ask.order_id).await,Err(TradingEngineError::OrderAlreadyMatched));
+    assert_eq!(engine.get_order_by_id_for_market(trading_pair.clone(), 99).await,Err(TradingEngineError::OrderDoesNotExist));
+    let result = engine.modify_order_for_market(trading_pair.clone(), ModifyOrderRequest{price:None,quantity:Some(dec!(5)),order_id:99}).await;
+    assert_eq!(result,Err(TradingEngineError::OrderDoesNotExist));
+    //a missing market is still reported before the book is asked
+    let result = engine.get_order_by_id_for_market(TradingPair::new("ETH".to_string(),"USDT".to_string()), ask.order_id).await;
+    assert_eq!(result,Err(TradingEngineError::TradingPairDoesNotExist));
+}
+
+#[tokio::test]
+async fn test_engine_keeps_working_after_a_panic_holding_the_markets(){
+    let engine = TradingEngine::new();
+    let btc_usdt =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market(btc_usdt.clone());
+    std::thread::scope(|s|{
+        let poisoned = s.spawn(||{
+            let _shards=engine.shards.write().unwrap();
+            panic!("panicked holding the markets");
+        }).join();
+        assert!(poisoned.is_err());
+    });
+    assert!(engine.shards.is_poisoned());
+
+    let eth_usdt =TradingPair::new("ETH".to_string(),"USDT".to_string());
+    assert_eq!(engine.create_market(eth_usdt.clone()),Ok(()));
+    assert_eq!(engine.get_markets().await.markets,vec![btc_usdt.clone(),eth_usdt]);
+    let result = engine.add_limit_order_into_market(btc_usdt.clone(), LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None}).await;
+    assert!(result.is_ok());
+    assert!(engine.snapshot(&btc_usdt).is_ok());
+}
+
+#[tokio::test]
+async fn test_huge_orders_are_turned_away_before_they_can_overflow_the_market(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market(trading_pair.clone());
+    for _ in 0..2{
+        let ask = engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(50_000_000_000_000_000_000_000_000_000),side:Side::Asks,user_id:1,client_order_id:None}).await.unwrap();
+        assert_eq!(ask.status,OrderStatus::Rejected);
+    }
+    let depth = engine.get_market_depth(trading_pair.clone()).await.unwrap();
+    assert!(depth.asks.is_empty());
+    let result = engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(1),side:Side::Asks,user_id:1,client_order_id:None}).await;
+    assert!(result.is_ok());
+}
\ No newline at end of file
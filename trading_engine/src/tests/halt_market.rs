@@ -0,0 +1,36 @@
+use orderbook::{FeeSchedule, LimitOrder, MarketOrder, ModifyOrderRequest, Side};
+use rust_decimal::dec;
+
+use crate::trading_engine::types::{TradingEngine, TradingEngineError, TradingPair};
+
+#[tokio::test]
+async fn test_halted_market_rejects_new_orders(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market(trading_pair.clone());
+    let _ =engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None}).await;
+    assert_eq!(engine.halt_market(trading_pair.clone()).await,Ok(()));
+    assert!(engine.is_market_halted(&trading_pair).await);
+
+    let result = engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Asks,user_id:2,client_order_id:None}).await;
+    assert_eq!(result,Err(TradingEngineError::MarketHalted));
+    let result = engine.add_market_order_into_market(trading_pair.clone(), MarketOrder::new(dec!(10), Side::Asks, 2)).await;
+    assert_eq!(result,Err(TradingEngineError::MarketHalted));
+    let result = engine.modify_order_for_market(trading_pair.clone(), ModifyOrderRequest{price:None,quantity:Some(dec!(20)),order_id:1}).await;
+    assert_eq!(result,Err(TradingEngineError::MarketHalted));
+
+    //the book is frozen, cancels wait for the market to resume
+    assert_eq!(engine.delete_order_for_market(trading_pair.clone(), 1).await,Err(TradingEngineError::MarketHalted));
+
+    assert_eq!(engine.resume_market(trading_pair.clone()).await,Ok(()));
+    let result = engine.add_limit_order_into_market(trading_pair, LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Asks,user_id:2,client_order_id:None}).await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_halt_non_existent_market(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    assert_eq!(engine.halt_market(trading_pair.clone()).await,Err(TradingEngineError::TradingPairDoesNotExist));
+    assert_eq!(engine.set_fee_schedule_for_market(trading_pair, FeeSchedule::default()).await,Err(TradingEngineError::TradingPairDoesNotExist));
+}
\ No newline at end of file
@@ -3,13 +3,13 @@ use rust_decimal::dec;
 
 use crate::trading_engine::types::{TradingEngine, TradingEngineError, TradingPair};
 
-#[test]
-fn test_add_limit_order(){
-    let mut engine = TradingEngine::new();
+#[tokio::test]
+async fn test_add_limit_order(){
+    let engine = TradingEngine::new();
     let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
     let _ =engine.create_market(trading_pair.clone());
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
-    let result = engine.add_limit_order_into_market(trading_pair, limit_order);
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let result = engine.add_limit_order_into_market(trading_pair, limit_order).await;
     
     assert_ne!(result,Err(TradingEngineError::TradingPairDoesNotExist));
     
@@ -19,8 +19,8 @@ fn test_add_limit_order(){
 
     let trading_pair =TradingPair::new("BTC".to_string(),"SOL".to_string());
     let _ = engine.create_market(trading_pair.clone());
-    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1};
-    let result = engine.add_limit_order_into_market(trading_pair, limit_order);
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let result = engine.add_limit_order_into_market(trading_pair, limit_order).await;
     
     assert_ne!(result,Err(TradingEngineError::TradingPairDoesNotExist));
     
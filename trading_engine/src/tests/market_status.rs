@@ -0,0 +1,65 @@
+use orderbook::{BatchOperation, LimitOrder, MarketOrder, Side};
+use rust_decimal::dec;
+
+use crate::trading_engine::types::{MarketStatus, TradingEngine, TradingEngineError, TradingPair};
+
+#[tokio::test]
+async fn test_cancel_only_market_only_takes_cancels(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market(trading_pair.clone());
+    let _ =engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None}).await;
+    let _ =engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(99),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None}).await;
+    assert!(engine.set_market_status(trading_pair.clone(), MarketStatus::CancelOnly).await.is_ok());
+    assert_eq!(engine.get_market_status(&trading_pair).await,Ok(MarketStatus::CancelOnly));
+
+    let result = engine.add_market_order_into_market(trading_pair.clone(), MarketOrder::new(dec!(10), Side::Asks, 2)).await;
+    assert_eq!(result,Err(TradingEngineError::MarketCancelOnly));
+    let result = engine.apply_batch_for_market(trading_pair.clone(), vec![BatchOperation::Cancel{order_id:1},BatchOperation::Place(LimitOrder{price:dec!(98),quantity:dec!(1),side:Side::Bids,user_id:1,client_order_id:None})], false).await;
+    assert_eq!(result,Err(TradingEngineError::MarketCancelOnly));
+
+    assert!(engine.apply_batch_for_market(trading_pair.clone(), vec![BatchOperation::Cancel{order_id:1}], false).await.unwrap().success);
+    assert!(engine.delete_order_for_market(trading_pair, 2).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_pre_open_market_collects_orders_and_uncrosses_on_open(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market(trading_pair.clone());
+    assert!(engine.set_market_status(trading_pair.clone(), MarketStatus::PreOpen).await.is_ok());
+    let result = engine.add_market_order_into_market(trading_pair.clone(), MarketOrder::new(dec!(10), Side::Asks, 2)).await;
+    assert_eq!(result,Err(TradingEngineError::MarketNotOpen));
+    let _ =engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(101),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None}).await;
+    let _ =engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(4),side:Side::Asks,user_id:2,client_order_id:None}).await;
+    assert!(engine.get_recent_trades_for_market(trading_pair.clone(), 10).await.unwrap().is_empty());
+    assert_eq!(engine.get_indicative_uncross_for_market(&trading_pair).await.unwrap().unwrap().volume,dec!(4));
+
+    let change = engine.set_market_status(trading_pair.clone(), MarketStatus::Open).await.unwrap();
+    let uncross = change.uncross.unwrap();
+    assert_eq!((uncross.price,uncross.volume),(dec!(101),dec!(4)));
+    assert_eq!(engine.get_recent_trades_for_market(trading_pair.clone(), 10).await.unwrap().len(),1);
+    assert_eq!(engine.get_indicative_uncross_for_market(&trading_pair).await,Ok(None));
+}
+
+#[tokio::test]
+async fn test_delist_cancels_orders_and_is_final(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market(trading_pair.clone());
+    let _ =engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None}).await;
+    let _ =engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(101),quantity:dec!(5),side:Side::Asks,user_id:2,client_order_id:None}).await;
+
+    let change = engine.set_market_status(trading_pair.clone(), MarketStatus::Delisted).await.unwrap();
+    assert_eq!(change.cancelled.len(),2);
+    assert!(engine.get_markets().await.markets.is_empty());
+    assert_eq!(engine.get_market_depth(trading_pair.clone()).await.unwrap().bids.len(),0);
+
+    //the archived book still answers history
+    assert_eq!(engine.get_order_history_for_market(trading_pair.clone(), 1, 10).await.unwrap().len(),1);
+
+    let result = engine.add_limit_order_into_market(trading_pair.clone(), LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Bids,user_id:1,client_order_id:None}).await;
+    assert_eq!(result,Err(TradingEngineError::MarketDelisted));
+    assert_eq!(engine.set_market_status(trading_pair.clone(), MarketStatus::Open).await,Err(TradingEngineError::MarketDelisted));
+    assert_eq!(engine.create_market(trading_pair),Err(TradingEngineError::TradingPairAlreadyExists));
+}
\ No newline at end of file
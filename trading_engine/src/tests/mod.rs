@@ -1,4 +1,18 @@
 mod create_trading_engine;
 mod get_markets;
 mod limit_order;
-mod market_order;
\ No newline at end of file
+mod market_order;
+mod trades;
+mod order_history;
+mod cancel_all;
+mod batch;
+mod halt_market;
+mod market_status;
+mod circuit_breaker;
+mod batch_auction;
+mod sharding;
+mod ring;
+mod sequencer;
+mod snapshot;
+mod errors;
+mod numeric_mode;
\ No newline at end of file
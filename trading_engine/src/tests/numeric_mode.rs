@@ -0,0 +1,52 @@
+use std::time::{Duration, Instant};
+
+use orderbook::{LimitOrder, OrderStatus, Side};
+use rust_decimal::{dec, Decimal};
+
+use crate::trading_engine::types::{MatchingMode, NumericMode, TradingEngine, TradingEngineError, TradingPair};
+
+fn limit_order(price:Decimal,quantity:Decimal,side:Side,user_id:u64)->LimitOrder{
+    LimitOrder{price,quantity,side,user_id,client_order_id:None}
+}
+
+const TICKS_AND_LOTS:NumericMode=NumericMode::FixedPoint { tick_size:dec!(0.01), lot_size:dec!(0.001) };
+
+#[tokio::test]
+async fn test_fixed_point_market_matches_in_ticks_and_lots(){
+    for engine in [TradingEngine::new(),TradingEngine::sequenced(16, 0).0]{
+        let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+        assert_eq!(engine.create_market_with_modes(trading_pair.clone(), MatchingMode::Continuous, TICKS_AND_LOTS),Ok(()));
+        assert_eq!(engine.on_market(&trading_pair, |market|market.orderbook.numeric_mode()).await,Ok(TICKS_AND_LOTS));
+
+        let _ =engine.add_limit_order_into_market(trading_pair.clone(), limit_order(dec!(100.05), dec!(1.5), Side::Asks, 1)).await;
+        let _ =engine.add_limit_order_into_market(trading_pair.clone(), limit_order(dec!(100.05), dec!(0.25), Side::Bids, 2)).await;
+        //neither a fraction of a tick nor a fraction of a lot gets into the book
+        let off_tick = engine.add_limit_order_into_market(trading_pair.clone(), limit_order(dec!(100.005), dec!(1), Side::Asks, 1)).await.unwrap();
+        assert_eq!(off_tick.status,OrderStatus::Rejected);
+        let off_lot = engine.add_limit_order_into_market(trading_pair.clone(), limit_order(dec!(101), dec!(0.0005), Side::Asks, 1)).await.unwrap();
+        assert_eq!(off_lot.status,OrderStatus::Rejected);
+
+        let depth = engine.get_market_depth(trading_pair.clone()).await.unwrap();
+        assert_eq!(depth.asks.len(),1);
+        assert_eq!((depth.asks[0].price,depth.asks[0].quantity),(dec!(100.05),dec!(1.25)));
+        assert_eq!(engine.get_recent_trades_for_market(trading_pair.clone(), 10).await.unwrap().len(),1);
+        //the snapshot follows a fixed point book as it follows a decimal one
+        let deadline=Instant::now()+Duration::from_secs(1);
+        while engine.snapshot(&trading_pair).unwrap().depth(None)!=depth&&Instant::now()<deadline{
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(engine.snapshot(&trading_pair).unwrap().depth(None),depth);
+    }
+}
+
+#[tokio::test]
+async fn test_create_market_rejects_a_tick_or_lot_size_that_is_not_above_zero(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    for numeric_mode in [NumericMode::FixedPoint { tick_size:dec!(0), lot_size:dec!(0.001) },NumericMode::FixedPoint { tick_size:dec!(0.01), lot_size:dec!(-1) }]{
+        assert_eq!(engine.create_market_with_modes(trading_pair.clone(), MatchingMode::Continuous, numeric_mode),Err(TradingEngineError::InvalidTickOrLotSize));
+    }
+    assert_eq!(engine.get_matching_mode(&trading_pair),Err(TradingEngineError::TradingPairDoesNotExist));
+    let _ =engine.create_market(trading_pair.clone());
+    assert_eq!(engine.on_market(&trading_pair, |market|market.orderbook.numeric_mode()).await,Ok(NumericMode::Decimal));
+}
\ No newline at end of file
@@ -0,0 +1,32 @@
+use orderbook::{LimitOrder, OrderStatus, Side};
+use rust_decimal::dec;
+
+use crate::trading_engine::types::{TradingEngine, TradingEngineError, TradingPair};
+
+#[tokio::test]
+async fn test_open_orders_and_order_history_for_market(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market(trading_pair.clone());
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let open_order = engine.add_limit_order_into_market(trading_pair.clone(), limit_order).await.unwrap();
+    let limit_order = LimitOrder{price:dec!(106),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let _ = engine.add_limit_order_into_market(trading_pair.clone(), limit_order).await;
+    let _ = engine.delete_order_for_market(trading_pair.clone(), open_order.order_id).await;
+
+    let open_orders = engine.get_open_orders_for_market(trading_pair.clone(), 1).await.unwrap();
+    assert_eq!(open_orders.len(),1);
+    assert_eq!(open_orders[0].price,dec!(106));
+
+    let history = engine.get_order_history_for_market(trading_pair, 1, 10).await.unwrap();
+    assert_eq!(history.len(),1);
+    assert_eq!(history[0].status,OrderStatus::Cancelled);
+}
+
+#[tokio::test]
+async fn test_open_orders_for_non_existent_market(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let result = engine.get_open_orders_for_market(trading_pair, 1).await;
+    assert_eq!(result,Err(TradingEngineError::TradingPairDoesNotExist));
+}
\ No newline at end of file
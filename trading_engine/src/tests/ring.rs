@@ -0,0 +1,49 @@
+use std::thread;
+
+use crate::trading_engine::ring::RingBuffer;
+
+#[test]
+fn test_ring_hands_over_every_value_in_order(){
+    //a ring much smaller than the stream makes the producer wait on the consumer
+    let (mut producer,mut receiver)=RingBuffer::<u64>::single(8);
+    let producing=thread::spawn(move||{
+        for value in 0..10_000{
+            producer.push(value);
+        }
+    });
+    let received:Vec<u64>=std::iter::from_fn(||receiver.recv()).collect();
+    producing.join().unwrap();
+    assert_eq!(received,(0..10_000).collect::<Vec<u64>>());
+}
+
+#[test]
+fn test_every_subscriber_sees_every_value(){
+    let (mut producer,subscribers)=RingBuffer::<u64>::broadcast(4, 3);
+    let reading:Vec<_>=subscribers
+        .into_iter()
+        .map(|mut subscriber|thread::spawn(move||std::iter::from_fn(||subscriber.recv()).sum::<u64>()))
+        .collect();
+    for value in 1..=1_000{
+        producer.push(value);
+    }
+    drop(producer);
+    for sum in reading{
+        assert_eq!(sum.join().unwrap(),500_500);
+    }
+}
+
+#[test]
+fn test_dropped_subscriber_does_not_hold_up_the_producer(){
+    let (mut producer,mut subscribers)=RingBuffer::<u64>::broadcast(2, 2);
+    let mut subscriber=subscribers.pop().unwrap();
+    drop(subscribers);
+    assert_eq!(subscriber.try_recv(),None);
+    producer.push(1);
+    producer.push(2);
+    assert_eq!((subscriber.try_recv(),subscriber.try_recv()),(Some(1),Some(2)));
+    drop(subscriber);
+    //nobody reads any more, the ring keeps taking values
+    for value in 3..10{
+        producer.push(value);
+    }
+}
\ No newline at end of file
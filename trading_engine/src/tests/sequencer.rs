@@ -0,0 +1,61 @@
+use orderbook::{LimitOrder, MarketOrder, Side};
+use rust_decimal::dec;
+
+use crate::trading_engine::types::{EngineEvent, EngineMode, MarketStatus, MatchingMode, SequencedEvent, TradingEngine, TradingEngineError, TradingPair};
+use crate::RingSubscriber;
+
+fn drain(subscriber:&mut RingSubscriber<SequencedEvent>)->Vec<SequencedEvent>{
+    std::iter::from_fn(||subscriber.recv()).collect()
+}
+
+#[tokio::test]
+async fn test_sequenced_engine_publishes_numbered_events(){
+    let (engine,mut subscribers) = TradingEngine::sequenced(16, 2);
+    assert_eq!(engine.mode(),EngineMode::Sequenced);
+    let btc_usdt =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let eth_usdt =TradingPair::new("ETH".to_string(),"USDT".to_string());
+    let _ =engine.create_market(btc_usdt.clone());
+    let _ =engine.create_market(eth_usdt.clone());
+    let _ =engine.add_limit_order_into_market(btc_usdt.clone(), LimitOrder{price:dec!(100),quantity:dec!(10),side:Side::Asks,user_id:1,client_order_id:None}).await;
+    let _ =engine.add_limit_order_into_market(eth_usdt.clone(), LimitOrder{price:dec!(10),quantity:dec!(5),side:Side::Bids,user_id:1,client_order_id:None}).await;
+    let _ =engine.add_market_order_into_market(btc_usdt.clone(), MarketOrder::new(dec!(4), Side::Bids, 2)).await;
+    assert_eq!(engine.get_market_depth(btc_usdt.clone()).await.unwrap().asks[0].quantity,dec!(6));
+    let _ =engine.halt_market(eth_usdt.clone()).await;
+    drop(engine);
+
+    let events = drain(&mut subscribers[0]);
+    assert_eq!(events,drain(&mut subscribers[1]));
+    let trades:Vec<_>=events.iter().filter_map(|e|match &e.event{
+        EngineEvent::Trade(trade)=>Some((e.sequence,trade.price,trade.quantity,trade.taker_user_id)),
+        _=>None
+    }).collect();
+    assert_eq!(trades,vec![(5,dec!(100),dec!(4),2)]);
+    //the market order left the best ask where it was and the depth query, sequence 6, changed nothing
+    let others:Vec<(u64,&str,EngineEvent)>=events.iter()
+        .filter(|e|!matches!(e.event,EngineEvent::Trade(_)))
+        .map(|e|(e.sequence,e.trading_pair.base.as_str(),e.event.clone()))
+        .collect();
+    assert_eq!(others,vec![
+        (1,"BTC",EngineEvent::MarketCreated(MatchingMode::Continuous)),
+        (2,"ETH",EngineEvent::MarketCreated(MatchingMode::Continuous)),
+        (3,"BTC",EngineEvent::TopOfBook { best_bid:None, best_ask:Some(dec!(100)) }),
+        (4,"ETH",EngineEvent::TopOfBook { best_bid:Some(dec!(10)), best_ask:None }),
+        (7,"ETH",EngineEvent::StatusChanged(MarketStatus::Halted)),
+    ]);
+}
+
+#[tokio::test]
+async fn test_sequenced_market_that_panicked_is_unavailable(){
+    let (engine,_subscribers) = TradingEngine::sequenced(16, 0);
+    let btc_usdt =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let eth_usdt =TradingPair::new("ETH".to_string(),"USDT".to_string());
+    let _ =engine.create_market(btc_usdt.clone());
+    let _ =engine.create_market(eth_usdt.clone());
+
+    let result = engine.on_market(&btc_usdt, |_|panic!("matching bug")).await;
+    assert_eq!(result,Err(TradingEngineError::MarketUnavailable));
+    assert_eq!(engine.get_market_depth(btc_usdt).await.err(),Some(TradingEngineError::MarketUnavailable));
+    //the matching thread carries on with the other markets
+    assert!(engine.get_market_depth(eth_usdt.clone()).await.is_ok());
+    assert_eq!(engine.get_markets().await.markets,vec![eth_usdt]);
+}
\ No newline at end of file
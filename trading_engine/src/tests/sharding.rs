@@ -0,0 +1,45 @@
+use std::sync::mpsc;
+
+use orderbook::{LimitOrder, Side};
+use rust_decimal::dec;
+
+use crate::trading_engine::types::{TradingEngine, TradingEngineError, TradingPair};
+
+#[tokio::test]
+async fn test_busy_market_does_not_hold_up_other_markets(){
+    let engine = TradingEngine::new();
+    let btc_usdt =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let eth_usdt =TradingPair::new("ETH".to_string(),"USDT".to_string());
+    let _ =engine.create_market(btc_usdt.clone());
+    let _ =engine.create_market(eth_usdt.clone());
+    let _ =engine.add_limit_order_into_market(eth_usdt.clone(), LimitOrder{price:dec!(10),quantity:dec!(5),side:Side::Bids,user_id:1,client_order_id:None}).await;
+
+    //btc stays busy until eth has answered
+    let (release,wait)=mpsc::channel::<()>();
+    let (busy,depth)=tokio::join!(
+        engine.on_market(&btc_usdt, move|_|wait.recv().is_ok()),
+        async{
+            let depth = engine.get_market_depth(eth_usdt.clone()).await;
+            let _ =release.send(());
+            depth
+        }
+    );
+    assert_eq!(busy,Ok(true));
+    assert_eq!(depth.unwrap().bids.len(),1);
+}
+
+#[tokio::test]
+async fn test_market_whose_thread_stopped_is_unavailable(){
+    let engine = TradingEngine::new();
+    let btc_usdt =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let eth_usdt =TradingPair::new("ETH".to_string(),"USDT".to_string());
+    let _ =engine.create_market(btc_usdt.clone());
+    let _ =engine.create_market(eth_usdt.clone());
+
+    let result = engine.on_market(&btc_usdt, |_|panic!("matching bug")).await;
+    assert_eq!(result,Err(TradingEngineError::MarketUnavailable));
+    assert_eq!(engine.get_market_depth(btc_usdt).await.err(),Some(TradingEngineError::MarketUnavailable));
+    //the other markets keep trading and are still listed
+    assert!(engine.get_market_depth(eth_usdt.clone()).await.is_ok());
+    assert_eq!(engine.get_markets().await.markets,vec![eth_usdt]);
+}
\ No newline at end of file
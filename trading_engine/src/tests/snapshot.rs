@@ -0,0 +1,116 @@
+use std::{sync::{mpsc, Arc}, time::{Duration, Instant}};
+
+use orderbook::{LimitOrder, MarketOrder, ModifyOrderRequest, OrderStatus, Side};
+use rust_decimal::dec;
+
+use crate::trading_engine::types::{BookSnapshot, TradingEngine, TradingEngineError, TradingPair};
+use crate::MAX_SNAPSHOT_STALENESS;
+
+//a command's reply can arrive just before the snapshot showing it is published
+fn snapshot_where(engine:&TradingEngine,trading_pair:&TradingPair,predicate:impl Fn(&BookSnapshot)->bool)->Arc<BookSnapshot>{
+    let deadline=Instant::now()+Duration::from_secs(1);
+    loop{
+        let snapshot=engine.snapshot(trading_pair).unwrap();
+        if predicate(&snapshot)||Instant::now()>deadline{
+            return snapshot
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+async fn check_snapshot_follows_the_book(engine:TradingEngine){
+    let btc_usdt =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market(btc_usdt.clone());
+    let snapshot = engine.snapshot(&btc_usdt).unwrap();
+    assert_eq!(snapshot.staleness.version,1);
+    assert_eq!(snapshot.staleness.max_staleness_ms,MAX_SNAPSHOT_STALENESS.as_millis() as u64);
+    assert!(snapshot.depth(None).bids.is_empty());
+
+    let ask = engine.add_limit_order_into_market(btc_usdt.clone(), LimitOrder{price:dec!(102),quantity:dec!(5),side:Side::Asks,user_id:1,client_order_id:Some("ask".to_string())}).await.unwrap();
+    let _ =engine.add_limit_order_into_market(btc_usdt.clone(), LimitOrder{price:dec!(98),quantity:dec!(5),side:Side::Bids,user_id:1,client_order_id:Some("bid".to_string())}).await;
+    let _ =engine.add_market_order_into_market(btc_usdt.clone(), MarketOrder::new(dec!(5), Side::Bids, 2)).await;
+    let _ =engine.add_limit_order_into_market(btc_usdt.clone(), LimitOrder{price:dec!(104),quantity:dec!(3),side:Side::Asks,user_id:1,client_order_id:None}).await;
+
+    let snapshot = snapshot_where(&engine, &btc_usdt, |s|s.depth(None).asks.first().is_some_and(|a|a.price==dec!(104)));
+    assert!(snapshot.staleness.version>1);
+    assert_eq!(snapshot.depth(None),engine.get_market_depth(btc_usdt.clone()).await.unwrap());
+    assert_eq!(snapshot.mid_price,Some(dec!(101)));
+    //the filled ask is answered from the closed orders, the bid from the book
+    assert_eq!(snapshot.get_order(ask.order_id).unwrap().status,OrderStatus::Filled);
+    assert_eq!(snapshot.get_order_by_client_order_id(1, "ask").unwrap().order_id,ask.order_id);
+    assert_eq!(snapshot.get_order_by_client_order_id(1, "bid").unwrap().price,dec!(98));
+    assert_eq!(snapshot.get_order_by_client_order_id(2, "bid"),Err(TradingEngineError::OrderDoesNotExist));
+    assert_eq!(snapshot.get_order(99),Err(TradingEngineError::OrderDoesNotExist));
+}
+
+#[tokio::test]
+async fn test_snapshot_follows_the_book(){
+    check_snapshot_follows_the_book(TradingEngine::new()).await;
+    check_snapshot_follows_the_book(TradingEngine::sequenced(16, 0).0).await;
+}
+
+async fn check_snapshot_follows_resting_orders(engine:TradingEngine){
+    let btc_usdt =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market(btc_usdt.clone());
+    let bid = engine.add_limit_order_into_market(btc_usdt.clone(), LimitOrder{price:dec!(98),quantity:dec!(5),side:Side::Bids,user_id:1,client_order_id:Some("bid".to_string())}).await.unwrap();
+    let _ =engine.add_limit_order_into_market(btc_usdt.clone(), LimitOrder{price:dec!(98),quantity:dec!(2),side:Side::Bids,user_id:2,client_order_id:None}).await;
+    let ask = engine.add_limit_order_into_market(btc_usdt.clone(), LimitOrder{price:dec!(101),quantity:dec!(4),side:Side::Asks,user_id:1,client_order_id:Some("ask".to_string())}).await.unwrap();
+    let _ =engine.modify_order_for_market(btc_usdt.clone(), ModifyOrderRequest{price:Some(dec!(97)),quantity:Some(dec!(3)),order_id:bid.order_id}).await;
+    let _ =engine.add_market_order_into_market(btc_usdt.clone(), MarketOrder::new(dec!(1), Side::Bids, 2)).await;
+    let _ =engine.delete_order_for_market(btc_usdt.clone(), ask.order_id).await;
+    let _ =engine.add_limit_order_into_market(btc_usdt.clone(), LimitOrder{price:dec!(102),quantity:dec!(1),side:Side::Asks,user_id:1,client_order_id:None}).await;
+
+    let depth = engine.get_market_depth(btc_usdt.clone()).await.unwrap();
+    let snapshot = snapshot_where(&engine, &btc_usdt, |s|s.depth(None)==depth);
+    assert_eq!(snapshot.depth(None),depth);
+    assert_eq!(snapshot.depth(Some(1)).bids.len(),1);
+    assert_eq!(snapshot.get_order(bid.order_id).unwrap(),engine.get_order_by_id_for_market(btc_usdt.clone(), bid.order_id).await.unwrap());
+    assert_eq!(snapshot.get_order(ask.order_id).unwrap().status,OrderStatus::Cancelled);
+    //a cancelled order's client order id moves from the open orders to the closed ones
+    assert_eq!(snapshot.get_order_by_client_order_id(1, "ask").unwrap().status,OrderStatus::Cancelled);
+    assert_eq!(snapshot.get_order_by_client_order_id(1, "bid").unwrap().price,dec!(97));
+}
+
+#[tokio::test]
+async fn test_snapshot_follows_resting_orders(){
+    check_snapshot_follows_resting_orders(TradingEngine::new()).await;
+    check_snapshot_follows_resting_orders(TradingEngine::sequenced(16, 0).0).await;
+}
+
+#[tokio::test]
+async fn test_snapshot_does_not_wait_for_a_busy_market(){
+    let engine = TradingEngine::new();
+    let btc_usdt =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let _ =engine.create_market(btc_usdt.clone());
+    let _ =engine.add_limit_order_into_market(btc_usdt.clone(), LimitOrder{price:dec!(10),quantity:dec!(5),side:Side::Bids,user_id:1,client_order_id:None}).await;
+    snapshot_where(&engine, &btc_usdt, |s|!s.depth(None).bids.is_empty());
+
+    //the market stays busy until its snapshot was read
+    let (release,wait)=mpsc::channel::<()>();
+    let (busy,bids)=tokio::join!(
+        engine.on_market(&btc_usdt, move|_|wait.recv().is_ok()),
+        async{
+            let bids = engine.snapshot(&btc_usdt).unwrap().depth(None).bids.len();
+            let _ =release.send(());
+            bids
+        }
+    );
+    assert_eq!(busy,Ok(true));
+    assert_eq!(bids,1);
+    assert_eq!(engine.snapshot(&TradingPair::new("ETH".to_string(),"USDT".to_string())).err(),Some(TradingEngineError::TradingPairDoesNotExist));
+}
+
+#[tokio::test]
+async fn test_market_that_panicked_has_no_snapshot(){
+    for engine in [TradingEngine::new(),TradingEngine::sequenced(16, 0).0]{
+        let btc_usdt =TradingPair::new("BTC".to_string(),"USDT".to_string());
+        let _ =engine.create_market(btc_usdt.clone());
+        let _ =engine.on_market(&btc_usdt, |_|panic!("matching bug")).await;
+        //the sharded thread drops its snapshot while unwinding, which may finish after the reply failed
+        let deadline=Instant::now()+Duration::from_secs(1);
+        while engine.snapshot(&btc_usdt).is_ok()&&Instant::now()<deadline{
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(engine.snapshot(&btc_usdt).err(),Some(TradingEngineError::MarketUnavailable));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,32 @@
+use orderbook::{LimitOrder, MarketOrder, Side, TradeHistoryQuery};
+use rust_decimal::dec;
+
+use crate::trading_engine::types::{TradingEngine, TradingEngineError, TradingPair};
+
+#[tokio::test]
+async fn test_trades_are_kept_per_market(){
+    let engine = TradingEngine::new();
+    let btc_usdt =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let eth_usdt =TradingPair::new("ETH".to_string(),"USDT".to_string());
+    let _ = engine.create_market(btc_usdt.clone());
+    let _ = engine.create_market(eth_usdt.clone());
+    let limit_order = LimitOrder{price:dec!(105),quantity:dec!(200),side:Side::Asks,user_id:1,client_order_id:None};
+    let _ = engine.add_limit_order_into_market(btc_usdt.clone(), limit_order).await;
+    let _ = engine.add_market_order_into_market(btc_usdt.clone(), MarketOrder::new(dec!(50), Side::Bids, 2)).await;
+
+    let trades = engine.get_recent_trades_for_market(btc_usdt.clone(), 10).await.unwrap();
+    assert_eq!(trades.len(),1);
+    assert_eq!(trades[0].quantity,dec!(50));
+    assert!(engine.get_recent_trades_for_market(eth_usdt, 10).await.unwrap().is_empty());
+
+    let my_trades = engine.get_user_trades_for_market(btc_usdt, 2, TradeHistoryQuery::default()).await.unwrap();
+    assert_eq!(my_trades.trades.len(),1);
+}
+
+#[tokio::test]
+async fn test_trades_for_non_existent_market(){
+    let engine = TradingEngine::new();
+    let trading_pair =TradingPair::new("BTC".to_string(),"USDT".to_string());
+    let result = engine.get_trade_history_for_market(trading_pair, TradeHistoryQuery::default()).await;
+    assert_eq!(result,Err(TradingEngineError::TradingPairDoesNotExist));
+}
\ No newline at end of file
@@ -0,0 +1,192 @@
+use orderbook::{
+    BatchOperation, BatchResponse, CancelFilter, DeleteResponse, LimitOrder, MarketOrder, MarketOrderResponse, ModifyOrderRequest, ModifyOrderResponse, OpenOrder, Uncross
+};
+use rust_decimal::Decimal;
+use crate::trading_engine::types::{BatchAuction, CircuitBreaker, CircuitBreakerConfig, Market, MarketBook, MarketStatus, MatchingMode, NumericMode, TradingEngineError};
+
+impl Market{
+    //a batch auction market keeps its book in auction and clears it every interval
+    pub fn new(matching_mode:MatchingMode,numeric_mode:NumericMode)->Result<Market,TradingEngineError>{
+        let mut market=Market { orderbook:MarketBook::new(numeric_mode)?, ..Market::default() };
+        if let MatchingMode::BatchAuction{interval_ms}=matching_mode{
+            if interval_ms==0{
+                return Err(TradingEngineError::InvalidBatchInterval)
+            }
+            market.orderbook.start_auction();
+            market.batch_auction=Some(BatchAuction{interval_ms,next_clear_at:0});
+        }
+        Ok(market)
+    }
+
+    pub fn matching_mode(&self)->MatchingMode{
+        match &self.batch_auction{
+            Some(batch)=>MatchingMode::BatchAuction{interval_ms:batch.interval_ms},
+            None=>MatchingMode::Continuous
+        }
+    }
+
+    //best bid and best ask
+    pub fn top_of_book(&self)->(Option<Decimal>,Option<Decimal>){
+        (self.orderbook.get_best_bid(),self.orderbook.get_best_ask())
+    }
+
+    //stops an open market once its last trade moved too far from any other trade within the window
+    fn check_circuit_breaker(&mut self){
+        if !self.status.accepts_orders(){
+            return
+        }
+        let Some(breaker)=self.circuit_breaker.as_mut() else{
+            return
+        };
+        let Some(last)=self.orderbook.trade_log().trades.back() else{
+            return
+        };
+        let since=last.timestamp.saturating_sub(breaker.config.window_ms);
+        let tripped=self.orderbook.trade_log().trades
+            .iter()
+            .rev()
+            .take_while(|t|t.trade_id>breaker.last_trade_id && t.timestamp>=since)
+            .any(|t|breaker.config.is_exceeded_by(t.price, last.price));
+        if tripped{
+            breaker.last_trade_id=last.trade_id;
+            let trip_status=breaker.config.trip_status;
+            let _=self.set_status(trip_status);
+        }
+    }
+
+    //clears the collected orders once the interval ran out at `now`, only while the market is open
+    pub fn clear_batch_auction_if_due(&mut self,now:u64)->Option<Uncross>{
+        let batch=self.batch_auction.as_mut()?;
+        if batch.next_clear_at>now{
+            return None
+        }
+        batch.next_clear_at=now+batch.interval_ms;
+        if !self.status.accepts_orders(){
+            return None
+        }
+        let uncross=self.orderbook.end_auction();
+        self.orderbook.start_auction();
+        if uncross.is_some(){
+            self.check_circuit_breaker();
+        }
+        uncross
+    }
+
+    pub fn add_limit_order(&mut self,order:LimitOrder)->Result<OpenOrder,TradingEngineError>{
+        self.status.check_limit_orders()?;
+        let open_order=self.orderbook.add_limit_order(order);
+        self.check_circuit_breaker();
+        Ok(open_order)
+    }
+
+    pub fn place_limit_order(&mut self,order:LimitOrder)->Result<OpenOrder,TradingEngineError>{
+        self.status.check_limit_orders()?;
+        let open_order=self.orderbook.place_limit_order(order)?;
+        self.check_circuit_breaker();
+        Ok(open_order)
+    }
+
+    pub fn add_market_order(&mut self,order:MarketOrder)->Result<MarketOrderResponse,TradingEngineError>{
+        self.status.check_orders()?;
+        let response=self.orderbook.add_market_order(order);
+        self.check_circuit_breaker();
+        Ok(response)
+    }
+
+    pub fn delete_order(&mut self,order_id:u64)->Result<DeleteResponse,TradingEngineError>{
+        self.status.check_cancels()?;
+        Ok(self.orderbook.delete_order(order_id)?)
+    }
+
+    pub fn delete_user_order(&mut self,user_id:u64,order_id:u64)->Result<DeleteResponse,TradingEngineError>{
+        self.status.check_cancels()?;
+        Ok(self.orderbook.delete_user_order(user_id, order_id)?)
+    }
+
+    pub fn delete_order_by_client_order_id(&mut self,user_id:u64,client_order_id:&str)->Result<DeleteResponse,TradingEngineError>{
+        self.status.check_cancels()?;
+        Ok(self.orderbook.delete_order_by_client_order_id(user_id, client_order_id)?)
+    }
+
+    pub fn modify_order(&mut self,order:ModifyOrderRequest)->Result<ModifyOrderResponse,TradingEngineError>{
+        self.status.check_limit_orders()?;
+        //a repriced order can trade like a new one
+        let response=self.orderbook.modify_order(order)?;
+        self.check_circuit_breaker();
+        Ok(response)
+    }
+
+    pub fn modify_user_order(&mut self,user_id:u64,order:ModifyOrderRequest)->Result<ModifyOrderResponse,TradingEngineError>{
+        self.status.check_limit_orders()?;
+        let response=self.orderbook.modify_user_order(user_id, order)?;
+        self.check_circuit_breaker();
+        Ok(response)
+    }
+
+    pub fn cancel_all(&mut self,filter:CancelFilter)->Result<Vec<DeleteResponse>,TradingEngineError>{
+        self.status.check_cancels()?;
+        Ok(self.orderbook.cancel_all(filter))
+    }
+
+    //a batch of nothing but cancels goes through wherever cancels do
+    fn check_batch(&self,operations:&[BatchOperation])->Result<(),TradingEngineError>{
+        if operations.iter().all(|o|matches!(o,BatchOperation::Cancel{..})){
+            self.status.check_cancels()
+        }else{
+            self.status.check_limit_orders()
+        }
+    }
+
+    pub fn apply_batch(&mut self,operations:Vec<BatchOperation>,all_or_nothing:bool)->Result<BatchResponse,TradingEngineError>{
+        self.check_batch(&operations)?;
+        let response=self.orderbook.apply_batch(operations, all_or_nothing);
+        self.check_circuit_breaker();
+        Ok(response)
+    }
+
+    pub fn apply_user_batch(&mut self,user_id:u64,operations:Vec<BatchOperation>,all_or_nothing:bool)->Result<BatchResponse,TradingEngineError>{
+        self.check_batch(&operations)?;
+        let response=self.orderbook.apply_user_batch(user_id, operations, all_or_nothing);
+        self.check_circuit_breaker();
+        Ok(response)
+    }
+
+    //any status can be reached from any other except out of delisted, delisting cancels every resting order
+    pub fn set_status(&mut self,status:MarketStatus)->Result<(Vec<DeleteResponse>,Option<Uncross>),TradingEngineError>{
+        if self.status==MarketStatus::Delisted{
+            return Err(TradingEngineError::MarketDelisted)
+        }
+        let cancelled=if status==MarketStatus::Delisted{
+            self.orderbook.cancel_all(CancelFilter::default())
+        }else{
+            Vec::new()
+        };
+        //pre-open runs a call auction that uncrosses once the market opens, a delisted book is simply emptied
+        let uncross=match status{
+            MarketStatus::PreOpen=>{
+                self.orderbook.start_auction();
+                None
+            },
+            MarketStatus::Open if self.orderbook.in_auction()=>{
+                let uncross=self.orderbook.end_auction();
+                if self.batch_auction.is_some(){
+                    self.orderbook.start_auction();
+                }
+                uncross
+            },
+            MarketStatus::Delisted=>{
+                self.orderbook.abandon_auction();
+                None
+            },
+            _=>None
+        };
+        self.status=status;
+        Ok((cancelled,uncross))
+    }
+
+    //trades from before the breaker was set never trip it
+    pub fn set_circuit_breaker(&mut self,config:Option<CircuitBreakerConfig>){
+        let last_trade_id=self.orderbook.trade_log().trade_id_index;
+        self.circuit_breaker=config.map(|config|CircuitBreaker { config, last_trade_id });
+    }
+}
\ No newline at end of file
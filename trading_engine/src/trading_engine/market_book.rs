@@ -0,0 +1,187 @@
+use orderbook::{
+    Allocation, BatchOperation, BatchResponse, BookChanges, CancelFilter, CustomError, DeleteResponse, Depth, FeeSchedule, FixedPointScale, LimitOrder, MarketOrder, MarketOrderResponse, ModifyOrderRequest, ModifyOrderResponse, OpenOrder, OrderHistory, Orderbook, PriceBandConfig, PublicTrade, TradeHistoryQuery, TradeHistoryResponse, TradeLog, Uncross, UserTrade
+};
+use rust_decimal::Decimal;
+use crate::trading_engine::types::{MarketBook, NumericMode, TradingEngineError};
+
+//runs the same code against whichever book the market keeps
+macro_rules! on_book{
+    ($book:expr,$orderbook:ident=>$body:expr)=>{
+        match $book{
+            MarketBook::Decimal($orderbook)=>$body,
+            MarketBook::FixedPoint($orderbook)=>$body
+        }
+    };
+}
+
+impl Default for MarketBook{
+    fn default()->Self{
+        MarketBook::Decimal(Orderbook::new())
+    }
+}
+
+impl MarketBook{
+    pub fn new(numeric_mode:NumericMode)->Result<MarketBook,TradingEngineError>{
+        match numeric_mode{
+            NumericMode::Decimal=>Ok(MarketBook::Decimal(Orderbook::new())),
+            NumericMode::FixedPoint{tick_size,lot_size}=>{
+                if tick_size<=Decimal::ZERO||lot_size<=Decimal::ZERO{
+                    return Err(TradingEngineError::InvalidTickOrLotSize)
+                }
+                Ok(MarketBook::FixedPoint(Orderbook::with_scale(FixedPointScale::new(tick_size, lot_size))))
+            }
+        }
+    }
+
+    pub fn numeric_mode(&self)->NumericMode{
+        match self{
+            MarketBook::Decimal(_)=>NumericMode::Decimal,
+            MarketBook::FixedPoint(orderbook)=>NumericMode::FixedPoint { tick_size:orderbook.scale.tick_size, lot_size:orderbook.scale.lot_size }
+        }
+    }
+
+    pub fn trade_log(&self)->&TradeLog{
+        on_book!(self,orderbook=>&orderbook.trade_log)
+    }
+
+    pub fn order_history(&self)->&OrderHistory{
+        on_book!(self,orderbook=>&orderbook.order_history)
+    }
+
+    pub fn in_auction(&self)->bool{
+        on_book!(self,orderbook=>orderbook.in_auction)
+    }
+
+    //leaves the auction without uncrossing it
+    pub fn abandon_auction(&mut self){
+        on_book!(self,orderbook=>orderbook.in_auction=false)
+    }
+
+    pub fn start_auction(&mut self){
+        on_book!(self,orderbook=>orderbook.start_auction())
+    }
+
+    pub fn end_auction(&mut self)->Option<Uncross>{
+        on_book!(self,orderbook=>orderbook.end_auction())
+    }
+
+    pub fn indicative_uncross(&self)->Option<Uncross>{
+        on_book!(self,orderbook=>orderbook.indicative_uncross())
+    }
+
+    pub fn add_limit_order(&mut self,order:LimitOrder)->OpenOrder{
+        on_book!(self,orderbook=>orderbook.add_limit_order(order))
+    }
+
+    pub fn place_limit_order(&mut self,order:LimitOrder)->Result<OpenOrder,CustomError>{
+        on_book!(self,orderbook=>orderbook.place_limit_order(order))
+    }
+
+    pub fn add_market_order(&mut self,order:MarketOrder)->MarketOrderResponse{
+        on_book!(self,orderbook=>orderbook.add_market_order(order))
+    }
+
+    pub fn delete_order(&mut self,order_id:u64)->Result<DeleteResponse,CustomError>{
+        on_book!(self,orderbook=>orderbook.delete_order(order_id))
+    }
+
+    pub fn delete_user_order(&mut self,user_id:u64,order_id:u64)->Result<DeleteResponse,CustomError>{
+        on_book!(self,orderbook=>orderbook.delete_user_order(user_id, order_id))
+    }
+
+    pub fn delete_order_by_client_order_id(&mut self,user_id:u64,client_order_id:&str)->Result<DeleteResponse,CustomError>{
+        on_book!(self,orderbook=>orderbook.delete_order_by_client_order_id(user_id, client_order_id))
+    }
+
+    pub fn modify_order(&mut self,order:ModifyOrderRequest)->Result<ModifyOrderResponse,CustomError>{
+        on_book!(self,orderbook=>orderbook.modify_order(order))
+    }
+
+    pub fn modify_user_order(&mut self,user_id:u64,order:ModifyOrderRequest)->Result<ModifyOrderResponse,CustomError>{
+        on_book!(self,orderbook=>orderbook.modify_user_order(user_id, order))
+    }
+
+    pub fn cancel_all(&mut self,filter:CancelFilter)->Vec<DeleteResponse>{
+        on_book!(self,orderbook=>orderbook.cancel_all(filter))
+    }
+
+    pub fn apply_batch(&mut self,operations:Vec<BatchOperation>,all_or_nothing:bool)->BatchResponse{
+        on_book!(self,orderbook=>orderbook.apply_batch(operations, all_or_nothing))
+    }
+
+    pub fn apply_user_batch(&mut self,user_id:u64,operations:Vec<BatchOperation>,all_or_nothing:bool)->BatchResponse{
+        on_book!(self,orderbook=>orderbook.apply_user_batch(user_id, operations, all_or_nothing))
+    }
+
+    pub fn get_order(&self,order_id:u64)->Result<OpenOrder,CustomError>{
+        on_book!(self,orderbook=>orderbook.get_order(order_id))
+    }
+
+    pub fn get_order_by_client_order_id(&self,user_id:u64,client_order_id:&str)->Result<OpenOrder,CustomError>{
+        on_book!(self,orderbook=>orderbook.get_order_by_client_order_id(user_id, client_order_id))
+    }
+
+    pub fn get_open_orders(&self,user_id:u64)->Vec<OpenOrder>{
+        on_book!(self,orderbook=>orderbook.get_open_orders(user_id))
+    }
+
+    pub fn get_order_history(&self,user_id:u64,limit:usize)->Vec<OpenOrder>{
+        on_book!(self,orderbook=>orderbook.get_order_history(user_id, limit))
+    }
+
+    pub fn get_depth(&self)->Depth{
+        on_book!(self,orderbook=>orderbook.get_depth())
+    }
+
+    pub fn get_best_bid(&self)->Option<Decimal>{
+        on_book!(self,orderbook=>orderbook.get_best_bid())
+    }
+
+    pub fn get_best_ask(&self)->Option<Decimal>{
+        on_book!(self,orderbook=>orderbook.get_best_ask())
+    }
+
+    pub fn mid_price(&self)->Option<Decimal>{
+        on_book!(self,orderbook=>orderbook.mid_price())
+    }
+
+    pub fn get_recent_trades(&self,limit:usize)->Vec<PublicTrade>{
+        on_book!(self,orderbook=>orderbook.get_recent_trades(limit))
+    }
+
+    pub fn get_trade_history(&self,query:TradeHistoryQuery)->TradeHistoryResponse<PublicTrade>{
+        on_book!(self,orderbook=>orderbook.get_trade_history(query))
+    }
+
+    pub fn get_user_trades(&self,user_id:u64,query:TradeHistoryQuery)->TradeHistoryResponse<UserTrade>{
+        on_book!(self,orderbook=>orderbook.get_user_trades(user_id, query))
+    }
+
+    pub fn set_price_band(&mut self,config:Option<PriceBandConfig>){
+        on_book!(self,orderbook=>orderbook.set_price_band(config))
+    }
+
+    pub fn fee_schedule(&self)->FeeSchedule{
+        on_book!(self,orderbook=>orderbook.fee_schedule.clone())
+    }
+
+    pub fn allocation(&self)->Allocation{
+        on_book!(self,orderbook=>orderbook.allocation.clone())
+    }
+
+    pub fn set_fee_schedule(&mut self,fee_schedule:FeeSchedule){
+        on_book!(self,orderbook=>orderbook.set_fee_schedule(fee_schedule))
+    }
+
+    pub fn set_allocation(&mut self,allocation:Allocation){
+        on_book!(self,orderbook=>orderbook.set_allocation(allocation))
+    }
+
+    pub fn track_changes(&mut self){
+        on_book!(self,orderbook=>orderbook.track_changes())
+    }
+
+    pub fn take_changes(&mut self)->BookChanges{
+        on_book!(self,orderbook=>orderbook.take_changes())
+    }
+}
\ No newline at end of file
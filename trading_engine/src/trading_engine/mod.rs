@@ -1,2 +1,9 @@
+#[allow(clippy::module_inception)]
 mod trading_engine;
-pub mod types;
+mod market;
+mod market_book;
+mod shard;
+pub mod ring;
+mod sequencer;
+pub mod snapshot;
+pub mod types;
\ No newline at end of file
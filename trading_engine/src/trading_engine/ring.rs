@@ -0,0 +1,194 @@
+use std::{
+    cell::UnsafeCell, hint, sync::{atomic::{AtomicBool, AtomicU64, Ordering}, Arc}, thread, time::Duration
+};
+
+//how long a consumer or a blocked producer sleeps once spinning and yielding did not help
+const IDLE_SLEEP:Duration=Duration::from_micros(50);
+
+//a bounded ring in the style of the lmax disruptor: one producer publishes by moving its cursor,
+//every consumer follows with a gate of its own and the producer never laps the slowest gate
+pub(crate) struct RingBuffer<T>{
+    slots: Box<[UnsafeCell<Option<T>>]>,
+    mask: u64,
+    //every sequence below it is published
+    cursor: AtomicU64,
+    //the next sequence each consumer reads, a dropped consumer's gate is parked at u64::MAX
+    gates: Box<[AtomicU64]>,
+    closed: AtomicBool
+}
+
+//a slot is only written once every gate moved past it and only read below the cursor,
+//consumers share a slot by reference only through `broadcast`, which requires `T: Sync`
+unsafe impl<T:Send> Sync for RingBuffer<T>{}
+
+//the single writer of a ring, dropping it lets the consumers drain and stop
+pub(crate) struct RingProducer<T>{
+    ring: Arc<RingBuffer<T>>,
+    next: u64
+}
+
+//the only consumer of a ring, it moves every value out
+pub(crate) struct RingReceiver<T>{
+    ring: Arc<RingBuffer<T>>,
+    next: u64
+}
+
+//one of several consumers of a ring, each of them sees every value
+pub struct RingSubscriber<T>{
+    ring: Arc<RingBuffer<T>>,
+    gate: usize,
+    next: u64
+}
+
+//spins first, then yields, then sleeps, so an idle ring does not keep a core busy
+#[derive(Default)]
+struct Backoff{
+    step: u32
+}
+
+impl Backoff{
+    fn snooze(&mut self){
+        match self.step{
+            0..64=>hint::spin_loop(),
+            64..128=>thread::yield_now(),
+            _=>thread::sleep(IDLE_SLEEP)
+        }
+        self.step=self.step.saturating_add(1);
+    }
+}
+
+impl<T> RingBuffer<T>{
+    //the capacity is rounded up to a power of two so a sequence maps to its slot with a mask
+    fn new(capacity:usize,consumers:usize)->Arc<RingBuffer<T>>{
+        let capacity=capacity.max(1).next_power_of_two();
+        Arc::new(RingBuffer{
+            slots: (0..capacity).map(|_|UnsafeCell::new(None)).collect(),
+            mask: capacity as u64-1,
+            cursor: AtomicU64::new(0),
+            gates: (0..consumers).map(|_|AtomicU64::new(0)).collect(),
+            closed: AtomicBool::new(false)
+        })
+    }
+
+    fn slot(&self,sequence:u64)->*mut Option<T>{
+        self.slots[(sequence&self.mask) as usize].get()
+    }
+
+    fn slowest_gate(&self)->u64{
+        self.gates.iter().map(|gate|gate.load(Ordering::Acquire)).min().unwrap_or(u64::MAX)
+    }
+
+    //whether `next` is published, false right away without `block` and once the producer is gone and nothing is left
+    fn wait_for(&self,next:u64,block:bool)->bool{
+        let mut backoff=Backoff::default();
+        loop{
+            if next<self.cursor.load(Ordering::Acquire){
+                return true
+            }
+            //the producer publishes before it closes, so a closed ring shows its last value
+            if self.closed.load(Ordering::Acquire){
+                return next<self.cursor.load(Ordering::Acquire)
+            }
+            if !block{
+                return false
+            }
+            backoff.snooze();
+        }
+    }
+
+    //a ring whose one consumer takes every value out
+    pub(crate) fn single(capacity:usize)->(RingProducer<T>,RingReceiver<T>){
+        let ring=RingBuffer::new(capacity, 1);
+        (RingProducer { ring:ring.clone(), next:0 },RingReceiver { ring, next:0 })
+    }
+}
+
+impl<T:Clone+Sync> RingBuffer<T>{
+    //a ring every subscriber reads in full, the producer waits for the slowest of them
+    pub(crate) fn broadcast(capacity:usize,subscribers:usize)->(RingProducer<T>,Vec<RingSubscriber<T>>){
+        let ring=RingBuffer::new(capacity, subscribers);
+        let subscribers=(0..subscribers).map(|gate|RingSubscriber { ring:ring.clone(), gate, next:0 }).collect();
+        (RingProducer { ring, next:0 },subscribers)
+    }
+}
+
+impl<T> RingProducer<T>{
+    //waits while the slowest consumer is a whole ring behind, then publishes the value
+    pub(crate) fn push(&mut self,value:T){
+        let capacity=self.ring.mask+1;
+        let mut backoff=Backoff::default();
+        while self.next.saturating_sub(self.ring.slowest_gate())>=capacity{
+            backoff.snooze();
+        }
+        unsafe{
+            *self.ring.slot(self.next)=Some(value);
+        }
+        self.next+=1;
+        self.ring.cursor.store(self.next, Ordering::Release);
+    }
+}
+
+impl<T> Drop for RingProducer<T>{
+    fn drop(&mut self){
+        self.ring.closed.store(true, Ordering::Release);
+    }
+}
+
+impl<T> RingReceiver<T>{
+    fn take(&mut self)->Option<T>{
+        let value=unsafe{ (*self.ring.slot(self.next)).take() };
+        self.next+=1;
+        self.ring.gates[0].store(self.next, Ordering::Release);
+        value
+    }
+
+    //waits for the next value, None once the producer is gone and every value was read
+    pub(crate) fn recv(&mut self)->Option<T>{
+        if !self.ring.wait_for(self.next, true){
+            return None
+        }
+        self.take()
+    }
+
+    //whether every value published so far was read
+    pub(crate) fn is_empty(&self)->bool{
+        !self.ring.wait_for(self.next, false)
+    }
+}
+
+impl<T> Drop for RingReceiver<T>{
+    fn drop(&mut self){
+        self.ring.gates[0].store(u64::MAX, Ordering::Release);
+    }
+}
+
+impl<T:Clone> RingSubscriber<T>{
+    fn read(&mut self)->Option<T>{
+        let value=unsafe{ (*self.ring.slot(self.next)).clone() };
+        self.next+=1;
+        self.ring.gates[self.gate].store(self.next, Ordering::Release);
+        value
+    }
+
+    //the next value if one is published already
+    pub fn try_recv(&mut self)->Option<T>{
+        if !self.ring.wait_for(self.next, false){
+            return None
+        }
+        self.read()
+    }
+
+    //waits for the next value, None once the producer is gone and every value was read
+    pub fn recv(&mut self)->Option<T>{
+        if !self.ring.wait_for(self.next, true){
+            return None
+        }
+        self.read()
+    }
+}
+
+impl<T> Drop for RingSubscriber<T>{
+    fn drop(&mut self){
+        self.ring.gates[self.gate].store(u64::MAX, Ordering::Release);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,82 @@
+use std::{collections::BTreeMap, panic::{self, AssertUnwindSafe}, thread};
+
+use tokio::sync::mpsc::{self, UnboundedSender};
+use crate::trading_engine::ring::{RingBuffer, RingProducer, RingSubscriber};
+use crate::trading_engine::snapshot::SnapshotPublisher;
+use crate::trading_engine::types::{EngineEvent, Market, MarketCommand, SequencedEvent, SequencerInput, TradingPair};
+
+//starts the sequencer and the matching thread behind it, the subscribers each receive every event published
+pub(crate) fn spawn_sequencer(ring_capacity:usize,subscribers:usize)->(UnboundedSender<SequencerInput>,Vec<RingSubscriber<SequencedEvent>>){
+    let (sender,mut receiver)=mpsc::unbounded_channel::<SequencerInput>();
+    let (mut commands,mut pending)=RingBuffer::<(u64,SequencerInput)>::single(ring_capacity);
+    let (mut events,subscribers)=RingBuffer::broadcast(ring_capacity, subscribers);
+    //numbers the commands of every caller in the order they arrive
+    thread::Builder::new()
+        .name("sequencer".to_string())
+        .spawn(move||{
+            let mut sequence=0;
+            while let Some(input)=receiver.blocking_recv(){
+                sequence+=1;
+                commands.push((sequence,input));
+            }
+        })
+        .expect("failed to spawn sequencer thread");
+    //the one thread every market matches on, strictly in sequence
+    thread::Builder::new()
+        .name("matching".to_string())
+        .spawn(move||{
+            let mut markets=BTreeMap::new();
+            while let Some((sequence,input))=pending.recv(){
+                apply(&mut markets, sequence, input, &mut events);
+                //every market publishes what it held back as soon as the sequencer has nothing queued
+                if pending.is_empty(){
+                    for (market,snapshots) in markets.values_mut(){
+                        snapshots.flush(market);
+                    }
+                }
+            }
+        })
+        .expect("failed to spawn matching thread");
+    (sender,subscribers)
+}
+
+fn apply(markets:&mut BTreeMap<TradingPair,(Market,SnapshotPublisher)>,sequence:u64,input:SequencerInput,events:&mut RingProducer<SequencedEvent>){
+    match input{
+        SequencerInput::Create(trading_pair,market,snapshots)=>{
+            events.push(SequencedEvent { sequence, trading_pair:trading_pair.clone(), event:EngineEvent::MarketCreated(market.matching_mode()) });
+            markets.insert(trading_pair, (*market,*snapshots));
+        },
+        SequencerInput::Run(trading_pair,command)=>run(markets, sequence, trading_pair, command, events),
+        SequencerInput::RunEvery(commands)=>{
+            for (trading_pair,command) in commands{
+                run(markets, sequence, trading_pair, command, events);
+            }
+        }
+    }
+}
+
+fn run(markets:&mut BTreeMap<TradingPair,(Market,SnapshotPublisher)>,sequence:u64,trading_pair:TradingPair,command:MarketCommand,events:&mut RingProducer<SequencedEvent>){
+    let mut publish=|event:EngineEvent|events.push(SequencedEvent { sequence, trading_pair:trading_pair.clone(), event });
+    //a market that panicked was dropped, its commands are dropped too and their callers see it unavailable
+    let Some((market,snapshots))=markets.get_mut(&trading_pair) else{
+        return
+    };
+    let (last_trade_id,top_of_book,status)=(market.orderbook.trade_log().trade_id_index,market.top_of_book(),market.status);
+    if panic::catch_unwind(AssertUnwindSafe(||command(market))).is_err(){
+        markets.remove(&trading_pair);
+        return
+    }
+    snapshots.record(market, false);
+    let mut trades:Vec<_>=market.orderbook.trade_log().trades.iter().rev().take_while(|t|t.trade_id>last_trade_id).cloned().collect();
+    trades.reverse();
+    for trade in trades{
+        publish(EngineEvent::Trade(trade));
+    }
+    let (best_bid,best_ask)=market.top_of_book();
+    if (best_bid,best_ask)!=top_of_book{
+        publish(EngineEvent::TopOfBook { best_bid, best_ask });
+    }
+    if market.status!=status{
+        publish(EngineEvent::StatusChanged(market.status));
+    }
+}
\ No newline at end of file
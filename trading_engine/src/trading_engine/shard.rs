@@ -0,0 +1,110 @@
+use std::{panic::{self, AssertUnwindSafe}, sync::{Arc, Condvar, Mutex, PoisonError}, thread};
+
+use tokio::sync::{mpsc::{self, UnboundedSender}, oneshot};
+use crate::trading_engine::snapshot::SnapshotPublisher;
+use crate::trading_engine::types::{BookSnapshot, Gate, GatePlace, Market, MarketCommand, MarketShard, MatchingMode, Route, SequencerInput, TradingEngineError, TradingPair};
+
+impl MarketShard{
+    //moves the market onto a thread of its own, which stops once every sender is dropped
+    pub(crate) fn spawn(trading_pair:&TradingPair,mut market:Market)->MarketShard{
+        let matching_mode=market.matching_mode();
+        let mut snapshots=SnapshotPublisher::new(&mut market);
+        let snapshot=snapshots.cell();
+        let (sender,mut receiver)=mpsc::unbounded_channel::<MarketCommand>();
+        thread::Builder::new()
+            .name(format!("market-{}-{}",trading_pair.base,trading_pair.quote))
+            .spawn(move||{
+                while let Some(command)=receiver.blocking_recv(){
+                    //as in the sequencer, a market that panicked may be half way through a change, so it is dropped rather than carried on
+                    if panic::catch_unwind(AssertUnwindSafe(||command(&mut market))).is_err(){
+                        break
+                    }
+                    snapshots.record(&mut market, receiver.is_empty());
+                }
+            })
+            .expect("failed to spawn market thread");
+        MarketShard { route:Route::Thread(sender), matching_mode, snapshot }
+    }
+
+    //hands the market to the sequencer, it is created on the matching thread before any later command reaches it
+    pub(crate) fn sequenced(sender:&UnboundedSender<SequencerInput>,trading_pair:&TradingPair,mut market:Market)->MarketShard{
+        let matching_mode=market.matching_mode();
+        let snapshots=SnapshotPublisher::new(&mut market);
+        let snapshot=snapshots.cell();
+        let _=sender.send(SequencerInput::Create(trading_pair.clone(), Box::new(market), Box::new(snapshots)));
+        MarketShard { route:Route::Sequencer { sender:sender.clone(), trading_pair:trading_pair.clone() }, matching_mode, snapshot }
+    }
+
+    pub(crate) fn matching_mode(&self)->MatchingMode{
+        self.matching_mode
+    }
+
+    //None once the market stopped
+    pub(crate) fn snapshot(&self)->Option<Arc<BookSnapshot>>{
+        self.snapshot.load_full()
+    }
+
+    //queues the command behind the ones already sent to the market, the reply arrives once it ran
+    pub(crate) fn send<R:Send+'static>(&self,command:impl FnOnce(&mut Market)->R+Send+'static)->oneshot::Receiver<R>{
+        let (command,receiver)=with_reply(command);
+        //a stopped market drops the command along with its reply sender, which the receiver reports
+        match &self.route{
+            Route::Thread(sender)=>{
+                let _=sender.send(command);
+            },
+            Route::Sequencer{sender,trading_pair}=>{
+                let _=sender.send(SequencerInput::Run(trading_pair.clone(), command));
+            }
+        }
+        receiver
+    }
+}
+
+//boxes the command so it answers through the receiver once it ran
+pub(crate) fn with_reply<R:Send+'static>(command:impl FnOnce(&mut Market)->R+Send+'static)->(MarketCommand,oneshot::Receiver<R>){
+    let (reply,receiver)=oneshot::channel();
+    let command:MarketCommand=Box::new(move|market:&mut Market|{
+        let _=reply.send(command(market));
+    });
+    (command,receiver)
+}
+
+//waits for a market to answer a command sent with `MarketShard::send`
+pub(crate) async fn reply<R>(receiver:oneshot::Receiver<R>)->Result<R,TradingEngineError>{
+    receiver.await.map_err(|_|TradingEngineError::MarketUnavailable)
+}
+
+impl Gate{
+    //one place per market, the gate opens once every place arrived or was dropped
+    pub(crate) fn places(count:usize)->Vec<GatePlace>{
+        let gate=Arc::new(Gate { remaining:Mutex::new(count), opened:Condvar::new() });
+        (0..count).map(|_|GatePlace { gate:gate.clone(), arrived:false }).collect()
+    }
+
+    fn arrive(&self){
+        let mut remaining=self.remaining.lock().unwrap_or_else(PoisonError::into_inner);
+        *remaining-=1;
+        if *remaining==0{
+            self.opened.notify_all();
+        }
+    }
+}
+
+impl GatePlace{
+    //blocks the market's thread until every other market of the sweep got here too
+    pub(crate) fn wait(mut self){
+        self.arrived=true;
+        self.gate.arrive();
+        let remaining=self.gate.remaining.lock().unwrap_or_else(PoisonError::into_inner);
+        let _remaining=self.gate.opened.wait_while(remaining, |remaining|*remaining>0).unwrap_or_else(PoisonError::into_inner);
+    }
+}
+
+//a market that stopped drops the commands queued for it along with their places, so it never holds the others up
+impl Drop for GatePlace{
+    fn drop(&mut self){
+        if !self.arrived{
+            self.gate.arrive();
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,180 @@
+use std::{cmp::Reverse, collections::VecDeque, sync::Arc, time::{Duration, Instant}};
+
+use arc_swap::ArcSwapOption;
+use orderbook::{current_timestamp, BookChanges, Depth, OpenOrder, Order, MAX_ORDER_HISTORY_SIZE};
+use rust_decimal::Decimal;
+use crate::trading_engine::types::{BookSnapshot, Market, Staleness, TradingEngineError};
+
+//a busy market publishes at least this often, an idle one as soon as its queue runs dry
+pub const MAX_SNAPSHOT_STALENESS:Duration=Duration::from_millis(10);
+
+//where a market's thread leaves its latest snapshot, emptied once the market stops
+pub(crate) type SnapshotCell=ArcSwapOption<BookSnapshot>;
+
+//kept next to the market by the thread matching it
+pub(crate) struct SnapshotPublisher{
+    cell: Arc<SnapshotCell>,
+    version: u64,
+    published_at: Instant,
+    //whether a command ran since the last snapshot
+    changed: bool,
+    //the book as last published, brought up to date with only what its commands changed
+    bids: imbl::OrdMap<Reverse<Decimal>,Order>,
+    asks: imbl::OrdMap<Decimal,Order>,
+    open_orders: imbl::HashMap<u64,OpenOrder>,
+    open_client_order_ids: imbl::HashMap<(u64,String),u64>,
+    //how much of the order history the closed orders below have caught up with
+    archived_total: u64,
+    closed_order_ids: VecDeque<u64>,
+    closed_orders: imbl::HashMap<u64,OpenOrder>,
+    closed_client_order_ids: imbl::HashMap<(u64,String),u64>
+}
+
+impl SnapshotPublisher{
+    //publishes the market as it is, so it can be read before any command reached it,
+    //from then on its book tracks what each command changes
+    pub(crate) fn new(market:&mut Market)->SnapshotPublisher{
+        market.orderbook.track_changes();
+        let mut publisher=SnapshotPublisher{
+            cell: Arc::new(ArcSwapOption::empty()),
+            version: 0,
+            published_at: Instant::now(),
+            changed: false,
+            bids: imbl::OrdMap::new(),
+            asks: imbl::OrdMap::new(),
+            open_orders: imbl::HashMap::new(),
+            open_client_order_ids: imbl::HashMap::new(),
+            archived_total: 0,
+            closed_order_ids: VecDeque::new(),
+            closed_orders: imbl::HashMap::new(),
+            closed_client_order_ids: imbl::HashMap::new()
+        };
+        publisher.publish(market);
+        publisher
+    }
+
+    pub(crate) fn cell(&self)->Arc<SnapshotCell>{
+        self.cell.clone()
+    }
+
+    //called after every command, publishes once nothing else is queued or the last snapshot is due
+    pub(crate) fn record(&mut self,market:&mut Market,idle:bool){
+        self.changed=true;
+        if idle||self.published_at.elapsed()>=MAX_SNAPSHOT_STALENESS{
+            self.publish(market);
+        }
+    }
+
+    //publishes whatever `record` held back
+    pub(crate) fn flush(&mut self,market:&mut Market){
+        if self.changed{
+            self.publish(market);
+        }
+    }
+
+    fn publish(&mut self,market:&mut Market){
+        self.apply(market.orderbook.take_changes());
+        let orderbook=&market.orderbook;
+        for order in orderbook.order_history().archived_since(self.archived_total){
+            if let Some(client_order_id)=&order.client_order_id{
+                self.closed_client_order_ids.insert((order.user_id,client_order_id.clone()), order.order_id);
+            }
+            self.closed_order_ids.push_back(order.order_id);
+            self.closed_orders.insert(order.order_id, order.clone());
+        }
+        self.archived_total=orderbook.order_history().archived_total;
+        //forgets closed orders along with the order history
+        while self.closed_order_ids.len()>MAX_ORDER_HISTORY_SIZE
+            && let Some(order_id)=self.closed_order_ids.pop_front(){
+            if let Some(order)=self.closed_orders.remove(&order_id){
+                forget_client_order_id(&mut self.closed_client_order_ids, order);
+            }
+        }
+        self.version+=1;
+        self.published_at=Instant::now();
+        self.changed=false;
+        self.cell.store(Some(Arc::new(BookSnapshot {
+            staleness: Staleness { version:self.version, taken_at:current_timestamp(), max_staleness_ms:MAX_SNAPSHOT_STALENESS.as_millis() as u64 },
+            mid_price: orderbook.mid_price(),
+            bids: self.bids.clone(),
+            asks: self.asks.clone(),
+            open_orders: self.open_orders.clone(),
+            open_client_order_ids: self.open_client_order_ids.clone(),
+            closed_orders: self.closed_orders.clone(),
+            closed_client_order_ids: self.closed_client_order_ids.clone()
+        })));
+    }
+
+    fn apply(&mut self,changes:BookChanges){
+        for (order_id,order) in changes.orders{
+            match order{
+                Some(order)=>{
+                    if !self.open_orders.contains_key(&order_id)
+                        && let Some(client_order_id)=&order.client_order_id{
+                        self.open_client_order_ids.insert((order.user_id,client_order_id.clone()), order_id);
+                    }
+                    self.open_orders.insert(order_id, order);
+                },
+                None=>if let Some(order)=self.open_orders.remove(&order_id){
+                    forget_client_order_id(&mut self.open_client_order_ids, order);
+                }
+            }
+        }
+        for (price,level) in changes.bids{
+            match level{
+                Some(level)=>self.bids.insert(Reverse(price), level),
+                None=>self.bids.remove(&Reverse(price))
+            };
+        }
+        for (price,level) in changes.asks{
+            match level{
+                Some(level)=>self.asks.insert(price, level),
+                None=>self.asks.remove(&price)
+            };
+        }
+    }
+}
+
+//unless a later order reused the client order id
+fn forget_client_order_id(client_order_ids:&mut imbl::HashMap<(u64,String),u64>,order:OpenOrder){
+    if let OpenOrder{user_id,order_id,client_order_id:Some(client_order_id),..}=order{
+        let key=(user_id,client_order_id);
+        if client_order_ids.get(&key)==Some(&order_id){
+            client_order_ids.remove(&key);
+        }
+    }
+}
+
+//a market that stopped, panicking or not, leaves no snapshot behind that would never be updated again
+impl Drop for SnapshotPublisher{
+    fn drop(&mut self){
+        self.cell.store(None);
+    }
+}
+
+impl BookSnapshot{
+    //the best `levels` prices of each side, all of them without a limit
+    pub fn depth(&self,levels:Option<usize>)->Depth{
+        let levels=levels.unwrap_or(usize::MAX);
+        Depth {
+            bids: self.bids.values().take(levels).cloned().collect(),
+            asks: self.asks.values().take(levels).cloned().collect()
+        }
+    }
+
+    //same answer as `Orderbook::get_order` at the time of the snapshot
+    pub fn get_order(&self,order_id:u64)->Result<OpenOrder,TradingEngineError>{
+        self.open_orders.get(&order_id)
+            .or_else(||self.closed_orders.get(&order_id))
+            .cloned()
+            .ok_or(TradingEngineError::OrderDoesNotExist)
+    }
+
+    pub fn get_order_by_client_order_id(&self,user_id:u64,client_order_id:&str)->Result<OpenOrder,TradingEngineError>{
+        let key=(user_id,client_order_id.to_string());
+        match self.open_client_order_ids.get(&key).or_else(||self.closed_client_order_ids.get(&key)){
+            Some(order_id)=>self.get_order(*order_id),
+            None=>Err(TradingEngineError::OrderDoesNotExist)
+        }
+    }
+}
\ No newline at end of file
@@ -1,119 +1,320 @@
-use std::collections::{BTreeMap};
+use std::collections::BTreeMap;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use orderbook::{
-    DeleteResponse, Depth, ErrorResponse, LimitOrder, MarketOrder, MarketOrderResponse, ModifyOrderRequest, ModifyOrderResponse, OpenOrder, Orderbook
+    Allocation, BatchOperation, BatchResponse, CancelFilter, DeleteResponse, Depth, FeeSchedule, LimitOrder, MarketOrder, MarketOrderResponse, ModifyOrderRequest, ModifyOrderResponse, OpenOrder, PriceBandConfig, PublicTrade, TradeHistoryQuery, TradeHistoryResponse, Uncross, UserTrade
 };
 use rust_decimal::{Decimal};
-use crate::trading_engine::types::{Markets, TradingEngine, TradingEngineError, TradingPair};
+use tokio::sync::oneshot;
+use crate::trading_engine::ring::RingSubscriber;
+use crate::trading_engine::sequencer::spawn_sequencer;
+use crate::trading_engine::shard::{reply, with_reply};
+use crate::trading_engine::types::{BatchClearing, BookSnapshot, CircuitBreakerConfig, EngineMode, Gate, Market, MarketCancellations, MarketShard, MarketStatus, MarketStatusChange, Markets, MatchingMode, NumericMode, SequencedEvent, SequencerInput, TradingEngine, TradingEngineError, TradingPair};
 
 
 
+impl Default for TradingEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TradingEngine{
     pub fn new()->TradingEngine{
-        TradingEngine { orderbooks:BTreeMap::new() }
+        TradingEngine { shards:RwLock::new(BTreeMap::new()), sequencer:None }
     }
 
-    fn check_if_market_exists(&self,trading_pair:TradingPair)->bool{
-        let engine=&self.orderbooks;
-        engine.contains_key(&trading_pair)
+    //every command is numbered by one sequencer and all markets match on one thread in that order,
+    //each subscriber receives every event published and the matching thread waits for the slowest one
+    pub fn sequenced(ring_capacity:usize,subscribers:usize)->(TradingEngine,Vec<RingSubscriber<SequencedEvent>>){
+        let (sequencer,subscribers)=spawn_sequencer(ring_capacity, subscribers);
+        (TradingEngine { shards:RwLock::new(BTreeMap::new()), sequencer:Some(sequencer) },subscribers)
     }
 
-    fn add_limit_order_for_trading_pair(&mut self,trading_pair:TradingPair,order: LimitOrder)->OpenOrder{
-        self.orderbooks.entry(trading_pair).or_insert(Orderbook::new()).add_limit_order(order)
+    pub fn mode(&self)->EngineMode{
+        match self.sequencer{
+            Some(_)=>EngineMode::Sequenced,
+            None=>EngineMode::Sharded
+        }
     }
 
-    fn add_market_order_for_trading_pair(&mut self,trading_pair:TradingPair,order:MarketOrder)->MarketOrderResponse{
-        self.orderbooks.entry(trading_pair).or_insert(Orderbook::new()).add_market_order(order)
+    //the map is never left half updated, so a thread that panicked holding the lock does not stop every other caller
+    fn shards(&self)->RwLockReadGuard<'_,BTreeMap<TradingPair,MarketShard>>{
+        self.shards.read().unwrap_or_else(PoisonError::into_inner)
     }
 
-    fn get_depth_for_trading_pair(&mut self,trading_pair:TradingPair)->Depth{
-        self.orderbooks.entry(trading_pair).or_insert(Orderbook::new()).get_depth()
+    fn shards_mut(&self)->RwLockWriteGuard<'_,BTreeMap<TradingPair,MarketShard>>{
+        self.shards.write().unwrap_or_else(PoisonError::into_inner)
     }
 
-    fn delete_order_for_trading_pair(&mut self,trading_pair:TradingPair,order_id:u64)->Result<DeleteResponse,ErrorResponse>{
-        self.orderbooks.entry(trading_pair).or_insert(Orderbook::new()).delete_order(order_id)
+    fn send<R:Send+'static>(&self,trading_pair:&TradingPair,command:impl FnOnce(&mut Market)->R+Send+'static)->Result<oneshot::Receiver<R>,TradingEngineError>{
+        let shards=self.shards();
+        let shard=shards.get(trading_pair).ok_or(TradingEngineError::TradingPairDoesNotExist)?;
+        Ok(shard.send(command))
     }
 
-    fn modify_order_for_trading_pair(&mut self,trading_pair:TradingPair,order:ModifyOrderRequest)->Result<ModifyOrderResponse,ErrorResponse>{
-        self.orderbooks.entry(trading_pair).or_insert(Orderbook::new()).modify_order(order)
+    //runs `command` on the thread matching the market and waits for its answer without blocking the caller's thread
+    pub async fn on_market<R:Send+'static>(&self,trading_pair:&TradingPair,command:impl FnOnce(&mut Market)->R+Send+'static)->Result<R,TradingEngineError>{
+        let receiver=self.send(trading_pair, command)?;
+        reply(receiver).await
     }
-    
-    fn get_order_by_id_for_trading_pair(&mut self,trading_pair:TradingPair,order_id:u64)->Result<OpenOrder,ErrorResponse>{
-        self.orderbooks.entry(trading_pair).or_insert(Orderbook::new()).get_order(order_id)
+
+    //the market as its thread last published it, read without waiting on that thread
+    pub fn snapshot(&self,trading_pair:&TradingPair)->Result<Arc<BookSnapshot>,TradingEngineError>{
+        let shards=self.shards();
+        let shard=shards.get(trading_pair).ok_or(TradingEngineError::TradingPairDoesNotExist)?;
+        shard.snapshot().ok_or(TradingEngineError::MarketUnavailable)
     }
 
-    fn get_mid_price_for_trading_pair(&mut self,trading_pair:TradingPair)->Option<Decimal>{
-        self.orderbooks.entry(trading_pair).or_insert(Orderbook::new()).mid_price()
+    //sends `command` to every market before waiting on any, so they all run it at the same time
+    async fn on_every_market<R:Send+'static>(&self,command:impl Fn(&mut Market)->R+Clone+Send+'static)->Vec<(TradingPair,R)>{
+        let receivers:Vec<_>={
+            let shards=self.shards();
+            shards.iter().map(|(trading_pair,shard)|(trading_pair.clone(),shard.send(command.clone()))).collect()
+        };
+        collect_replies(receivers).await
     }
 
-    fn _get_markets(&self)->Markets{
-        let keys=self.orderbooks.keys();
-        let mut vec=Vec::<TradingPair>::new();
-        for key in keys{
-            vec.push(key.to_owned());
-        }
-        Markets::new(vec)
+    //like `on_every_market`, but every market runs `command` at the same cut: nothing else runs on any of them
+    //from the first market running it until the last one did. sharded markets wait for each other at a gate before
+    //and after it, sequenced ones already share a thread and get the commands under one sequence number
+    async fn on_every_market_at_once<R:Send+'static>(&self,command:impl Fn(&mut Market)->R+Clone+Send+'static)->Vec<(TradingPair,R)>{
+        let receivers:Vec<_>={
+            //held exclusively so two sweeps reach every market in the same order, or each could wait at its gate
+            //for a market queued behind the other
+            let shards=self.shards_mut();
+            match &self.sequencer{
+                Some(sequencer)=>{
+                    let (commands,receivers):(Vec<_>,Vec<_>)=shards.keys().map(|trading_pair|{
+                        let (command,receiver)=with_reply(command.clone());
+                        ((trading_pair.clone(),command),(trading_pair.clone(),receiver))
+                    }).unzip();
+                    let _=sequencer.send(SequencerInput::RunEvery(commands));
+                    receivers
+                },
+                None=>{
+                    let places=Gate::places(shards.len()).into_iter().zip(Gate::places(shards.len()));
+                    shards.iter().zip(places).map(|((trading_pair,shard),(paused,done))|{
+                        let command=command.clone();
+                        (trading_pair.clone(),shard.send(move|market|{
+                            paused.wait();
+                            let r=command(market);
+                            done.wait();
+                            r
+                        }))
+                    }).collect()
+                }
+            }
+        };
+        collect_replies(receivers).await
+    }
+
+    pub async fn get_markets(&self)->Markets{
+        let statuses=self.on_every_market(|market|market.status).await;
+        Markets::new(statuses.into_iter().filter(|(_,status)|*status!=MarketStatus::Delisted).map(|(trading_pair,_)|trading_pair).collect())
     }
 
-    pub fn get_markets(&self)->Markets{
-        self._get_markets()
+    pub fn create_market(&self,trading_pair:TradingPair)->Result<(),TradingEngineError>{
+        self.create_market_with_matching_mode(trading_pair, MatchingMode::Continuous)
     }
 
+    //a batch auction market keeps its book in auction and clears it every interval through `clear_due_batch_auctions`
+    pub fn create_market_with_matching_mode(&self,trading_pair:TradingPair,matching_mode:MatchingMode)->Result<(),TradingEngineError>{
+        self.create_market_with_modes(trading_pair, matching_mode, NumericMode::Decimal)
+    }
 
-    pub fn create_market(&mut self,trading_pair:TradingPair)->Result<(),TradingEngineError>{
-        if self.check_if_market_exists(trading_pair.clone()){
+    //a fixed point market matches in whole ticks and lots of the sizes given
+    pub fn create_market_with_modes(&self,trading_pair:TradingPair,matching_mode:MatchingMode,numeric_mode:NumericMode)->Result<(),TradingEngineError>{
+        let mut shards=self.shards_mut();
+        if shards.contains_key(&trading_pair){
             return Err(TradingEngineError::TradingPairAlreadyExists)
         }
-        self.orderbooks.insert(trading_pair, Orderbook::new());
+        let market=Market::new(matching_mode, numeric_mode)?;
+        let shard=match &self.sequencer{
+            Some(sequencer)=>MarketShard::sequenced(sequencer, &trading_pair, market),
+            None=>MarketShard::spawn(&trading_pair, market)
+        };
+        shards.insert(trading_pair, shard);
         Ok(())
     }
 
-    pub fn add_limit_order_into_market(&mut self,trading_pair:TradingPair,order: LimitOrder)->Result<OpenOrder,TradingEngineError>{
-        if !self.check_if_market_exists(trading_pair.clone()){
-            return Err(TradingEngineError::TradingPairDoesNotExist)
-        }
-        Ok(self.add_limit_order_for_trading_pair(trading_pair, order))
+    pub fn get_matching_mode(&self,trading_pair:&TradingPair)->Result<MatchingMode,TradingEngineError>{
+        let shards=self.shards();
+        shards.get(trading_pair).map(|shard|shard.matching_mode()).ok_or(TradingEngineError::TradingPairDoesNotExist)
     }
 
-    pub fn add_market_order_into_market(&mut self,trading_pair:TradingPair,order:MarketOrder)->Result<MarketOrderResponse,TradingEngineError>{
-        if !self.check_if_market_exists(trading_pair.clone()){
-            return Err(TradingEngineError::TradingPairDoesNotExist)
+    //clears every open batch auction market whose interval has run out at `now` and returns the ones that traded
+    pub async fn clear_due_batch_auctions(&self,now:u64)->Vec<BatchClearing>{
+        let receivers:Vec<_>={
+            let shards=self.shards();
+            shards
+                .iter()
+                .filter(|(_,shard)|shard.matching_mode()!=MatchingMode::Continuous)
+                .map(|(trading_pair,shard)|(trading_pair.clone(),shard.send(move|market|market.clear_batch_auction_if_due(now))))
+                .collect()
+        };
+        let mut clearings=Vec::new();
+        for (trading_pair,receiver) in receivers{
+            if let Ok(Some(uncross))=reply(receiver).await{
+                clearings.push(BatchClearing::new(trading_pair, uncross));
+            }
         }
-        Ok(self.add_market_order_for_trading_pair(trading_pair, order))
+        clearings
     }
 
-    pub fn get_market_depth(&mut self,trading_pair:TradingPair)->Result<Depth,TradingEngineError>{
-        if !self.check_if_market_exists(trading_pair.clone()){
-            return Err(TradingEngineError::TradingPairDoesNotExist)
-        }
-        Ok(self.get_depth_for_trading_pair(trading_pair))
+    pub async fn add_limit_order_into_market(&self,trading_pair:TradingPair,order: LimitOrder)->Result<OpenOrder,TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.add_limit_order(order)).await?
     }
 
-    pub fn delete_order_for_market(&mut self,trading_pair:TradingPair,order_id:u64)->Result<Result<DeleteResponse,ErrorResponse>,TradingEngineError>{
-        if !self.check_if_market_exists(trading_pair.clone()){
-            return Err(TradingEngineError::TradingPairDoesNotExist)
-        }
-        Ok(self.delete_order_for_trading_pair(trading_pair, order_id))
+    //an order the book would reject comes back as the error instead of as a rejected order
+    pub async fn place_limit_order_into_market(&self,trading_pair:TradingPair,order: LimitOrder)->Result<OpenOrder,TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.place_limit_order(order)).await?
     }
 
-    pub fn modify_order_for_market(&mut self,trading_pair:TradingPair,order:ModifyOrderRequest)->Result<Result<ModifyOrderResponse,ErrorResponse>,TradingEngineError>{
-        if !self.check_if_market_exists(trading_pair.clone()){
-            return Err(TradingEngineError::TradingPairDoesNotExist)
-        }
-        Ok(self.modify_order_for_trading_pair(trading_pair, order))
+    pub async fn add_market_order_into_market(&self,trading_pair:TradingPair,order:MarketOrder)->Result<MarketOrderResponse,TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.add_market_order(order)).await?
     }
 
-    pub fn get_order_by_id_for_market(&mut self,trading_pair:TradingPair,order_id:u64)->Result<Result<OpenOrder,ErrorResponse>,TradingEngineError>{
-        if !self.check_if_market_exists(trading_pair.clone()){
-            return Err(TradingEngineError::TradingPairDoesNotExist)
-        }
-        Ok(self.get_order_by_id_for_trading_pair(trading_pair, order_id))
+    pub async fn get_market_depth(&self,trading_pair:TradingPair)->Result<Depth,TradingEngineError>{
+        self.on_market(&trading_pair, |market|market.orderbook.get_depth()).await
     }
 
-    pub fn get_mid_price_for_market(&mut self,trading_pair:TradingPair)->Result<Option<Decimal>,TradingEngineError>{
-        if !self.check_if_market_exists(trading_pair.clone()){
-            return Err(TradingEngineError::TradingPairDoesNotExist)
-        }
-        Ok(self.get_mid_price_for_trading_pair(trading_pair))
+    pub async fn delete_order_for_market(&self,trading_pair:TradingPair,order_id:u64)->Result<DeleteResponse,TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.delete_order(order_id)).await?
+    }
+
+    pub async fn modify_order_for_market(&self,trading_pair:TradingPair,order:ModifyOrderRequest)->Result<ModifyOrderResponse,TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.modify_order(order)).await?
+    }
+
+    //the owner is checked by the same command that cancels, so the order cannot change hands in between
+    pub async fn delete_user_order_for_market(&self,trading_pair:TradingPair,user_id:u64,order_id:u64)->Result<DeleteResponse,TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.delete_user_order(user_id, order_id)).await?
+    }
+
+    pub async fn modify_user_order_for_market(&self,trading_pair:TradingPair,user_id:u64,order:ModifyOrderRequest)->Result<ModifyOrderResponse,TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.modify_user_order(user_id, order)).await?
+    }
+
+    pub async fn get_order_by_id_for_market(&self,trading_pair:TradingPair,order_id:u64)->Result<OpenOrder,TradingEngineError>{
+        Ok(self.on_market(&trading_pair, move|market|market.orderbook.get_order(order_id)).await??)
+    }
+
+    pub async fn get_order_by_client_order_id_for_market(&self,trading_pair:TradingPair,user_id:u64,client_order_id:&str)->Result<OpenOrder,TradingEngineError>{
+        let client_order_id=client_order_id.to_string();
+        Ok(self.on_market(&trading_pair, move|market|market.orderbook.get_order_by_client_order_id(user_id, &client_order_id)).await??)
+    }
+
+    pub async fn delete_order_by_client_order_id_for_market(&self,trading_pair:TradingPair,user_id:u64,client_order_id:&str)->Result<DeleteResponse,TradingEngineError>{
+        let client_order_id=client_order_id.to_string();
+        self.on_market(&trading_pair, move|market|market.delete_order_by_client_order_id(user_id, &client_order_id)).await?
+    }
+
+    pub async fn get_mid_price_for_market(&self,trading_pair:TradingPair)->Result<Option<Decimal>,TradingEngineError>{
+        self.on_market(&trading_pair, |market|market.orderbook.mid_price()).await
+    }
+
+    pub async fn get_recent_trades_for_market(&self,trading_pair:TradingPair,limit:usize)->Result<Vec<PublicTrade>,TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.orderbook.get_recent_trades(limit)).await
+    }
+
+    pub async fn get_trade_history_for_market(&self,trading_pair:TradingPair,query:TradeHistoryQuery)->Result<TradeHistoryResponse<PublicTrade>,TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.orderbook.get_trade_history(query)).await
+    }
+
+    pub async fn get_user_trades_for_market(&self,trading_pair:TradingPair,user_id:u64,query:TradeHistoryQuery)->Result<TradeHistoryResponse<UserTrade>,TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.orderbook.get_user_trades(user_id, query)).await
+    }
+
+    pub async fn get_open_orders_for_market(&self,trading_pair:TradingPair,user_id:u64)->Result<Vec<OpenOrder>,TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.orderbook.get_open_orders(user_id)).await
+    }
+
+    pub async fn get_order_history_for_market(&self,trading_pair:TradingPair,user_id:u64,limit:usize)->Result<Vec<OpenOrder>,TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.orderbook.get_order_history(user_id, limit)).await
+    }
+
+    pub async fn cancel_all_for_market(&self,trading_pair:TradingPair,filter:CancelFilter)->Result<Vec<DeleteResponse>,TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.cancel_all(filter)).await?
+    }
+
+    //markets not taking cancels are skipped instead of failing the sweep, the others are all cancelled at the same cut
+    pub async fn cancel_all(&self,filter:CancelFilter)->Vec<MarketCancellations>{
+        let cancellations=self.on_every_market_at_once(move|market|market.cancel_all(filter.clone())).await;
+        cancellations
+            .into_iter()
+            .filter_map(|(trading_pair,cancelled)|cancelled.ok().filter(|c|!c.is_empty()).map(|c|MarketCancellations::new(trading_pair, c)))
+            .collect()
+    }
+
+    //cancel-on-disconnect also sweeps halted markets so no stale order is left for when they reopen, every market
+    //is swept at the same cut so no order placed after it in one market is cancelled while an earlier one survives in another
+    pub async fn cancel_all_for_user(&self,user_id:u64)->Vec<MarketCancellations>{
+        let filter=CancelFilter::for_user(user_id);
+        let cancellations=self.on_every_market_at_once(move|market|market.orderbook.cancel_all(filter.clone())).await;
+        cancellations
+            .into_iter()
+            .filter(|(_,cancelled)|!cancelled.is_empty())
+            .map(|(trading_pair,cancelled)|MarketCancellations::new(trading_pair, cancelled))
+            .collect()
+    }
+
+    pub async fn apply_batch_for_market(&self,trading_pair:TradingPair,operations:Vec<BatchOperation>,all_or_nothing:bool)->Result<BatchResponse,TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.apply_batch(operations, all_or_nothing)).await?
+    }
+
+    pub async fn apply_user_batch_for_market(&self,trading_pair:TradingPair,user_id:u64,operations:Vec<BatchOperation>,all_or_nothing:bool)->Result<BatchResponse,TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.apply_user_batch(user_id, operations, all_or_nothing)).await?
+    }
+
+    pub async fn get_market_status(&self,trading_pair:&TradingPair)->Result<MarketStatus,TradingEngineError>{
+        self.on_market(trading_pair, |market|market.status).await
+    }
+
+    pub async fn set_market_status(&self,trading_pair:TradingPair,status:MarketStatus)->Result<MarketStatusChange,TradingEngineError>{
+        let (cancelled,uncross)=self.on_market(&trading_pair, move|market|market.set_status(status)).await??;
+        Ok(MarketStatusChange::new(trading_pair, status, cancelled, uncross))
+    }
+
+    pub async fn halt_market(&self,trading_pair:TradingPair)->Result<(),TradingEngineError>{
+        self.set_market_status(trading_pair, MarketStatus::Halted).await.map(|_|())
+    }
+
+    pub async fn resume_market(&self,trading_pair:TradingPair)->Result<(),TradingEngineError>{
+        self.set_market_status(trading_pair, MarketStatus::Open).await.map(|_|())
+    }
+
+    pub async fn is_market_halted(&self,trading_pair:&TradingPair)->bool{
+        self.get_market_status(trading_pair).await==Ok(MarketStatus::Halted)
+    }
+
+    pub async fn get_indicative_uncross_for_market(&self,trading_pair:&TradingPair)->Result<Option<Uncross>,TradingEngineError>{
+        self.on_market(trading_pair, |market|market.orderbook.indicative_uncross()).await
+    }
+
+    pub async fn set_price_band_for_market(&self,trading_pair:TradingPair,config:Option<PriceBandConfig>)->Result<(),TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.orderbook.set_price_band(config)).await
+    }
+
+    pub async fn set_circuit_breaker_for_market(&self,trading_pair:TradingPair,config:Option<CircuitBreakerConfig>)->Result<(),TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.set_circuit_breaker(config)).await
+    }
+
+    pub async fn set_fee_schedule_for_market(&self,trading_pair:TradingPair,fee_schedule:FeeSchedule)->Result<(),TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.orderbook.set_fee_schedule(fee_schedule)).await
+    }
+
+    pub async fn set_allocation_for_market(&self,trading_pair:TradingPair,allocation:Allocation)->Result<(),TradingEngineError>{
+        self.on_market(&trading_pair, move|market|market.orderbook.set_allocation(allocation)).await
     }
 }
- 
\ No newline at end of file
+
+//markets that stopped before answering are left out
+async fn collect_replies<R>(receivers:Vec<(TradingPair,oneshot::Receiver<R>)>)->Vec<(TradingPair,R)>{
+    let mut replies=Vec::with_capacity(receivers.len());
+    for (trading_pair,receiver) in receivers{
+        if let Ok(r)=reply(receiver).await{
+            replies.push((trading_pair,r));
+        }
+    }
+    replies
+}
\ No newline at end of file
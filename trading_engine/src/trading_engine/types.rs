@@ -1,14 +1,188 @@
-use std::collections::{BTreeMap};
+use std::{cmp::Reverse, collections::BTreeMap, sync::{Arc, Condvar, Mutex, RwLock}};
 
 use orderbook::{
-    Orderbook
+    CustomError,
+    DeleteResponse,
+    FixedPoint,
+    OpenOrder,
+    Order,
+    Orderbook,
+    Trade,
+    Uncross
 };
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
+use crate::trading_engine::snapshot::{SnapshotCell, SnapshotPublisher};
 
 
-#[derive(Serialize,Deserialize,Clone)]
+//routes every call to the thread matching its market, so markets never wait on each other
 pub struct TradingEngine{
-    pub orderbooks: BTreeMap<TradingPair,Orderbook> 
+    //only locked to look up or add a market, never while one is matching
+    pub(crate) shards: RwLock<BTreeMap<TradingPair,MarketShard>>,
+    //set in sequenced mode, new markets are then handed to the sequencer's matching thread
+    pub(crate) sequencer: Option<UnboundedSender<SequencerInput>>
+}
+
+//how the engine spreads its markets over threads, chosen when it is created
+#[derive(Debug,Default,Clone,Copy,PartialEq,Eq,Serialize,Deserialize)]
+pub enum EngineMode{
+    //every market matches on a thread of its own
+    #[default]
+    Sharded,
+    //one sequencer numbers every command and all markets match on one thread in that order
+    Sequenced
+}
+
+//everything one market matches with, owned by a single thread that applies its commands in arrival order
+#[derive(Serialize,Deserialize,Clone,Default)]
+pub struct Market{
+    pub orderbook: MarketBook,
+    pub status: MarketStatus,
+    pub circuit_breaker: Option<CircuitBreaker>,
+    //set when the market matches in periodic batch auctions instead of continuously
+    pub batch_auction: Option<BatchAuction>
+}
+
+pub(crate) type MarketCommand=Box<dyn FnOnce(&mut Market)+Send>;
+
+//where the engine sends a market's commands
+pub(crate) struct MarketShard{
+    pub(crate) route: Route,
+    //fixed at creation so the engine can tell batch markets apart without asking them
+    pub(crate) matching_mode: MatchingMode,
+    //the market's latest snapshot, read without going through its thread
+    pub(crate) snapshot: Arc<SnapshotCell>
+}
+
+pub(crate) enum Route{
+    //the market's own thread
+    Thread(UnboundedSender<MarketCommand>),
+    //the sequencer, which forwards to the matching thread holding every market
+    Sequencer{sender:UnboundedSender<SequencerInput>,trading_pair:TradingPair}
+}
+
+pub(crate) enum SequencerInput{
+    Create(TradingPair,Box<Market>,Box<SnapshotPublisher>),
+    Run(TradingPair,MarketCommand),
+    //one command per market, run back to back under a single sequence number so nothing comes in between
+    RunEvery(Vec<(TradingPair,MarketCommand)>)
+}
+
+//where the markets of a sweep wait for each other in sharded mode, handed out as one place per market
+pub(crate) struct Gate{
+    pub(crate) remaining: Mutex<usize>,
+    pub(crate) opened: Condvar
+}
+
+pub(crate) struct GatePlace{
+    pub(crate) gate: Arc<Gate>,
+    pub(crate) arrived: bool
+}
+
+//what the matching thread publishes in sequenced mode, numbered after the command that caused it
+#[derive(Debug,Clone,PartialEq,Serialize,Deserialize)]
+pub struct SequencedEvent{
+    pub sequence: u64,
+    pub trading_pair: TradingPair,
+    pub event: EngineEvent
+}
+
+#[derive(Debug,Clone,PartialEq,Serialize,Deserialize)]
+pub enum EngineEvent{
+    MarketCreated(MatchingMode),
+    Trade(Trade),
+    //best prices after a command moved either of them
+    TopOfBook{best_bid:Option<Decimal>,best_ask:Option<Decimal>},
+    StatusChanged(MarketStatus)
+}
+
+//a market as its matching thread last published it, never changed once published
+#[derive(Debug,Clone)]
+pub struct BookSnapshot{
+    pub staleness: Staleness,
+    pub mid_price: Option<Decimal>,
+    //consecutive snapshots share everything a command did not change
+    pub(crate) bids: imbl::OrdMap<Reverse<Decimal>,Order>,
+    pub(crate) asks: imbl::OrdMap<Decimal,Order>,
+    pub(crate) open_orders: imbl::HashMap<u64,OpenOrder>,
+    pub(crate) open_client_order_ids: imbl::HashMap<(u64,String),u64>,
+    pub(crate) closed_orders: imbl::HashMap<u64,OpenOrder>,
+    pub(crate) closed_client_order_ids: imbl::HashMap<(u64,String),u64>
+}
+
+//how far a snapshot may lag the market: every command that finished `max_staleness_ms` before it is read is in it
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Serialize,Deserialize)]
+pub struct Staleness{
+    //snapshots published by the market so far, the first is 1
+    pub version: u64,
+    pub taken_at: u64,
+    pub max_staleness_ms: u64
+}
+
+//how a market matches, chosen when it is created
+#[derive(Debug,Default,Clone,Copy,PartialEq,Eq,Serialize,Deserialize)]
+pub enum MatchingMode{
+    //every order matches as soon as it arrives
+    #[default]
+    Continuous,
+    //orders are collected for `interval_ms` and cleared together at a single price
+    BatchAuction{interval_ms:u64}
+}
+
+//how a market keeps its prices and quantities, chosen when it is created
+#[derive(Debug,Default,Clone,Copy,PartialEq,Eq,Serialize,Deserialize)]
+pub enum NumericMode{
+    //any price and quantity, matched in decimals
+    #[default]
+    Decimal,
+    //matched in whole ticks and lots, a price or quantity that is not a multiple of them is rejected
+    FixedPoint{tick_size:Decimal,lot_size:Decimal}
+}
+
+//a market's book in the representation its numeric mode chose, decimals in and out either way
+#[derive(Serialize,Deserialize,Clone)]
+pub enum MarketBook{
+    Decimal(Orderbook),
+    FixedPoint(Orderbook<FixedPoint>)
+}
+
+#[derive(Debug,Clone,Serialize,Deserialize)]
+pub struct BatchAuction{
+    pub interval_ms: u64,
+    //when the collected orders are cleared next, 0 until the first clearing is due
+    pub next_clear_at: u64
+}
+
+#[derive(Debug,Clone,PartialEq,Serialize,Deserialize)]
+pub struct CircuitBreakerConfig{
+    //largest move of the last trade against any trade within the window before the market is stopped
+    pub max_move_bps: u32,
+    pub window_ms: u64,
+    //status the market is moved to when the breaker trips
+    #[serde(default="CircuitBreakerConfig::default_trip_status")]
+    pub trip_status: MarketStatus
+}
+
+#[derive(Debug,Clone,Serialize,Deserialize)]
+pub struct CircuitBreaker{
+    pub config: CircuitBreakerConfig,
+    //trades up to this id came before the breaker was set or last tripped and no longer count
+    pub last_trade_id: u64
+}
+
+#[derive(Debug,Default,Clone,Copy,PartialEq,Eq,Serialize,Deserialize)]
+pub enum MarketStatus{
+    //call auction, limit orders are collected without matching until the market opens
+    PreOpen,
+    #[default]
+    Open,
+    //users can pull their orders but not place or amend any
+    CancelOnly,
+    //the book is frozen, not even cancels go through
+    Halted,
+    //every order was cancelled and the book is kept read-only for its history, this is final
+    Delisted
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash,Serialize,Deserialize,PartialOrd, Ord)]
@@ -20,7 +194,51 @@ pub struct TradingPair{
 #[derive(Debug,PartialEq,Serialize,Deserialize)]
 pub enum TradingEngineError{
     TradingPairDoesNotExist,
-    TradingPairAlreadyExists
+    TradingPairAlreadyExists,
+    MarketNotOpen,
+    MarketCancelOnly,
+    MarketHalted,
+    MarketDelisted,
+    InvalidBatchInterval,
+    //a fixed point market needs a tick and a lot size above zero
+    InvalidTickOrLotSize,
+    //the thread owning the market stopped, only after it panicked
+    MarketUnavailable,
+    //the rest are the book turning a command down, one variant per `CustomError` so every layer shares one flat set of names
+    OrderDoesNotExist,
+    ModifyQuantityCannotBeLesserThanFilledQuantity,
+    LimitOrderDoesNotExist,
+    OrderAlreadyMatched,
+    OrderAlreadyClosed,
+    BatchTooLarge,
+    PriceBandExceeded,
+    ProtectionPriceExceeded,
+    QuoteQuantityOnlyForBuys,
+    InvalidQuantity,
+    InvalidPrice,
+    AuctionInProgress
+}
+
+#[derive(PartialEq,Debug,Serialize,Deserialize)]
+pub struct MarketCancellations{
+    pub trading_pair: TradingPair,
+    pub cancelled: Vec<DeleteResponse>
+}
+
+#[derive(PartialEq,Debug,Serialize,Deserialize)]
+pub struct MarketStatusChange{
+    pub trading_pair: TradingPair,
+    pub status: MarketStatus,
+    //orders cancelled by a delisting
+    pub cancelled: Vec<DeleteResponse>,
+    //how the auction cleared when the market left pre-open for open
+    pub uncross: Option<Uncross>
+}
+
+#[derive(PartialEq,Debug,Serialize,Deserialize)]
+pub struct BatchClearing{
+    pub trading_pair: TradingPair,
+    pub uncross: Uncross
 }
 
 #[derive(PartialEq,Debug,Serialize,Deserialize)]
@@ -34,6 +252,92 @@ impl Markets{
     }
 }
 
+impl MarketStatus{
+    pub fn accepts_orders(&self)->bool{
+        *self==MarketStatus::Open
+    }
+
+    //limit orders and amendments are also collected during the auction
+    pub fn accepts_limit_orders(&self)->bool{
+        matches!(self,MarketStatus::PreOpen|MarketStatus::Open)
+    }
+
+    pub fn accepts_cancels(&self)->bool{
+        matches!(self,MarketStatus::PreOpen|MarketStatus::Open|MarketStatus::CancelOnly)
+    }
+
+    //why an order was turned away, only meaningful when the status does not accept it
+    fn error(&self)->TradingEngineError{
+        match self{
+            MarketStatus::PreOpen|MarketStatus::Open=>TradingEngineError::MarketNotOpen,
+            MarketStatus::CancelOnly=>TradingEngineError::MarketCancelOnly,
+            MarketStatus::Halted=>TradingEngineError::MarketHalted,
+            MarketStatus::Delisted=>TradingEngineError::MarketDelisted
+        }
+    }
+
+    pub(crate) fn check_orders(&self)->Result<(),TradingEngineError>{
+        if self.accepts_orders(){Ok(())}else{Err(self.error())}
+    }
+
+    pub(crate) fn check_limit_orders(&self)->Result<(),TradingEngineError>{
+        if self.accepts_limit_orders(){Ok(())}else{Err(self.error())}
+    }
+
+    pub(crate) fn check_cancels(&self)->Result<(),TradingEngineError>{
+        if self.accepts_cancels(){Ok(())}else{Err(self.error())}
+    }
+}
+
+impl From<CustomError> for TradingEngineError{
+    fn from(error:CustomError)->Self{
+        match error{
+            CustomError::OrderDoesNotExist=>TradingEngineError::OrderDoesNotExist,
+            CustomError::ModifyQuantityCannotBeLesserThanFilledQuantity=>TradingEngineError::ModifyQuantityCannotBeLesserThanFilledQuantity,
+            CustomError::LimitOrderDoesNotExist=>TradingEngineError::LimitOrderDoesNotExist,
+            CustomError::OrderAlreadyMatched=>TradingEngineError::OrderAlreadyMatched,
+            CustomError::OrderAlreadyClosed=>TradingEngineError::OrderAlreadyClosed,
+            CustomError::BatchTooLarge=>TradingEngineError::BatchTooLarge,
+            CustomError::PriceBandExceeded=>TradingEngineError::PriceBandExceeded,
+            CustomError::ProtectionPriceExceeded=>TradingEngineError::ProtectionPriceExceeded,
+            CustomError::QuoteQuantityOnlyForBuys=>TradingEngineError::QuoteQuantityOnlyForBuys,
+            CustomError::InvalidQuantity=>TradingEngineError::InvalidQuantity,
+            CustomError::InvalidPrice=>TradingEngineError::InvalidPrice,
+            CustomError::AuctionInProgress=>TradingEngineError::AuctionInProgress
+        }
+    }
+}
+
+impl CircuitBreakerConfig{
+    //a tripped market collects orders in a call auction and reopens with an uncross rather than straight into continuous matching
+    pub(crate) fn default_trip_status()->MarketStatus{
+        MarketStatus::PreOpen
+    }
+
+    //whether `price` moved further away from `from` than the breaker allows
+    pub(crate) fn is_exceeded_by(&self,from:Decimal,price:Decimal)->bool{
+        from!=Decimal::ZERO && (price-from).abs()*Decimal::from(10_000)>from*Decimal::from(self.max_move_bps)
+    }
+}
+
+impl MarketStatusChange{
+    pub fn new(trading_pair:TradingPair,status:MarketStatus,cancelled:Vec<DeleteResponse>,uncross:Option<Uncross>)->MarketStatusChange{
+        MarketStatusChange { trading_pair, status, cancelled, uncross }
+    }
+}
+
+impl BatchClearing{
+    pub fn new(trading_pair:TradingPair,uncross:Uncross)->BatchClearing{
+        BatchClearing { trading_pair, uncross }
+    }
+}
+
+impl MarketCancellations{
+    pub fn new(trading_pair:TradingPair,cancelled:Vec<DeleteResponse>)->MarketCancellations{
+        MarketCancellations { trading_pair, cancelled }
+    }
+}
+
 impl TradingPair{
     pub fn new(base:String,quote:String)->TradingPair{
         TradingPair { base, quote }